@@ -0,0 +1,518 @@
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{Entry, EnumExtensibility, ObjCMethodDecl, ObjCPropertyDecl, ParmDecl};
+use crate::error::Error;
+use crate::typ::{Nullability, Typ};
+
+/// A value of an API notes file merged into a declaration.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct ApiNote {
+    /// The key of the value in the API notes file, e.g. `SwiftName`
+    pub key: String,
+    pub value: String,
+    /// The API notes file
+    pub file: PathBuf,
+}
+
+/// An API notes file, e.g. `Foo.apinotes` next to the headers of the
+/// framework `Foo`, as described in clang's `APINotes.rst`. Unsupported
+/// keys, e.g. `SwiftVersions`, are ignored.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct ApiNotes {
+    #[serde(skip)]
+    pub path: PathBuf,
+    /// The name of the module
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub classes: Vec<ContainerNotes>,
+    #[serde(default)]
+    pub protocols: Vec<ContainerNotes>,
+    #[serde(default)]
+    pub functions: Vec<FunctionNotes>,
+    #[serde(default)]
+    pub globals: Vec<VariableNotes>,
+    #[serde(default)]
+    pub enumerators: Vec<NamedNotes>,
+    #[serde(default)]
+    pub tags: Vec<TagNotes>,
+    #[serde(default)]
+    pub typedefs: Vec<NamedNotes>,
+}
+
+/// The keys common to all the declarations.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct CommonNotes {
+    pub swift_name: Option<String>,
+    /// `available`, `nonswift` for unavailable in Swift only, or `none`
+    pub availability: Option<String>,
+    pub availability_msg: Option<String>,
+    pub swift_private: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct NamedNotes {
+    pub name: String,
+    #[serde(flatten)]
+    pub common: CommonNotes,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerNotes {
+    pub name: String,
+    #[serde(flatten)]
+    pub common: CommonNotes,
+    #[serde(default)]
+    pub methods: Vec<MethodNotes>,
+    #[serde(default)]
+    pub properties: Vec<PropertyNotes>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodKind {
+    Class,
+    Instance,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct MethodNotes {
+    pub selector: String,
+    pub method_kind: MethodKind,
+    #[serde(flatten)]
+    pub common: CommonNotes,
+    /// The nullabilities of the parameters, in order
+    #[serde(default)]
+    pub nullability: Vec<NullabilityNote>,
+    pub nullability_of_ret: Option<NullabilityNote>,
+    pub result_type: Option<String>,
+    #[serde(default)]
+    pub parameters: Vec<ParameterNotes>,
+    pub designated_init: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct PropertyNotes {
+    pub name: String,
+    #[serde(flatten)]
+    pub common: CommonNotes,
+    pub nullability: Option<NullabilityNote>,
+    #[serde(rename = "Type")]
+    pub ty: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ParameterNotes {
+    pub position: usize,
+    pub nullability: Option<NullabilityNote>,
+    pub no_escape: Option<bool>,
+    #[serde(rename = "Type")]
+    pub ty: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct FunctionNotes {
+    pub name: String,
+    #[serde(flatten)]
+    pub common: CommonNotes,
+    /// The nullabilities of the parameters, in order
+    #[serde(default)]
+    pub nullability: Vec<NullabilityNote>,
+    pub nullability_of_ret: Option<NullabilityNote>,
+    pub result_type: Option<String>,
+    #[serde(default)]
+    pub parameters: Vec<ParameterNotes>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct VariableNotes {
+    pub name: String,
+    #[serde(flatten)]
+    pub common: CommonNotes,
+    pub nullability: Option<NullabilityNote>,
+    #[serde(rename = "Type")]
+    pub ty: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct TagNotes {
+    pub name: String,
+    #[serde(flatten)]
+    pub common: CommonNotes,
+    /// `open`, `closed` or `none`
+    pub enum_extensibility: Option<String>,
+    pub flag_enum: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullabilityNote {
+    #[serde(rename = "N", alias = "Nonnull")]
+    NonNull,
+    #[serde(rename = "O", alias = "Optional")]
+    Nullable,
+    #[serde(rename = "U", alias = "Unspecified")]
+    Unspecified,
+    /// Not a pointer
+    #[serde(rename = "S", alias = "Scalar")]
+    Scalar,
+}
+
+impl NullabilityNote {
+    pub fn nullability(&self) -> Option<Nullability> {
+        match self {
+            Self::NonNull => Some(Nullability::NonNull),
+            Self::Nullable => Some(Nullability::Nullable),
+            Self::Unspecified => Some(Nullability::Unspecified),
+            Self::Scalar => None,
+        }
+    }
+}
+
+impl ApiNotes {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let file = std::fs::File::open(path).map_err(|e| Error::Io { source: e })?;
+        let mut api_notes: Self =
+            serde_yaml::from_reader(std::io::BufReader::new(file)).map_err(|e| {
+                Error::Serialization {
+                    source: Box::new(e),
+                }
+            })?;
+        api_notes.path = path.to_path_buf();
+        Ok(api_notes)
+    }
+
+    /// Merges the notes into the matching `entries`, recording each value
+    /// with this file in the `api_notes` of the declaration. Type overrides
+    /// are only recorded, as the types are not parsed.
+    pub fn apply(&self, entries: &mut [Entry]) {
+        entries.iter_mut().for_each(|entry| self.apply_entry(entry));
+    }
+
+    fn apply_entry(&self, entry: &mut Entry) {
+        match entry {
+            Entry::FunctionDecl {
+                name,
+                arguments,
+                result_type,
+                availability,
+                api_notes,
+                ..
+            } => {
+                for notes in self.functions.iter().filter(|notes| &notes.name == name) {
+                    let mut merger = self.merger(api_notes);
+                    merger.common(&notes.common, Some(&mut *availability));
+                    if let Some(nullability) = &notes.nullability_of_ret {
+                        merger.nullability("NullabilityOfRet", nullability, result_type);
+                    }
+                    if let Some(ty) = &notes.result_type {
+                        merger.record("ResultType", ty);
+                    }
+                    self.apply_parameters(&notes.nullability, &notes.parameters, arguments);
+                }
+            }
+            Entry::VarDecl {
+                name,
+                ty,
+                availability,
+                api_notes,
+                ..
+            } => {
+                for notes in self.globals.iter().filter(|notes| &notes.name == name) {
+                    let mut merger = self.merger(api_notes);
+                    merger.common(&notes.common, Some(&mut *availability));
+                    if let Some(nullability) = &notes.nullability {
+                        merger.nullability("Nullability", nullability, ty);
+                    }
+                    if let Some(override_ty) = &notes.ty {
+                        merger.record("Type", override_ty);
+                    }
+                }
+            }
+            Entry::TypedefDecl {
+                name,
+                availability,
+                api_notes,
+                ..
+            } => {
+                for notes in self.typedefs.iter().filter(|notes| &notes.name == name) {
+                    self.merger(api_notes)
+                        .common(&notes.common, Some(&mut *availability));
+                }
+            }
+            Entry::EnumDecl {
+                name,
+                decls,
+                extensibility,
+                is_flag_enum,
+                availability,
+                api_notes,
+                ..
+            } => {
+                for notes in self.tags.iter().filter(|notes| &notes.name == name) {
+                    let mut merger = self.merger(api_notes);
+                    merger.common(&notes.common, Some(&mut *availability));
+                    if let Some(value) = &notes.enum_extensibility {
+                        merger.record("EnumExtensibility", value);
+                        *extensibility = match value.as_str() {
+                            "open" => Some(EnumExtensibility::Open),
+                            "closed" => Some(EnumExtensibility::Closed),
+                            _ => None,
+                        };
+                    }
+                    if let Some(flag_enum) = notes.flag_enum {
+                        merger.record("FlagEnum", flag_enum);
+                        *is_flag_enum = flag_enum;
+                    }
+                }
+                for decl in decls.iter_mut() {
+                    for notes in self
+                        .enumerators
+                        .iter()
+                        .filter(|notes| notes.name == decl.name)
+                    {
+                        self.merger(&mut decl.api_notes).common(&notes.common, None);
+                    }
+                }
+            }
+            Entry::StructDecl {
+                name: Some(name),
+                availability,
+                api_notes,
+                ..
+            }
+            | Entry::UnionDecl {
+                name: Some(name),
+                availability,
+                api_notes,
+                ..
+            } => {
+                for notes in self.tags.iter().filter(|notes| &notes.name == name) {
+                    self.merger(api_notes)
+                        .common(&notes.common, Some(&mut *availability));
+                }
+            }
+            Entry::ObjCInterfaceDecl {
+                name,
+                instance_methods,
+                class_methods,
+                properties,
+                availability,
+                api_notes,
+                ..
+            } => {
+                for notes in self.classes.iter().filter(|notes| &notes.name == name) {
+                    self.merger(api_notes)
+                        .common(&notes.common, Some(&mut *availability));
+                    self.apply_members(notes, instance_methods, class_methods, properties);
+                }
+            }
+            // the notes of a class are also about the members of its
+            // categories
+            Entry::ObjCCategoryDecl {
+                class_name,
+                instance_methods,
+                class_methods,
+                properties,
+                ..
+            } => {
+                for notes in self
+                    .classes
+                    .iter()
+                    .filter(|notes| &notes.name == class_name)
+                {
+                    self.apply_members(notes, instance_methods, class_methods, properties);
+                }
+            }
+            Entry::ObjCProtocolDecl {
+                name,
+                instance_methods,
+                class_methods,
+                properties,
+                availability,
+                api_notes,
+                ..
+            } => {
+                for notes in self.protocols.iter().filter(|notes| &notes.name == name) {
+                    self.merger(api_notes)
+                        .common(&notes.common, Some(&mut *availability));
+                    self.apply_members(notes, instance_methods, class_methods, properties);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_members(
+        &self,
+        notes: &ContainerNotes,
+        instance_methods: &mut [ObjCMethodDecl],
+        class_methods: &mut [ObjCMethodDecl],
+        properties: &mut [ObjCPropertyDecl],
+    ) {
+        for method_notes in &notes.methods {
+            let methods = match method_notes.method_kind {
+                MethodKind::Instance => &mut *instance_methods,
+                MethodKind::Class => &mut *class_methods,
+            };
+            methods
+                .iter_mut()
+                .filter(|method| method.name == method_notes.selector)
+                .for_each(|method| self.apply_method(method_notes, method));
+        }
+        for property_notes in &notes.properties {
+            for property in properties
+                .iter_mut()
+                .filter(|property| property.name == property_notes.name)
+            {
+                let mut merger = self.merger(&mut property.api_notes);
+                merger.common(&property_notes.common, Some(&mut property.availability));
+                if let Some(nullability) = &property_notes.nullability {
+                    merger.nullability("Nullability", nullability, &mut property.objc_type);
+                }
+                if let Some(ty) = &property_notes.ty {
+                    merger.record("Type", ty);
+                }
+            }
+        }
+    }
+
+    fn apply_method(&self, notes: &MethodNotes, method: &mut ObjCMethodDecl) {
+        if let Some(swift_name) = &notes.common.swift_name {
+            method.set_swift_name(swift_name);
+        }
+        let mut merger = self.merger(&mut method.api_notes);
+        merger.common(&notes.common, Some(&mut method.availability));
+        if let Some(nullability) = &notes.nullability_of_ret {
+            merger.nullability("NullabilityOfRet", nullability, &mut method.result_type);
+        }
+        if let Some(ty) = &notes.result_type {
+            merger.record("ResultType", ty);
+        }
+        if let Some(designated_init) = notes.designated_init {
+            merger.record("DesignatedInit", designated_init);
+            method.is_designated_initializer = designated_init;
+        }
+        self.apply_parameters(&notes.nullability, &notes.parameters, &mut method.arguments);
+    }
+
+    fn apply_parameters(
+        &self,
+        nullabilities: &[NullabilityNote],
+        parameters: &[ParameterNotes],
+        arguments: &mut [ParmDecl],
+    ) {
+        for (argument, nullability) in arguments.iter_mut().zip(nullabilities) {
+            self.merger(&mut argument.api_notes).nullability(
+                "Nullability",
+                nullability,
+                &mut argument.objc_type,
+            );
+            argument.nullability = nullability.nullability();
+        }
+        for notes in parameters {
+            let Some(argument) = arguments.get_mut(notes.position) else {
+                continue;
+            };
+            let mut merger = self.merger(&mut argument.api_notes);
+            if let Some(nullability) = &notes.nullability {
+                merger.nullability("Nullability", nullability, &mut argument.objc_type);
+                argument.nullability = nullability.nullability();
+            }
+            if let Some(no_escape) = notes.no_escape {
+                merger.record("NoEscape", no_escape);
+                argument.is_noescape = no_escape;
+            }
+            if let Some(ty) = &notes.ty {
+                merger.record("Type", ty);
+            }
+        }
+    }
+
+    fn merger<'a>(&'a self, api_notes: &'a mut Vec<ApiNote>) -> Merger<'a> {
+        Merger {
+            file: &self.path,
+            api_notes,
+        }
+    }
+}
+
+// Records the values merged into a declaration.
+struct Merger<'a> {
+    file: &'a Path,
+    api_notes: &'a mut Vec<ApiNote>,
+}
+
+impl Merger<'_> {
+    fn record(&mut self, key: &str, value: impl ToString) {
+        self.api_notes.push(ApiNote {
+            key: key.to_string(),
+            value: value.to_string(),
+            file: self.file.to_path_buf(),
+        });
+    }
+
+    fn common(&mut self, notes: &CommonNotes, availability: Option<&mut clang::Availability>) {
+        if let Some(swift_name) = &notes.swift_name {
+            self.record("SwiftName", swift_name);
+        }
+        if let Some(value) = &notes.availability {
+            self.record("Availability", value);
+            if let (Some(availability), "none") = (availability, value.as_str()) {
+                *availability = clang::Availability::Unavailable;
+            }
+        }
+        if let Some(message) = &notes.availability_msg {
+            self.record("AvailabilityMsg", message);
+        }
+        if let Some(swift_private) = notes.swift_private {
+            self.record("SwiftPrivate", swift_private);
+        }
+    }
+
+    fn nullability(&mut self, key: &str, nullability: &NullabilityNote, ty: &mut Typ) {
+        self.record(key, format!("{:?}", nullability));
+        if let Some(slot) = ty.nullability_mut() {
+            *slot = nullability.nullability();
+        }
+    }
+}
+
+/// The API notes files in `dir`, e.g. `Foo.apinotes` and
+/// `Foo_Private.apinotes` in the `Headers` directory of a framework, sorted
+/// by path.
+pub fn find_api_notes(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .map(|ext| ext == "apinotes")
+            .unwrap_or(false)
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Loads the API notes files in `dir`.
+pub fn load_api_notes(dir: &Path) -> Result<Vec<ApiNotes>, Error> {
+    find_api_notes(dir)
+        .map_err(|e| Error::Io { source: e })?
+        .iter()
+        .map(|path| ApiNotes::load(path))
+        .collect()
+}
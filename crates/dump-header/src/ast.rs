@@ -0,0 +1,241 @@
+use std::path::{Path, PathBuf};
+
+use clang::TranslationUnit;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::location::to_location;
+use crate::entity::Location;
+use crate::error::Error;
+
+/// An entity of the clang AST, as printed by the `ast` subcommand.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct AstNode {
+    /// The spelling of the entity
+    pub name: Option<String>,
+    /// The `clang::EntityKind`, e.g. `ObjCInterfaceDecl`
+    pub kind: String,
+    /// The display name of the type
+    pub ty: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usr: Option<String>,
+    /// With the extent of the entity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    /// The tokens of the attribute children, e.g. `NS_SWIFT_NAME(foo)`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<String>,
+    pub children: Vec<AstNode>,
+}
+
+/// Selects the nodes printed by the `ast` subcommand.
+#[derive(Debug, Clone, Default)]
+pub struct AstFilter {
+    /// Names of `clang::EntityKind`, e.g. `ObjCInterfaceDecl`, any if empty
+    pub kinds: Vec<String>,
+    /// Matched against the names
+    pub name: Option<Regex>,
+    /// The levels of children kept under a selected node, all if `None`
+    pub depth: Option<usize>,
+    /// Also select the entities of the files included by the main file
+    pub include_non_main_file: bool,
+    /// Make the paths of the locations relative to this directory
+    pub location_root: Option<PathBuf>,
+}
+
+impl AstNode {
+    /// Converts `entity` with `depth` levels of children, all if `None`.
+    pub fn from_entity(entity: &clang::Entity, depth: Option<usize>, root: Option<&Path>) -> Self {
+        let children = if depth == Some(0) {
+            vec![]
+        } else {
+            entity
+                .get_children()
+                .iter()
+                .map(|child| Self::from_entity(child, depth.map(|depth| depth - 1), root))
+                .collect()
+        };
+        AstNode {
+            name: entity.get_name(),
+            kind: kind_name(entity.get_kind()).to_string(),
+            ty: entity.get_type().map(|t| t.get_display_name()),
+            usr: entity.get_usr().map(|usr| usr.0),
+            location: entity
+                .get_location()
+                .and_then(|location| to_location(location, entity.get_range(), root)),
+            attributes: entity
+                .get_children()
+                .iter()
+                .filter(|child| child.is_attribute())
+                .filter_map(attribute_text)
+                .collect(),
+            children,
+        }
+    }
+
+    /// The tree as text, with two spaces of indentation per level.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        self.push_text(0, &mut text);
+        text
+    }
+
+    fn push_text(&self, depth: usize, text: &mut String) {
+        text.push_str(&format!(
+            "{}{:?} {}  {}\n",
+            "  ".repeat(depth),
+            self.name.as_deref().unwrap_or(""),
+            self.kind,
+            self.ty.as_deref().unwrap_or("")
+        ));
+        self.children
+            .iter()
+            .for_each(|child| child.push_text(depth + 1, text));
+    }
+}
+
+impl AstFilter {
+    fn is_match(&self, entity: &clang::Entity) -> bool {
+        let kind = kind_name(entity.get_kind());
+        (self.kinds.is_empty() || self.kinds.iter().any(|name| name == kind))
+            && self.name.as_ref().is_none_or(|name| {
+                entity
+                    .get_name()
+                    .is_some_and(|entity_name| name.is_match(&entity_name))
+            })
+    }
+
+    fn is_filtering(&self) -> bool {
+        !self.kinds.is_empty() || self.name.is_some()
+    }
+}
+
+/// Fails with `Error::InvalidArgument` unless `name` names a
+/// `clang::EntityKind`, e.g. `ObjCInterfaceDecl`.
+pub fn check_kind(name: &str) -> Result<(), Error> {
+    if KIND_NAMES.contains(&name) {
+        Ok(())
+    } else {
+        Err(Error::InvalidArgument(format!(
+            "unknown entity kind {}",
+            name
+        )))
+    }
+}
+
+/// The nodes of `tu` selected by `filter`: the top-level entities of the main
+/// file, or, with kind or name filters, the outermost matching entities
+/// under them, each with its children.
+pub fn select_nodes(tu: &TranslationUnit, filter: &AstFilter) -> Vec<AstNode> {
+    let mut nodes = vec![];
+    tu.get_entity()
+        .get_children()
+        .iter()
+        .filter(|entity| filter.include_non_main_file || entity.is_in_main_file())
+        .for_each(|entity| select(entity, filter, &mut nodes));
+    nodes
+}
+
+fn select(entity: &clang::Entity, filter: &AstFilter, nodes: &mut Vec<AstNode>) {
+    if !filter.is_filtering() || filter.is_match(entity) {
+        nodes.push(AstNode::from_entity(
+            entity,
+            filter.depth,
+            filter.location_root.as_deref(),
+        ));
+    } else {
+        entity
+            .get_children()
+            .iter()
+            .for_each(|child| select(child, filter, nodes));
+    }
+}
+
+// The tokens of `attribute` joined as they are usually written, `None` for an
+// implicit attribute without tokens.
+fn attribute_text(attribute: &clang::Entity) -> Option<String> {
+    let tokens: Vec<String> = attribute
+        .get_range()?
+        .tokenize()
+        .iter()
+        .map(|token| token.get_spelling())
+        .collect();
+    let mut text = String::new();
+    let mut previous: Option<&str> = None;
+    for token in &tokens {
+        let glued = matches!(token.as_str(), "(" | ")" | "," | ".")
+            || matches!(previous, Some("(") | Some(".") | None);
+        if !glued {
+            text.push(' ');
+        }
+        text.push_str(token);
+        previous = Some(token);
+    }
+    (!text.is_empty()).then_some(text)
+}
+
+// Defines `kind_name` and `KIND_NAMES` from the variants of `clang::EntityKind`,
+// all of them, or `kind_name` does not compile.
+macro_rules! entity_kinds {
+    ($($kind:ident)*) => {
+        fn kind_name(kind: clang::EntityKind) -> &'static str {
+            match kind {
+                $(clang::EntityKind::$kind => stringify!($kind),)*
+            }
+        }
+
+        const KIND_NAMES: &[&str] = &[$(stringify!($kind)),*];
+    };
+}
+
+entity_kinds! {
+    UnexposedDecl StructDecl UnionDecl ClassDecl EnumDecl FieldDecl EnumConstantDecl FunctionDecl
+    VarDecl ParmDecl ObjCInterfaceDecl ObjCCategoryDecl ObjCProtocolDecl ObjCPropertyDecl
+    ObjCIvarDecl ObjCInstanceMethodDecl ObjCClassMethodDecl ObjCImplementationDecl
+    ObjCCategoryImplDecl TypedefDecl Method Namespace LinkageSpec Constructor Destructor
+    ConversionFunction TemplateTypeParameter NonTypeTemplateParameter TemplateTemplateParameter
+    FunctionTemplate ClassTemplate ClassTemplatePartialSpecialization NamespaceAlias UsingDirective
+    UsingDeclaration TypeAliasDecl ObjCSynthesizeDecl ObjCDynamicDecl AccessSpecifier
+    ObjCSuperClassRef ObjCProtocolRef ObjCClassRef TypeRef BaseSpecifier TemplateRef NamespaceRef
+    MemberRef LabelRef OverloadedDeclRef VariableRef InvalidFile InvalidDecl NotImplemented
+    InvalidCode UnexposedExpr DeclRefExpr MemberRefExpr CallExpr ObjCMessageExpr BlockExpr
+    IntegerLiteral FloatingLiteral ImaginaryLiteral StringLiteral CharacterLiteral ParenExpr
+    UnaryOperator ArraySubscriptExpr BinaryOperator CompoundAssignOperator ConditionalOperator
+    CStyleCastExpr CompoundLiteralExpr InitListExpr AddrLabelExpr StmtExpr GenericSelectionExpr
+    GNUNullExpr StaticCastExpr DynamicCastExpr ReinterpretCastExpr ConstCastExpr FunctionalCastExpr
+    TypeidExpr BoolLiteralExpr NullPtrLiteralExpr ThisExpr ThrowExpr NewExpr DeleteExpr UnaryExpr
+    ObjCStringLiteral ObjCEncodeExpr ObjCSelectorExpr ObjCProtocolExpr ObjCBridgedCastExpr
+    PackExpansionExpr SizeOfPackExpr LambdaExpr ObjCBoolLiteralExpr ObjCSelfExpr OmpArraySectionExpr
+    ObjCAvailabilityCheckExpr FixedPointLiteral UnexposedStmt LabelStmt CompoundStmt CaseStmt
+    DefaultStmt IfStmt SwitchStmt WhileStmt DoStmt ForStmt GotoStmt IndirectGotoStmt ContinueStmt
+    BreakStmt ReturnStmt AsmStmt ObjCAtTryStmt ObjCAtCatchStmt ObjCAtFinallyStmt ObjCAtThrowStmt
+    ObjCAtSynchronizedStmt ObjCAutoreleasePoolStmt ObjCForCollectionStmt CatchStmt TryStmt
+    ForRangeStmt SehTryStmt SehExceptStmt SehFinallyStmt SehLeaveStmt MsAsmStmt NullStmt DeclStmt
+    OmpParallelDirective OmpSimdDirective OmpForDirective OmpSectionsDirective OmpSectionDirective
+    OmpSingleDirective OmpParallelForDirective OmpParallelSectionsDirective OmpTaskDirective
+    OmpMasterDirective OmpCriticalDirective OmpTaskyieldDirective OmpBarrierDirective
+    OmpTaskwaitDirective OmpFlushDirective OmpOrderedDirective OmpAtomicDirective
+    OmpForSimdDirective OmpParallelForSimdDirective OmpTargetDirective OmpTeamsDirective
+    OmpTaskgroupDirective OmpCancellationPointDirective OmpCancelDirective OmpTargetDataDirective
+    OmpTaskLoopDirective OmpTaskLoopSimdDirective OmpDistributeDirective OmpTargetEnterDataDirective
+    OmpTargetExitDataDirective OmpTargetParallelDirective OmpTargetParallelForDirective
+    OmpTargetUpdateDirective OmpDistributeParallelForDirective OmpDistributeParallelForSimdDirective
+    OmpDistributeSimdDirective OmpTargetParallelForSimdDirective OmpTargetSimdDirective
+    OmpTeamsDistributeDirective OmpTeamsDistributeSimdDirective
+    OmpTeamsDistributeParallelForSimdDirective OmpTeamsDistributeParallelForDirective
+    OmpTargetTeamsDirective OmpTargetTeamsDistributeDirective
+    OmpTargetTeamsDistributeParallelForDirective OmpTargetTeamsDistributeParallelForSimdDirective
+    OmpTargetTeamsDistributeSimdDirective BitCastExpr OmpMasterTaskLoopDirective
+    OmpParallelMasterTaskLoopDirective OmpMasterTaskLoopSimdDirective
+    OmpParallelMasterTaskLoopSimdDirective OmpParallelMasterDirective TranslationUnit UnexposedAttr
+    IbActionAttr IbOutletAttr IbOutletCollectionAttr FinalAttr OverrideAttr AnnotateAttr
+    AsmLabelAttr PackedAttr PureAttr ConstAttr NoDuplicateAttr CudaConstantAttr CudaDeviceAttr
+    CudaGlobalAttr CudaHostAttr CudaSharedAttr VisibilityAttr DllExport DllImport NSReturnsRetained
+    NSReturnsNotRetained NSReturnsAutoreleased NSConsumesSelf NSConsumed ObjCException ObjCNSObject
+    ObjCIndependentClass ObjCPreciseLifetime ObjCReturnsInnerPointer ObjCRequiresSuper ObjCRootClass
+    ObjCSubclassingRestricted ObjCExplicitProtocolImpl ObjCDesignatedInitializer ObjCRuntimeVisible
+    ObjCBoxable FlagEnum ConvergentAttr WarnUnusedAttr WarnUnusedResultAttr AlignedAttr
+    PreprocessingDirective MacroDefinition MacroExpansion InclusionDirective ModuleImportDecl
+    TypeAliasTemplateDecl StaticAssert FriendDecl OverloadCandidate
+}
@@ -1,13 +1,28 @@
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 use anyhow::Result;
 use clang::Clang;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use dump_header::{
-    cli::{Lang, Std},
-    framework::FrameworkUnit,
-    headerfiletree::HeaderFile,
+    ast::{self, select_nodes, AstFilter},
+    cache::{CacheStats, ParseCache},
+    cli::{AvailabilityFilter, Format, Lang, SchemaKind, Std},
+    codegen,
+    deps::IncludeGraph,
+    diagnostic::{ensure_no_errors, get_diagnostics, Diagnostic},
+    diff::{self, ItemKind},
+    driver::{find_header_files, parallel_map, worker_count},
+    entity::ConvertOptions,
+    format::{self, Document},
+    framework::{find_frameworks, Framework, FrameworkUnit},
+    headerfiletree::{HeaderFile, HeaderFileTree},
+    interface::InterfaceIndex,
     parser::{self, ParserConfig},
+    query::{self, Query},
+    schema,
 };
 
 // dump a single header file
@@ -36,6 +51,73 @@ struct Cli {
     #[arg(long)]
     target: Option<String>,
 
+    /// Add a directory to the framework search paths
+    #[arg(short = 'F', long = "framework-path")]
+    framework_paths: Vec<PathBuf>,
+
+    /// Attach documentation comments to the entries
+    #[arg(long)]
+    comments: bool,
+
+    /// Attach source locations to the entries
+    #[arg(long)]
+    locations: bool,
+
+    /// Make the paths of source locations relative to this directory
+    #[arg(long, requires = "locations")]
+    location_root: Option<PathBuf>,
+
+    /// Store named records once in a top-level type table of `dump` and
+    /// `framework` instead of at every use
+    #[arg(long)]
+    type_table: bool,
+
+    /// Merge the API notes files (`*.apinotes`) next to the header files
+    /// into `dump`, `framework` and `gen-rust`
+    #[arg(long)]
+    api_notes: bool,
+
+    /// Output format of `dump`, `framework` and `ast`, input format of `diff`
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+
+    /// Fail instead of writing a partial dump when clang reports errors
+    #[arg(long)]
+    strict: bool,
+
+    /// Store the dumps of header files in this directory and reuse them
+    /// while the header files and their includes are unchanged
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Ignore --cache-dir
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Replace the dumps stored in --cache-dir instead of reusing them
+    #[arg(long, requires = "cache_dir")]
+    rebuild_cache: bool,
+
+    /// Precompile this header, included first by the header files sharing
+    /// it, once for all of them
+    #[arg(long)]
+    prefix_header: Option<PathBuf>,
+
+    /// Use this header precompiled from --prefix-header, as passed to the
+    /// workers of --jobs
+    #[arg(long, hide = true, conflicts_with = "prefix_header")]
+    prefix_pch: Option<PathBuf>,
+
+    /// Report the cache statistics to the parent process of --jobs instead
+    /// of printing them
+    #[arg(long, hide = true)]
+    worker: bool,
+
+    /// Dump the frameworks of `framework --all` and the header files of
+    /// `dump` in this many worker processes, 0 for one per CPU
+    #[arg(short, long, default_value_t = 1)]
+    jobs: usize,
+
     /// Extra arguments to pass to clang
     #[arg(long)]
     clang_args: Option<Vec<String>>,
@@ -43,83 +125,700 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// dump a header file
+    /// dump a header file, or a list of header files
     Dump {
-        /// JSON file for output
+        /// File for output
         #[arg(short, long)]
         output: Option<PathBuf>,
-        /// The header file to dump
-        file: PathBuf,
+        /// The header files to dump, and directories to dump the header
+        /// files (`*.h`) of. A single header file is dumped as itself,
+        /// anything else as a list sorted by path.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
     },
-    /// does framework stuff
+    /// dump a framework found in the framework search paths
     Framework {
-        #[arg(short, long)]
+        /// Dump every framework found in the framework search paths
+        #[arg(short, long, conflicts_with = "name")]
         all: bool,
+        /// File for output, or a directory for one file per framework with --all
         #[arg(short, long)]
         output: Option<PathBuf>,
-        /// The framework to dump
+        /// The framework to dump, e.g. Foundation
+        #[arg(required_unless_present = "all")]
         name: Option<String>,
     },
-    /// print the AST of a file
-    Ast { file: PathBuf },
+    /// print the include graph of a header file or a framework
+    Deps {
+        /// The framework to print the include graph of, instead of a header file
+        #[arg(long, conflicts_with = "file")]
+        framework: Option<String>,
+        /// Collapse the headers of a framework into a single node
+        #[arg(long)]
+        collapse: bool,
+        /// Print Graphviz DOT instead of JSON
+        #[arg(long)]
+        dot: bool,
+        /// File for output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// The header file
+        #[arg(required_unless_present = "framework")]
+        file: Option<PathBuf>,
+    },
+    /// print the JSON Schema of the output of a subcommand
+    Schema {
+        #[arg(value_enum, default_value_t = SchemaKind::HeaderFile)]
+        kind: SchemaKind,
+        /// File for output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// compare two dumps of a header file or a framework, read in --format
+    ///
+    /// Exits with status 2 if an API was removed, changed or made unavailable,
+    /// and with status 1 on errors, e.g. an unreadable dump.
+    Diff {
+        /// Print JSON instead of one line per change
+        #[arg(long)]
+        json: bool,
+        /// File for output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// The dump of the old version
+        old: PathBuf,
+        /// The dump of the new version
+        new: PathBuf,
+    },
+    /// search the declarations of dumps, read in --format, or of header files
+    Query {
+        /// Kinds of declarations, any by default
+        #[arg(long = "kind", value_enum)]
+        kinds: Vec<ItemKind>,
+        /// Regular expression matched against the names, e.g. `^NSURLSession -`
+        /// for the instance methods of NSURLSession
+        #[arg(long)]
+        name: Option<String>,
+        /// Regular expression matched against the paths of the header files
+        #[arg(long)]
+        header: Option<String>,
+        #[arg(long, value_enum)]
+        availability: Option<AvailabilityFilter>,
+        /// The platform of --availability, e.g. macos, any by default
+        #[arg(long, requires = "availability")]
+        platform: Option<String>,
+        /// Declarations whose signature names this type, e.g. CFStringRef
+        #[arg(long)]
+        mentions: Option<String>,
+        /// Print JSON instead of one signature per line
+        #[arg(long)]
+        json: bool,
+        /// File for output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Dumps, or header files (`*.h`) to parse
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// print the interface of an Objective-C class with the members it gets
+    /// from its superclasses, categories and protocols
+    Interface {
+        /// The class, e.g. NSMutableString
+        class: String,
+        /// Dump these frameworks, e.g. Foundation, and search them too
+        #[arg(long = "framework")]
+        frameworks: Vec<String>,
+        /// Print JSON instead of Objective-C
+        #[arg(long)]
+        json: bool,
+        /// File for output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Dumps, or header files (`*.h`) to parse
+        #[arg(required_unless_present = "frameworks")]
+        files: Vec<PathBuf>,
+    },
+    /// print the AST of a file, as text unless --json or --format is given
+    Ast {
+        /// Print JSON, as with --format json
+        #[arg(long)]
+        json: bool,
+        /// Print the outermost entities of these kinds, e.g. ObjCInterfaceDecl
+        #[arg(long = "kind")]
+        kinds: Vec<String>,
+        /// Print the outermost entities whose name matches this regular
+        /// expression
+        #[arg(long)]
+        name: Option<String>,
+        /// Levels of children printed under each entity, all by default
+        #[arg(long)]
+        depth: Option<usize>,
+        /// Also print the entities of the files included by FILE
+        #[arg(long)]
+        all_files: bool,
+        /// File for output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        file: PathBuf,
+    },
+    /// generate Rust bindings for a header file
+    GenRust {
+        /// Rust file for output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Read FILE as a dump in --format, JSON by default, instead of parsing it
+        #[arg(long)]
+        from_dump: bool,
+        /// The header file, or dump, to generate bindings for
+        file: PathBuf,
+    },
 }
 
-fn main() -> Result<()> {
+// Distinct from the status 1 of an `Err` returned by `main`.
+const EXIT_BREAKING_CHANGES: u8 = 2;
+
+// The stderr line of a worker with its cache statistics, as JSON, which the
+// parent adds to its own instead of replaying the line.
+const WORKER_CACHE_STATS: &str = "dump-header worker cache: ";
+
+fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
     clang_sys::load().unwrap();
     let clang = Clang::new().unwrap();
     let parser_config = ParserConfig {
         isysroot: cli.isysroot.clone(),
-        lang: cli.lang,
-        std: cli.std,
+        lang: cli.lang.clone(),
+        std: cli.std.clone(),
         target: cli.target.clone(),
+        framework_paths: cli.framework_paths.clone(),
+    };
+    let mut parser = parser::Parser::from(&clang, parser_config);
+    let convert_options = ConvertOptions {
+        comments: cli.comments,
+        locations: cli.locations,
+        location_root: cli.location_root.as_ref().map(absolute_path).transpose()?,
     };
-    let parser = parser::Parser::from(&clang, parser_config);
+
+    let mut cache = match &cli.cache_dir {
+        Some(dir) if !cli.no_cache => Some(ParseCache::new(dir, cli.rebuild_cache)?),
+        _ => None,
+    };
+    // without a cache, the precompiled header lasts for this run
+    let mut pch_dir = None;
+    if let Some(prefix_header) = &cli.prefix_header {
+        let prefix_header = absolute_path(prefix_header)?;
+        let pch = match &mut cache {
+            Some(cache) => cache.prefix_pch(&parser, &prefix_header)?,
+            None => {
+                let pch = pch_dir
+                    .insert(temp_dir::TempDir::new()?)
+                    .child("prefix.pch");
+                parser.precompile(&prefix_header, &pch)?;
+                pch
+            }
+        };
+        parser.set_prefix_pch(Some(pch));
+    }
+    if let Some(pch) = &cli.prefix_pch {
+        parser.set_prefix_pch(Some(absolute_path(pch)?));
+    }
+
+    let output_format = cli.format.unwrap_or(Format::Json);
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut worker_stats = CacheStats::default();
 
     match &cli.command {
-        Commands::Dump { output, file } => {
-            let pwd = std::env::current_dir()?;
-            let file = file
-                .is_absolute()
-                .then(|| file.clone())
-                .unwrap_or(pwd.join(file));
-            let tu = parser.parse(&file)?;
-            let header_file_entry = HeaderFile::from_path(&file, &tu);
-            if let Some(output) = output {
-                header_file_entry.save(output)?;
+        Commands::Dump { output, files } => {
+            let single = files.len() == 1 && !files[0].is_dir();
+            let paths = files
+                .iter()
+                .map(absolute_path)
+                .collect::<Result<Vec<_>>>()?;
+            let paths = find_header_files(&paths)?;
+            let header_files: Vec<HeaderFile> = if paths.len() > 1 && worker_count(cli.jobs) > 1 {
+                run_workers(
+                    &cli,
+                    parser.prefix_pch(),
+                    &mut worker_stats,
+                    &paths,
+                    |path| vec!["dump".into(), path.into()],
+                )?
             } else {
-                println!("{}", serde_json::to_string_pretty(&header_file_entry)?);
+                let mut header_files = vec![];
+                for path in &paths {
+                    let mut header_file_entry = dump_header_file(
+                        &parser,
+                        cache.as_mut(),
+                        path,
+                        &convert_options,
+                        cli.strict,
+                    )?;
+                    if cli.api_notes {
+                        header_file_entry.apply_api_notes()?;
+                    }
+                    if cli.type_table {
+                        header_file_entry.use_type_table();
+                    }
+                    header_files.push(header_file_entry);
+                }
+                header_files
+            };
+            if single {
+                write_document(&header_files[0], output_format, output.as_deref())?;
+            } else {
+                write_document(&header_files, output_format, output.as_deref())?;
             }
         }
-        #[allow(unused_variables)]
         Commands::Framework { all, output, name } => {
-            println!("{:?}", FrameworkUnit::with_parser("Foundation", &parser)?);
+            let names = if *all {
+                find_frameworks(&parser.config().framework_search_paths())?
+            } else {
+                name.iter().cloned().collect()
+            };
+            let mut frameworks: Vec<Framework> = vec![];
+            // the workers apply the API notes and the type table
+            let mut dumped = if names.len() > 1 && worker_count(cli.jobs) > 1 {
+                run_workers(
+                    &cli,
+                    parser.prefix_pch(),
+                    &mut worker_stats,
+                    &names,
+                    |name| vec!["framework".into(), name.into()],
+                )?
+            } else {
+                vec![]
+            }
+            .into_iter();
+            for name in &names {
+                let framework = match dumped.next() {
+                    Some(framework) => framework,
+                    None => {
+                        let mut framework = dump_framework(&cli, &parser, name, &convert_options)?;
+                        if cli.type_table {
+                            framework.use_type_table();
+                        }
+                        framework
+                    }
+                };
+                match output {
+                    Some(output) if *all => {
+                        std::fs::create_dir_all(output)?;
+                        let path = output.join(format!("{}.{}", name, output_format.extension()));
+                        write_document(&framework, output_format, Some(&path))?;
+                    }
+                    Some(output) => write_document(&framework, output_format, Some(output))?,
+                    None => frameworks.push(framework),
+                }
+            }
+            if output.is_none() {
+                if *all {
+                    write_document(&frameworks, output_format, None)?;
+                } else if let Some(framework) = frameworks.first() {
+                    write_document(framework, output_format, None)?;
+                }
+            }
         }
-        Commands::Ast { file } => {
+        Commands::GenRust {
+            output,
+            from_dump,
+            file,
+        } => {
+            let file = absolute_path(file)?;
+            let header_file_entry = if *from_dump {
+                format::read(std::fs::File::open(&file)?, output_format)?
+            } else {
+                let mut header_file_entry =
+                    dump_header_file(&parser, cache.as_mut(), &file, &convert_options, cli.strict)?;
+                if cli.api_notes {
+                    header_file_entry.apply_api_notes()?;
+                }
+                header_file_entry
+            };
+            let source = codegen::generate_rust(&header_file_entry);
+            if let Some(output) = output {
+                std::fs::write(output, source)?;
+            } else {
+                print!("{}", source);
+            }
+        }
+        Commands::Deps {
+            framework,
+            collapse,
+            dot,
+            output,
+            file,
+        } => {
+            let graph = if let Some(name) = framework {
+                let framework_unit = FrameworkUnit::with_parser(name, &parser)?;
+                report_diagnostics(&framework_unit.diagnostics(&convert_options), cli.strict)?;
+                IncludeGraph::from_framework(&framework_unit.dump(&convert_options), *collapse)?
+            } else {
+                let file = absolute_path(file.as_ref().unwrap())?;
+                let tu = parser.parse(&file)?;
+                report_diagnostics(&get_diagnostics(&tu, None, &convert_options), cli.strict)?;
+                let tree = HeaderFileTree::from_root_header(&file, &tu, &convert_options, |_| true);
+                IncludeGraph::from_header_file_tree(&tree, *collapse)
+            };
+            let text = if *dot {
+                graph.to_dot()
+            } else {
+                serde_json::to_string_pretty(&graph)? + "\n"
+            };
+            if let Some(output) = output {
+                std::fs::write(output, text)?;
+            } else {
+                print!("{}", text);
+            }
+        }
+        Commands::Schema { kind, output } => {
+            let text = serde_json::to_string_pretty(&schema::schema(*kind))? + "\n";
+            if let Some(output) = output {
+                std::fs::write(output, text)?;
+            } else {
+                print!("{}", text);
+            }
+        }
+        Commands::Diff {
+            json,
+            output,
+            old,
+            new,
+        } => {
+            let api_diff = diff::diff(
+                &diff::load_entries(old, output_format)?,
+                &diff::load_entries(new, output_format)?,
+            );
+            let text = if *json {
+                serde_json::to_string_pretty(&api_diff)? + "\n"
+            } else {
+                api_diff.to_text()
+            };
+            if let Some(output) = output {
+                std::fs::write(output, text)?;
+            } else {
+                print!("{}", text);
+            }
+            if api_diff.has_breaking_changes() {
+                exit_code = ExitCode::from(EXIT_BREAKING_CHANGES);
+            }
+        }
+        Commands::Query {
+            kinds,
+            name,
+            header,
+            availability,
+            platform,
+            mentions,
+            json,
+            output,
+            files,
+        } => {
+            let query = Query {
+                kinds: kinds.clone(),
+                name: name.as_deref().map(query::regex).transpose()?,
+                header: header.as_deref().map(query::regex).transpose()?,
+                availability: *availability,
+                platform: platform.clone(),
+                mentions: mentions.clone(),
+            };
+            let header_files = read_header_files(
+                &cli,
+                &parser,
+                cache.as_mut(),
+                files,
+                &convert_options,
+                output_format,
+            )?;
+            let matches = query.run(&header_files);
+            let text = if *json {
+                serde_json::to_string_pretty(&matches)? + "\n"
+            } else {
+                matches
+                    .iter()
+                    .map(|query_match| query_match.to_line() + "\n")
+                    .collect()
+            };
+            if let Some(output) = output {
+                std::fs::write(output, text)?;
+            } else {
+                print!("{}", text);
+            }
+        }
+        Commands::Interface {
+            class,
+            frameworks,
+            json,
+            output,
+            files,
+        } => {
+            let mut header_files = read_header_files(
+                &cli,
+                &parser,
+                cache.as_mut(),
+                files,
+                &convert_options,
+                output_format,
+            )?;
+            for name in frameworks {
+                let mut framework = dump_framework(&cli, &parser, name, &convert_options)?;
+                framework.inline_types();
+                header_files.extend(framework.iter().cloned());
+            }
+            let Some(interface) = InterfaceIndex::new(&header_files).resolve(class) else {
+                anyhow::bail!("no @interface {} in the header files", class);
+            };
+            let text = if *json {
+                serde_json::to_string_pretty(&interface)? + "\n"
+            } else {
+                interface.to_text()
+            };
+            if let Some(output) = output {
+                std::fs::write(output, text)?;
+            } else {
+                print!("{}", text);
+            }
+        }
+        Commands::Ast {
+            json,
+            kinds,
+            name,
+            depth,
+            all_files,
+            output,
+            file,
+        } => {
+            kinds.iter().try_for_each(|kind| ast::check_kind(kind))?;
             let tu = parser.parse(file)?;
-            tu.get_entity().get_children().iter().for_each(|entity| {
-                if entity.is_in_main_file() {
-                    pretty_print_entity(entity, 0);
+            let filter = AstFilter {
+                kinds: kinds.clone(),
+                name: name.as_deref().map(query::regex).transpose()?,
+                depth: *depth,
+                include_non_main_file: *all_files,
+                location_root: convert_options.location_root.clone(),
+            };
+            let nodes = select_nodes(&tu, &filter);
+            let format = cli.format.or(json.then_some(Format::Json));
+            if let Some(format) = format {
+                write_document(&nodes, format, output.as_deref())?;
+            } else {
+                let text: String = nodes.iter().map(|node| node.to_text()).collect();
+                if let Some(output) = output {
+                    std::fs::write(output, text)?;
+                } else {
+                    print!("{}", text);
                 }
-            });
+            }
         }
     }
-    Ok(())
+    if let Some(cache) = &cache {
+        let mut stats = cache.stats();
+        stats += worker_stats;
+        if cli.worker {
+            eprintln!("{}{}", WORKER_CACHE_STATS, serde_json::to_string(&stats)?);
+        } else {
+            eprintln!("cache {}: {}", cache.dir().display(), stats);
+        }
+    }
+    Ok(exit_code)
 }
 
-fn pretty_print_entity(entity: &clang::Entity, depth: usize) {
-    let spaces = "  ".repeat(depth);
-    println!(
-        "{}{:?} {:?}  {}",
-        spaces,
-        entity.get_name().unwrap_or("".to_string()),
-        entity.get_kind(),
-        entity
-            .get_type()
-            .map(|t| t.get_display_name())
-            .unwrap_or("".to_string())
-    );
-    entity.get_children().iter().for_each(|entity| {
-        pretty_print_entity(entity, depth + 1);
+// Runs this program on each of `items`, with the global options of `cli` and
+// the subcommand `args`, in `cli.jobs` worker processes sharing the
+// precompiled header `prefix_pch` of the parent. Returns the dumps in
+// the order of `items` and replays the stderr of the workers in that order,
+// so that the output does not depend on the number of workers, except for
+// their cache statistics which are added to `cache_stats`.
+fn run_workers<T, D>(
+    cli: &Cli,
+    prefix_pch: Option<&Path>,
+    cache_stats: &mut CacheStats,
+    items: &[T],
+    args: impl Fn(&T) -> Vec<OsString> + Sync,
+) -> Result<Vec<D>>
+where
+    T: Sync,
+    D: Document,
+{
+    let exe = std::env::current_exe()?;
+    let global_args = global_args(cli, prefix_pch);
+    let outputs = parallel_map(items, cli.jobs, |item| {
+        std::process::Command::new(&exe)
+            .args(&global_args)
+            .args(args(item))
+            .stdin(std::process::Stdio::null())
+            .output()
     });
+    let mut dumps = vec![];
+    for (item, output) in items.iter().zip(outputs) {
+        let output = output?;
+        for line in output.stderr.split_inclusive(|&byte| byte == b'\n') {
+            match line.strip_prefix(WORKER_CACHE_STATS.as_bytes()) {
+                Some(stats) => *cache_stats += serde_json::from_slice(stats)?,
+                None => std::io::stderr().write_all(line)?,
+            }
+        }
+        if !output.status.success() {
+            anyhow::bail!("worker for {:?} failed: {}", args(item), output.status);
+        }
+        dumps.push(format::read(output.stdout.as_slice(), Format::Cbor)?);
+    }
+    Ok(dumps)
+}
+
+// The global options of `cli` for a worker, which writes CBOR to stdout. The
+// worker uses `prefix_pch` rather than precompiling --prefix-header again.
+fn global_args(cli: &Cli, prefix_pch: Option<&Path>) -> Vec<OsString> {
+    let mut args: Vec<OsString> = vec![];
+    let mut push = |name: &str, value: Option<OsString>| {
+        args.push(name.into());
+        args.extend(value);
+    };
+    push(
+        "--lang",
+        cli.lang.to_possible_value().map(|v| v.get_name().into()),
+    );
+    if let Some(std) = &cli.std {
+        push(
+            "--std",
+            std.to_possible_value().map(|v| v.get_name().into()),
+        );
+    }
+    if let Some(isysroot) = &cli.isysroot {
+        push("--isysroot", Some(isysroot.into()));
+    }
+    if let Some(target) = &cli.target {
+        push("--target", Some(target.into()));
+    }
+    for framework_path in &cli.framework_paths {
+        push("--framework-path", Some(framework_path.into()));
+    }
+    if let Some(location_root) = &cli.location_root {
+        push("--location-root", Some(location_root.into()));
+    }
+    if let Some(cache_dir) = &cli.cache_dir {
+        push("--cache-dir", Some(cache_dir.into()));
+    }
+    if let Some(prefix_pch) = prefix_pch {
+        push("--prefix-pch", Some(prefix_pch.into()));
+    }
+    let flags = [
+        ("--comments", cli.comments),
+        ("--locations", cli.locations),
+        ("--type-table", cli.type_table),
+        ("--api-notes", cli.api_notes),
+        ("--strict", cli.strict),
+        ("--no-cache", cli.no_cache),
+        ("--rebuild-cache", cli.rebuild_cache),
+    ];
+    for (flag, _) in flags.iter().filter(|(_, set)| *set) {
+        push(flag, None);
+    }
+    push("--worker", None);
+    push("--format", Some("cbor".into()));
+    args
+}
+
+// Dumps the framework `name`, with its macros evaluated and its API notes
+// applied if asked, and reports the diagnostics and the skipped entities.
+fn dump_framework(
+    cli: &Cli,
+    parser: &parser::Parser,
+    name: &str,
+    options: &ConvertOptions,
+) -> Result<Framework> {
+    let framework_unit = FrameworkUnit::with_parser(name, parser)?;
+    report_diagnostics(&framework_unit.diagnostics(options), cli.strict)?;
+    let mut framework = framework_unit.dump(options);
+    framework.evaluate_macros(parser)?;
+    report_skipped(framework.iter());
+    if cli.api_notes {
+        framework.apply_api_notes()?;
+    }
+    Ok(framework)
+}
+
+// Parses the header files (`*.h`) of `files` and loads the others as dumps
+// in `format`.
+fn read_header_files(
+    cli: &Cli,
+    parser: &parser::Parser,
+    mut cache: Option<&mut ParseCache>,
+    files: &[PathBuf],
+    options: &ConvertOptions,
+    format: Format,
+) -> Result<Vec<HeaderFile>> {
+    let mut header_files = vec![];
+    for file in files {
+        if file.extension().map(|ext| ext == "h").unwrap_or(false) {
+            let file = absolute_path(file)?;
+            let mut header_file_entry =
+                dump_header_file(parser, cache.as_deref_mut(), &file, options, cli.strict)?;
+            if cli.api_notes {
+                header_file_entry.apply_api_notes()?;
+            }
+            header_files.push(header_file_entry);
+        } else {
+            header_files.extend(query::load_header_files(file, format)?);
+        }
+    }
+    Ok(header_files)
+}
+
+// Dumps `file`, with its macros evaluated, from `cache` if any, and reports
+// the diagnostics and the skipped entities.
+fn dump_header_file(
+    parser: &parser::Parser,
+    cache: Option<&mut ParseCache>,
+    file: &PathBuf,
+    options: &ConvertOptions,
+    strict: bool,
+) -> Result<HeaderFile> {
+    let header_file = if let Some(cache) = cache {
+        let cached = cache.header_file(parser, file, options)?;
+        report_diagnostics(&cached.diagnostics, strict)?;
+        cached.header_file
+    } else {
+        let tu = parser.parse(file)?;
+        report_diagnostics(&get_diagnostics(&tu, None, options), strict)?;
+        let mut header_file = HeaderFile::from_path_with_options(file, &tu, options);
+        header_file.evaluate_macros(parser)?;
+        header_file
+    };
+    report_skipped(std::iter::once(&header_file));
+    Ok(header_file)
+}
+
+// Writes `value` to `output`, or to stdout.
+fn write_document<T: Document>(value: &T, format: Format, output: Option<&Path>) -> Result<()> {
+    match output {
+        Some(output) => format::write(std::fs::File::create(output)?, value, format)?,
+        None => format::write(std::io::stdout().lock(), value, format)?,
+    }
+    Ok(())
+}
+
+// Prints `diagnostics` on stderr. With `strict`, fails if any is an error.
+fn report_diagnostics(diagnostics: &[Diagnostic], strict: bool) -> Result<()> {
+    diagnostics
+        .iter()
+        .for_each(|diagnostic| eprintln!("{}", diagnostic));
+    if strict {
+        ensure_no_errors(diagnostics)?;
+    }
+    Ok(())
+}
+
+// Prints the entities left out of `header_files` on stderr.
+fn report_skipped<'a>(header_files: impl Iterator<Item = &'a HeaderFile>) {
+    header_files
+        .flat_map(|header_file| header_file.report.skipped.iter())
+        .for_each(|skipped| eprintln!("{}", skipped));
+}
+
+fn absolute_path(file: &PathBuf) -> Result<PathBuf> {
+    if file.is_absolute() {
+        Ok(file.clone())
+    } else {
+        Ok(std::env::current_dir()?.join(file))
+    }
 }
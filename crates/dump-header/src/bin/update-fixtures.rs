@@ -16,6 +16,7 @@ fn main() -> Result<()> {
         lang: dump_header::cli::Lang::ObjC,
         std: None,
         target: None,
+        framework_paths: vec![],
     };
     let parser = parser::Parser::from(&clang, parser_config);
     let crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -0,0 +1,241 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use clang::TranslationUnit;
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::diagnostic::{get_diagnostics, Diagnostic};
+use crate::entity::ConvertOptions;
+use crate::error::Error;
+use crate::format::FORMAT_VERSION;
+use crate::headerfiletree::HeaderFile;
+use crate::parser::Parser;
+
+/// Converted header files stored in a directory.
+///
+/// A header file is looked up by its path, the clang arguments, the clang
+/// version and the `ConvertOptions`, which name the list of the files it
+/// depends on, i.e. itself and all its transitive includes. The stored result
+/// is keyed by the contents of those files, so editing any of them is a miss.
+///
+/// Keys are hashed with the standard library hasher, whose algorithm may
+/// change between Rust releases, which only costs misses.
+pub struct ParseCache {
+    dir: PathBuf,
+    rebuild: bool,
+    stats: CacheStats,
+}
+
+/// The lookups of a `ParseCache`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    /// Including the lookups ignoring the stored results to rebuild them
+    pub misses: usize,
+}
+
+impl std::fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} hit(s), {} miss(es)", self.hits, self.misses)
+    }
+}
+
+impl std::ops::AddAssign for CacheStats {
+    fn add_assign(&mut self, other: Self) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+    }
+}
+
+/// A header file dumped from the cache or from a translation unit.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedHeaderFile {
+    /// With the macros evaluated, but without API notes nor type table
+    pub header_file: HeaderFile,
+    /// All the diagnostics reported while parsing the header file
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ParseCache {
+    /// A cache in `dir`, created if missing. With `rebuild`, the stored
+    /// results are ignored and replaced.
+    pub fn new(dir: &Path, rebuild: bool) -> Result<Self, Error> {
+        std::fs::create_dir_all(dir).map_err(|e| Error::Io { source: e })?;
+        Ok(ParseCache {
+            dir: dir.to_path_buf(),
+            rebuild,
+            stats: CacheStats::default(),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Dumps the header file `path`, with its macros evaluated, from the
+    /// cache, or else with `parser` and stores the result.
+    pub fn header_file(
+        &mut self,
+        parser: &Parser,
+        path: &Path,
+        options: &ConvertOptions,
+    ) -> Result<CachedHeaderFile, Error> {
+        let key = self.lookup_key("header", path, parser, options)?;
+        if let Some(cached) = self.load(&key)? {
+            return Ok(cached);
+        }
+        let tu = parser.parse(path)?;
+        let diagnostics = get_diagnostics(&tu, None, options);
+        let mut header_file = HeaderFile::from_path_with_options(&path.to_path_buf(), &tu, options);
+        header_file.evaluate_macros(parser)?;
+        let cached = CachedHeaderFile {
+            header_file,
+            diagnostics,
+        };
+        self.store(&key, &get_dependencies(&tu, path), &cached)?;
+        Ok(cached)
+    }
+
+    /// Precompiles the prefix header `header` into the cache, unless already
+    /// there, and returns the path of the precompiled header to pass to
+    /// `Parser::set_prefix_pch`.
+    pub fn prefix_pch(&mut self, parser: &Parser, header: &Path) -> Result<PathBuf, Error> {
+        let key = self.lookup_key("pch", header, parser, &ConvertOptions::default())?;
+        if !self.rebuild {
+            if let Some(content_key) = self.content_key(&key)? {
+                let pch = self.dir.join(format!("{}.pch", content_key));
+                if pch.is_file() {
+                    self.stats.hits += 1;
+                    return Ok(pch);
+                }
+            }
+        }
+        self.stats.misses += 1;
+        // unique to this process, as in `write_atomically`
+        let pch = self
+            .dir
+            .join(format!("{}.pch.{}.tmp", key, std::process::id()));
+        let tu = parser.precompile(header, &pch)?;
+        let content_key = self.write_manifest(&key, &get_dependencies(&tu, header))?;
+        let path = self.dir.join(format!("{}.pch", content_key));
+        std::fs::rename(&pch, &path).map_err(|e| Error::Io { source: e })?;
+        Ok(path)
+    }
+
+    // Names the manifest listing the dependencies of `path`.
+    fn lookup_key(
+        &self,
+        kind: &str,
+        path: &Path,
+        parser: &Parser,
+        options: &ConvertOptions,
+    ) -> Result<String, Error> {
+        let mut hasher = DefaultHasher::new();
+        FORMAT_VERSION.hash(&mut hasher);
+        kind.hash(&mut hasher);
+        path.hash(&mut hasher);
+        clang::get_version().hash(&mut hasher);
+        parser.arguments()?.hash(&mut hasher);
+        options.comments.hash(&mut hasher);
+        options.locations.hash(&mut hasher);
+        options.location_root.hash(&mut hasher);
+        Ok(format!("{:016x}", hasher.finish()))
+    }
+
+    // Names the stored result of the current contents of the dependencies
+    // listed in the manifest `key`, `None` without a manifest.
+    fn content_key(&self, key: &str) -> Result<Option<String>, Error> {
+        let manifest = self.dir.join(format!("{}.deps.json", key));
+        let Ok(text) = std::fs::read_to_string(manifest) else {
+            return Ok(None);
+        };
+        let dependencies: Vec<PathBuf> =
+            serde_json::from_str(&text).map_err(|e| Error::Serialization {
+                source: Box::new(e),
+            })?;
+        Ok(Some(hash_contents(key, &dependencies)))
+    }
+
+    fn write_manifest(&self, key: &str, dependencies: &[PathBuf]) -> Result<String, Error> {
+        let text =
+            serde_json::to_string_pretty(dependencies).map_err(|e| Error::Serialization {
+                source: Box::new(e),
+            })?;
+        write_atomically(&self.dir.join(format!("{}.deps.json", key)), text)?;
+        Ok(hash_contents(key, dependencies))
+    }
+
+    fn load<T: DeserializeOwned>(&mut self, key: &str) -> Result<Option<T>, Error> {
+        if !self.rebuild {
+            if let Some(content_key) = self.content_key(key)? {
+                let result = self.dir.join(format!("{}.json", content_key));
+                if let Ok(text) = std::fs::read_to_string(result) {
+                    // a result written by another version is a miss
+                    if let Ok(value) = serde_json::from_str(&text) {
+                        self.stats.hits += 1;
+                        return Ok(Some(value));
+                    }
+                }
+            }
+        }
+        self.stats.misses += 1;
+        Ok(None)
+    }
+
+    fn store<T: Serialize>(
+        &self,
+        key: &str,
+        dependencies: &[PathBuf],
+        value: &T,
+    ) -> Result<(), Error> {
+        let content_key = self.write_manifest(key, dependencies)?;
+        let text = serde_json::to_string(value).map_err(|e| Error::Serialization {
+            source: Box::new(e),
+        })?;
+        write_atomically(&self.dir.join(format!("{}.json", content_key)), text)
+    }
+}
+
+/// The files a translation unit of `main_file` depends on: `main_file` and
+/// the files included directly or not, sorted.
+pub fn get_dependencies(tu: &TranslationUnit, main_file: &Path) -> Vec<PathBuf> {
+    let mut dependencies: Vec<PathBuf> = tu
+        .get_entity()
+        .get_children()
+        .iter()
+        .filter(|entity| entity.get_kind() == clang::EntityKind::InclusionDirective)
+        .filter_map(|entity| entity.get_file())
+        .map(|file| file.get_path())
+        .chain(std::iter::once(main_file.to_path_buf()))
+        .collect();
+    dependencies.sort();
+    dependencies.dedup();
+    dependencies
+}
+
+/// Hashes `key` with the paths and the contents of `dependencies`. A missing
+/// file hashes differently from any contents.
+pub fn hash_contents(key: &str, dependencies: &[PathBuf]) -> String {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    dependencies.iter().for_each(|path| {
+        path.hash(&mut hasher);
+        std::fs::read(path).ok().hash(&mut hasher);
+    });
+    format!("{:016x}", hasher.finish())
+}
+
+// Writes through a temporary file, so that a reader never sees a partial
+// file.
+fn write_atomically(path: &Path, text: String) -> Result<(), Error> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    std::fs::write(&tmp, text).map_err(|e| Error::Io { source: e })?;
+    std::fs::rename(&tmp, path).map_err(|e| Error::Io { source: e })
+}
@@ -5,8 +5,8 @@ pub enum Lang {
     C,
     #[value(name = "objective-c", alias = "objc")]
     ObjC,
-    //    #[value(name = "c++", alias = "cpp")]
-    //    Cpp,
+    #[value(name = "c++", alias = "cpp")]
+    Cpp,
 }
 
 #[derive(ValueEnum, Clone)]
@@ -19,4 +19,74 @@ pub enum Std {
     GNU11,
     C17,
     GNU17,
+    #[value(name = "c++11")]
+    Cxx11,
+    #[value(name = "gnu++11")]
+    GNUxx11,
+    #[value(name = "c++14")]
+    Cxx14,
+    #[value(name = "gnu++14")]
+    GNUxx14,
+    #[value(name = "c++17")]
+    Cxx17,
+    #[value(name = "gnu++17")]
+    GNUxx17,
+    #[value(name = "c++20")]
+    Cxx20,
+    #[value(name = "gnu++20")]
+    GNUxx20,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Pretty-printed JSON
+    Json,
+    /// JSON on a single line
+    #[value(name = "json-compact")]
+    JsonCompact,
+    /// Newline-delimited JSON, one entry per line
+    Ndjson,
+    Yaml,
+    Cbor,
+    #[value(name = "msgpack")]
+    MessagePack,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Json | Format::JsonCompact => "json",
+            Format::Ndjson => "ndjson",
+            Format::Yaml => "yaml",
+            Format::Cbor => "cbor",
+            Format::MessagePack => "msgpack",
+        }
+    }
+}
+
+/// The documents described by the `schema` subcommand.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum SchemaKind {
+    /// Output of `dump`
+    HeaderFile,
+    /// Output of `framework`
+    Framework,
+    /// Output of `ast`
+    Ast,
+    /// JSON output of `deps`
+    Deps,
+    /// JSON output of `query`
+    Query,
+    /// JSON output of `interface`
+    Interface,
+}
+
+/// The availability selected by `query`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AvailabilityFilter {
+    /// Usable, possibly deprecated
+    Available,
+    Deprecated,
+    /// Unavailable or obsoleted
+    Unavailable,
 }
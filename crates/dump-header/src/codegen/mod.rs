@@ -0,0 +1,782 @@
+use std::collections::{HashMap, HashSet};
+
+use clang::TypeKind;
+
+use crate::entity::{
+    Comment, Entry, EnumConstantDecl, EnumExtensibility, InitExpr, InitValue, Location,
+    ReturnOwnership, StorageClass,
+};
+use crate::headerfiletree::HeaderFile;
+use crate::typ::{RecordField, RecordLayout, Typ};
+
+mod typ;
+use typ::{is_char, is_const, is_identifier, rust_ident, TypeContext};
+
+const PRELUDE: &str = r#"/* automatically generated by dump-header */
+
+#![allow(
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    dead_code,
+    unused_imports
+)]
+
+use std::os::raw::*;
+"#;
+
+/// Generates Rust bindings for the C declarations of a header file.
+pub fn generate_rust(header_file: &HeaderFile) -> String {
+    let mut codegen = RustCodegen::default();
+    if header_file.types.is_empty() {
+        codegen.generate(&header_file.entries);
+    } else {
+        let mut header_file = header_file.clone();
+        header_file.inline_types();
+        codegen.generate(&header_file.entries);
+    }
+    codegen.finish()
+}
+
+/// Turns `Entry` values into Rust items. Call `generate` once per header
+/// file and `finish` to get the module source.
+///
+/// Records are generated from their `Typ`, so anonymous records are emitted
+/// under their typedef name, or under a generated name where they are used.
+/// Declarations that cannot be expressed in Rust are replaced by a comment.
+#[derive(Default)]
+pub struct RustCodegen {
+    items: Vec<String>,
+    extern_items: Vec<String>,
+    emitted: HashSet<String>,
+    // display name of an unnamed record -> Rust name
+    record_names: HashMap<String, String>,
+    anon_counter: usize,
+}
+
+impl RustCodegen {
+    pub fn generate(&mut self, entries: &[Entry]) {
+        self.collect_typedef_names(entries);
+        entries.iter().for_each(|entry| self.generate_entry(entry));
+    }
+
+    pub fn finish(self) -> String {
+        let mut out = PRELUDE.to_string();
+        self.items.iter().for_each(|item| {
+            out.push('\n');
+            out.push_str(item);
+            out.push('\n');
+        });
+        if !self.extern_items.is_empty() {
+            out.push_str("\nextern \"C\" {\n");
+            self.extern_items.iter().for_each(|item| {
+                item.lines().for_each(|line| {
+                    out.push_str("    ");
+                    out.push_str(line);
+                    out.push('\n');
+                });
+            });
+            out.push_str("}\n");
+        }
+        out
+    }
+
+    // `typedef struct { ... } Foo;` declares an unnamed record that Rust
+    // can only refer to as `Foo`.
+    fn collect_typedef_names(&mut self, entries: &[Entry]) {
+        entries.iter().for_each(|entry| {
+            if let Entry::TypedefDecl {
+                name,
+                ty:
+                    Typ::StructRecord {
+                        name: Some(record_name),
+                        ident,
+                        ..
+                    }
+                    | Typ::UnionRecord {
+                        name: Some(record_name),
+                        ident,
+                        ..
+                    },
+                ..
+            } = entry
+            {
+                let has_ident = ident.as_deref().map(is_identifier).unwrap_or(false);
+                if !has_ident && is_identifier(name) {
+                    self.record_names
+                        .entry(record_name.clone())
+                        .or_insert(name.clone());
+                }
+            }
+        });
+    }
+
+    fn generate_entry(&mut self, entry: &Entry) {
+        let doc = format!(
+            "{}{}",
+            doc_comment(entry.comment()),
+            location_comment(entry.location())
+        );
+        match entry {
+            Entry::TypedefDecl { name, ty, .. } => self.generate_typedef(name, ty, &doc),
+            Entry::EnumDecl { .. } => self.generate_enum(entry, &doc),
+            Entry::VarDecl {
+                name,
+                ty,
+                init_expr,
+                ..
+            } => self.generate_var(name, ty, init_expr.as_ref(), &doc),
+            Entry::StructDecl { name, ty, .. } | Entry::UnionDecl { name, ty, .. } => {
+                let rust_name = name.as_deref().and_then(rust_ident).or_else(|| {
+                    record_display_name(ty).and_then(|n| self.record_names.get(n).cloned())
+                });
+                // unnamed records without a typedef are emitted where they are used
+                if let Some(rust_name) = rust_name {
+                    if let Err(unsupported) = self.emit_record(&rust_name, ty, &doc) {
+                        self.skip(&rust_name, &unsupported);
+                    }
+                }
+            }
+            Entry::FunctionDecl { .. } => self.generate_function(entry, &doc),
+            Entry::MacroDefinition {
+                name,
+                value: Some(value),
+                ..
+            } => self.generate_macro(name, value, &doc),
+            _ => {}
+        }
+    }
+
+    /// A closed enum becomes a Rust `enum` and a flag enum a newtype with
+    /// bit operators. Other enums, whose values may be none of their
+    /// constants, are a type alias and constants.
+    fn generate_enum(&mut self, entry: &Entry, doc: &str) {
+        let Entry::EnumDecl {
+            decls,
+            name,
+            is_anonymous,
+            typedef_name,
+            ty,
+            extensibility,
+            is_flag_enum,
+            ..
+        } = entry
+        else {
+            return;
+        };
+        let underlying = match self.rust_type(ty, TypeContext::Field) {
+            Ok(underlying) => underlying,
+            Err(unsupported) => return self.skip(name, &unsupported),
+        };
+        let rust_name = if *is_anonymous {
+            typedef_name.as_deref().and_then(rust_ident)
+        } else {
+            rust_ident(name)
+        };
+        let Some(ident) = rust_name else {
+            // anonymous enums are plain constants
+            return self.generate_enum_consts(decls, &underlying, None);
+        };
+        if *is_flag_enum {
+            if self.emitted.insert(ident.clone()) {
+                self.items.push(format!(
+                    "{doc}#[repr(transparent)]\n#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]\npub struct {ident}(pub {underlying});"
+                ));
+                self.items.push(format!(
+                    "impl {ident} {{\n    pub const fn contains(self, other: Self) -> bool {{\n        self.0 & other.0 == other.0\n    }}\n}}"
+                ));
+                for (op, method, symbol) in [("BitOr", "bitor", "|"), ("BitAnd", "bitand", "&")] {
+                    self.items.push(format!(
+                        "impl std::ops::{op} for {ident} {{\n    type Output = Self;\n    fn {method}(self, rhs: Self) -> Self {{\n        Self(self.0 {symbol} rhs.0)\n    }}\n}}"
+                    ));
+                }
+            }
+            return self.generate_enum_consts(decls, &underlying, Some(&ident));
+        }
+        let variants = match (extensibility, enum_repr(ty)) {
+            (Some(EnumExtensibility::Closed), Some(repr)) => {
+                enum_variants(decls).map(|v| (repr, v))
+            }
+            _ => None,
+        };
+        if let Some((repr, variants)) = variants {
+            if self.emitted.insert(ident.clone()) {
+                let body: String = variants
+                    .iter()
+                    .map(|(variant_doc, variant, value)| {
+                        let variant_doc: String = variant_doc
+                            .lines()
+                            .map(|line| format!("    {}\n", line))
+                            .collect();
+                        format!("{}    {} = {},\n", variant_doc, variant, value)
+                    })
+                    .collect();
+                self.items.push(format!(
+                    "{doc}#[repr({repr})]\n#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]\npub enum {ident} {{\n{body}}}"
+                ));
+            }
+            return;
+        }
+        if self.emitted.insert(ident.clone()) {
+            self.items
+                .push(format!("{}pub type {} = {};", doc, ident, underlying));
+        }
+        self.generate_enum_consts(decls, &ident, None);
+    }
+
+    // `newtype` wraps the values of a flag enum.
+    fn generate_enum_consts(
+        &mut self,
+        decls: &[EnumConstantDecl],
+        const_ty: &str,
+        newtype: Option<&str>,
+    ) {
+        decls.iter().for_each(|decl| {
+            let (Some(ident), Some(value)) = (rust_ident(&decl.name), &decl.value) else {
+                return;
+            };
+            if self.emitted.insert(ident.clone()) {
+                let literal = integer_literal(value, const_ty);
+                let (const_ty, literal) = match newtype {
+                    Some(newtype) => (newtype, format!("{}({})", newtype, literal)),
+                    None => (const_ty, literal),
+                };
+                self.items.push(format!(
+                    "{}pub const {}: {} = {};",
+                    doc_comment(decl.comment.as_deref())
+                        + &location_comment(decl.location.as_ref()),
+                    ident,
+                    const_ty,
+                    literal
+                ));
+            }
+        });
+    }
+
+    fn generate_typedef(&mut self, name: &str, ty: &Typ, doc: &str) {
+        let Some(ident) = rust_ident(name) else {
+            return self.skip(name, name);
+        };
+        let is_record_name = record_display_name(ty)
+            .and_then(|n| self.record_names.get(n))
+            .map(|n| n == &ident)
+            .unwrap_or(false);
+        if is_record_name {
+            if let Err(unsupported) = self.emit_record(&ident, ty, doc) {
+                self.skip(name, &unsupported);
+            }
+            return;
+        }
+        match self.rust_type(ty, TypeContext::Field) {
+            // `typedef struct foo foo;`
+            Ok(rust_ty) if rust_ty == ident => {}
+            Ok(rust_ty) => {
+                if self.emitted.insert(ident.clone()) {
+                    self.items
+                        .push(format!("{}pub type {} = {};", doc, ident, rust_ty));
+                }
+            }
+            Err(unsupported) => self.skip(name, &unsupported),
+        }
+    }
+
+    fn generate_var(&mut self, name: &str, ty: &Typ, init_expr: Option<&InitExpr>, doc: &str) {
+        let Some(ident) = rust_ident(name) else {
+            return self.skip(name, name);
+        };
+        let rust_ty = match self.rust_type(ty, TypeContext::Field) {
+            Ok(rust_ty) => rust_ty,
+            Err(unsupported) => return self.skip(name, &unsupported),
+        };
+        if !self.emitted.insert(ident.clone()) {
+            return;
+        }
+        if let Some(InitExpr::Value(value)) = init_expr {
+            if is_const(ty) {
+                if let Some(literal) = scalar_literal(value, &rust_ty) {
+                    self.items.push(format!(
+                        "{}pub const {}: {} = {};",
+                        doc, ident, rust_ty, literal
+                    ));
+                    return;
+                }
+            }
+            if let InitValue::String(s) = value {
+                if is_string_constant(ty) {
+                    self.items.push(format!(
+                        "{}pub const {}: &[u8] = b\"{}\\0\";",
+                        doc,
+                        ident,
+                        s.as_bytes().escape_ascii()
+                    ));
+                    return;
+                }
+            }
+        }
+        let mutability = if is_const(ty) { "" } else { "mut " };
+        self.extern_items.push(format!(
+            "{}{}pub static {}{}: {};",
+            doc,
+            link_name(name, &ident),
+            mutability,
+            ident,
+            rust_ty
+        ));
+    }
+
+    /// A `static` function, usually `static inline`, has no symbol to link
+    /// to and is skipped. A `noreturn` function returns `!`. The ownership
+    /// annotations of the result and the arguments are documentation only:
+    /// the declarations keep raw pointers and no wrapper retains or releases.
+    fn generate_function(&mut self, entry: &Entry, doc: &str) {
+        let Entry::FunctionDecl {
+            name,
+            arguments,
+            result_type,
+            is_variadic,
+            storage_class,
+            is_inline,
+            return_ownership,
+            warn_unused_result,
+            is_noreturn,
+            ..
+        } = entry
+        else {
+            return;
+        };
+        if *storage_class == Some(StorageClass::Static) {
+            let kind = if *is_inline {
+                "static inline"
+            } else {
+                "static"
+            };
+            return self.skip_because(name, &format!("{} function without a symbol", kind));
+        }
+        let Some(ident) = rust_ident(name) else {
+            return self.skip(name, name);
+        };
+        if !self.emitted.insert(format!("fn {}", ident)) {
+            return;
+        }
+        let mut params = vec![];
+        let mut ownership_docs = vec![];
+        let mut param_names = HashSet::new();
+        for (i, arg) in arguments.iter().enumerate() {
+            let param_ty = match self.rust_type(&arg.objc_type, TypeContext::Param) {
+                Ok(param_ty) => param_ty,
+                Err(unsupported) => return self.skip(name, &unsupported),
+            };
+            let mut param_name = arg
+                .name
+                .as_deref()
+                .and_then(rust_ident)
+                .unwrap_or(format!("arg{}", i));
+            if !param_names.insert(param_name.clone()) {
+                param_name = format!("{}{}", param_name, i);
+            }
+            if arg.is_cf_consumed || arg.is_ns_consumed {
+                ownership_docs.push(format!(
+                    "/// Consumes `{}`: the function releases it.\n",
+                    param_name
+                ));
+            }
+            params.push(format!("{}: {}", param_name, param_ty));
+        }
+        if *is_variadic {
+            if params.is_empty() {
+                return self.skip_because(name, "variadic function without parameters");
+            }
+            params.push("...".to_string());
+        }
+        let result = match self.result_type(result_type) {
+            Ok(result) if result.is_empty() && *is_noreturn => " -> !".to_string(),
+            Ok(result) => result,
+            Err(unsupported) => return self.skip(name, &unsupported),
+        };
+        match return_ownership {
+            Some(ReturnOwnership::Retained) => ownership_docs.push(
+                "/// Returns a retained reference: the caller must release it.\n".to_string(),
+            ),
+            Some(ReturnOwnership::NotRetained) => ownership_docs.push(
+                "/// Returns a reference that is not retained: the caller must not release it.\n"
+                    .to_string(),
+            ),
+            Some(ReturnOwnership::Autoreleased) => {
+                ownership_docs.push("/// Returns an autoreleased reference.\n".to_string())
+            }
+            None => {}
+        }
+        let must_use = if *warn_unused_result {
+            "#[must_use]\n"
+        } else {
+            ""
+        };
+        self.extern_items.push(format!(
+            "{}{}{}{}pub fn {}({}){};",
+            doc,
+            ownership_docs.concat(),
+            must_use,
+            link_name(name, &ident),
+            ident,
+            params.join(", "),
+            result
+        ));
+    }
+
+    fn generate_macro(&mut self, name: &str, value: &InitValue, doc: &str) {
+        let Some(ident) = rust_ident(name) else {
+            return;
+        };
+        let (rust_ty, literal) = match value {
+            InitValue::Int(i) if i32::try_from(*i).is_ok() => ("i32", i.to_string()),
+            InitValue::Int(i) => ("i64", i.to_string()),
+            InitValue::UInt(u) if u32::try_from(*u).is_ok() => ("u32", u.to_string()),
+            InitValue::UInt(u) => ("u64", u.to_string()),
+            InitValue::String(s) => ("&[u8]", format!("b\"{}\\0\"", s.as_bytes().escape_ascii())),
+            InitValue::Bool(b) => ("bool", b.to_string()),
+            InitValue::Float(_) => match scalar_literal(value, "f64") {
+                Some(literal) => ("f64", literal),
+                None => return,
+            },
+            _ => return,
+        };
+        if self.emitted.insert(ident.clone()) {
+            self.items.push(format!(
+                "{}pub const {}: {} = {};",
+                doc, ident, rust_ty, literal
+            ));
+        }
+    }
+
+    fn record_name(&mut self, ty: &Typ) -> Result<String, String> {
+        if let Typ::StructRecord { ident, .. } | Typ::UnionRecord { ident, .. } = ty {
+            if let Some(ident) = ident.as_deref().and_then(rust_ident) {
+                return Ok(ident);
+            }
+        }
+        let display_name = record_display_name(ty);
+        if let Some(rust_name) = display_name.and_then(|n| self.record_names.get(n)) {
+            return Ok(rust_name.clone());
+        }
+        let rust_name = format!("__anon_record{}", self.anon_counter);
+        self.anon_counter += 1;
+        if let Some(display_name) = display_name {
+            self.record_names
+                .insert(display_name.to_string(), rust_name.clone());
+        }
+        self.emit_record(&rust_name, ty, "")?;
+        Ok(rust_name)
+    }
+
+    /// A record with a layout gets assertions that the Rust layout is the
+    /// one computed by clang: its size, alignment and, for a struct, the
+    /// offsets of its fields. A record with bit-fields, or both packed and
+    /// aligned, becomes an opaque struct of its size and alignment.
+    fn emit_record(&mut self, rust_name: &str, ty: &Typ, doc: &str) -> Result<(), String> {
+        let (keyword, fields, layout) = match ty {
+            Typ::StructRecord { fields, layout, .. } => ("struct", fields, layout),
+            Typ::UnionRecord { fields, layout, .. } => ("union", fields, layout),
+            _ => return Err(rust_name.to_string()),
+        };
+        if self.emitted.contains(rust_name) {
+            return Ok(());
+        }
+        if let Some(unsupported) = unsupported_layout(rust_name, fields, layout.as_ref()) {
+            let Some(layout) = layout else {
+                return Err(unsupported);
+            };
+            self.emitted.insert(rust_name.to_string());
+            self.items.push(format!(
+                "{}// opaque, unsupported `{}`\n#[repr(C, align({}))]\n#[derive(Copy, Clone)]\n\
+                 pub struct {} {{\n    _opaque: [u8; {}],\n}}",
+                doc, unsupported, layout.align, rust_name, layout.size
+            ));
+            self.push_layout_assertions(rust_name, layout, &[]);
+            return Ok(());
+        }
+        self.emitted.insert(rust_name.to_string());
+        let repr = match layout {
+            Some(RecordLayout { packed: true, .. }) => "C, packed".to_string(),
+            Some(RecordLayout {
+                aligned: Some(_),
+                align,
+                ..
+            }) => format!("C, align({})", align),
+            _ => "C".to_string(),
+        };
+        let mut lines = vec![];
+        let mut offsets = vec![];
+        for (i, field) in fields.iter().enumerate() {
+            let field_name = field
+                .name
+                .as_deref()
+                .and_then(rust_ident)
+                .unwrap_or(format!("__anon{}", i));
+            if let Some(field_layout) = &field.layout {
+                offsets.push((field_name.clone(), field_layout.bit_offset / 8));
+            }
+            let field_ty = if is_unnamed_record(&field.ty) {
+                let nested_name = format!("{}__anon{}", rust_name, i);
+                self.emit_record(&nested_name, &field.ty, "")
+                    .map(|()| nested_name)
+            } else {
+                self.rust_type(&field.ty, TypeContext::Field)
+            };
+            let field_ty = match field_ty {
+                Ok(field_ty) => field_ty,
+                Err(unsupported) => {
+                    self.emitted.remove(rust_name);
+                    return Err(unsupported);
+                }
+            };
+            lines.push(format!("    pub {}: {},", field_name, field_ty));
+        }
+        if lines.is_empty() {
+            // opaque or forward-declared only
+            lines.push("    _unused: [u8; 0],".to_string());
+        }
+        self.items.push(format!(
+            "{}#[repr({})]\n#[derive(Copy, Clone)]\npub {} {} {{\n{}\n}}",
+            doc,
+            repr,
+            keyword,
+            rust_name,
+            lines.join("\n")
+        ));
+        if let Some(layout) = layout {
+            // the fields of a union are all at offset 0
+            let offsets = if keyword == "struct" {
+                &offsets[..]
+            } else {
+                &[]
+            };
+            self.push_layout_assertions(rust_name, layout, offsets);
+        }
+        Ok(())
+    }
+
+    fn push_layout_assertions(
+        &mut self,
+        rust_name: &str,
+        layout: &RecordLayout,
+        offsets: &[(String, usize)],
+    ) {
+        let mut assertions = vec![
+            format!(
+                "    assert!(std::mem::size_of::<{}>() == {});",
+                rust_name, layout.size
+            ),
+            format!(
+                "    assert!(std::mem::align_of::<{}>() == {});",
+                rust_name, layout.align
+            ),
+        ];
+        assertions.extend(offsets.iter().map(|(field_name, offset)| {
+            format!(
+                "    assert!(std::mem::offset_of!({}, {}) == {});",
+                rust_name, field_name, offset
+            )
+        }));
+        self.items
+            .push(format!("const _: () = {{\n{}\n}};", assertions.join("\n")));
+    }
+
+    fn skip(&mut self, name: &str, unsupported: &str) {
+        self.skip_because(name, &format!("unsupported type `{}`", unsupported));
+    }
+
+    fn skip_because(&mut self, name: &str, reason: &str) {
+        self.items
+            .push(format!("// skipped `{}`: {}", name, reason));
+    }
+}
+
+// What Rust cannot lay out as clang does: a bit-field, or a record both
+// packed and aligned.
+fn unsupported_layout(
+    rust_name: &str,
+    fields: &[RecordField],
+    layout: Option<&RecordLayout>,
+) -> Option<String> {
+    if let Some(RecordLayout {
+        packed: true,
+        aligned: Some(_),
+        ..
+    }) = layout
+    {
+        return Some(format!("{} (packed and aligned)", rust_name));
+    }
+    fields.iter().find_map(|field| {
+        let width = field.layout.as_ref()?.bitfield_width?;
+        Some(format!(
+            "{} {} : {}",
+            field.ty.name().unwrap_or_default(),
+            field.name.as_deref().unwrap_or_default(),
+            width
+        ))
+    })
+}
+
+// Renders `/// ` lines ending with a newline, or an empty string.
+fn doc_comment(comment: Option<&Comment>) -> String {
+    let Some(comment) = comment else {
+        return String::new();
+    };
+    let mut sections = vec![];
+    if let Some(brief) = &comment.brief {
+        sections.push(brief.clone());
+    }
+    comment
+        .paragraphs
+        .iter()
+        .filter(|paragraph| comment.brief.as_ref() != Some(*paragraph))
+        .for_each(|paragraph| sections.push(paragraph.clone()));
+    if !comment.params.is_empty() {
+        sections.push(
+            comment
+                .params
+                .iter()
+                .map(|param| format!("- `{}`: {}", param.name, param.text))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+    }
+    if let Some(returns) = &comment.returns {
+        sections.push(format!("Returns: {}", returns));
+    }
+    comment
+        .notes
+        .iter()
+        .for_each(|note| sections.push(format!("Note: {}", note)));
+    if let Some(deprecated) = &comment.deprecated {
+        sections.push(format!("Deprecated: {}", deprecated));
+    }
+    let mut doc = String::new();
+    for (i, section) in sections.iter().enumerate() {
+        if i > 0 {
+            doc.push_str("///\n");
+        }
+        section.lines().for_each(|line| {
+            doc.push_str("/// ");
+            doc.push_str(line);
+            doc.push('\n');
+        });
+    }
+    doc
+}
+
+fn location_comment(location: Option<&Location>) -> String {
+    location
+        .map(|location| format!("// {}\n", location))
+        .unwrap_or_default()
+}
+
+fn record_display_name(ty: &Typ) -> Option<&str> {
+    match ty {
+        Typ::StructRecord { name, .. } | Typ::UnionRecord { name, .. } => name.as_deref(),
+        _ => None,
+    }
+}
+
+fn is_unnamed_record(ty: &Typ) -> bool {
+    matches!(
+        ty,
+        Typ::StructRecord {
+            name: None,
+            ident: None,
+            ..
+        } | Typ::UnionRecord {
+            name: None,
+            ident: None,
+            ..
+        }
+    )
+}
+
+// `const char *const` and `const char []`
+fn is_string_constant(ty: &Typ) -> bool {
+    match ty {
+        Typ::Pointer {
+            pointee_type,
+            is_const,
+            ..
+        } => *is_const && is_char(pointee_type),
+        Typ::CArray { element_type, .. } => is_const(element_type) && is_char(element_type),
+        _ => false,
+    }
+}
+
+fn link_name(name: &str, ident: &str) -> String {
+    if ident.trim_start_matches("r#") == name {
+        String::new()
+    } else {
+        format!("#[link_name = \"{}\"]\n", name)
+    }
+}
+
+/// The `#[repr]` of a Rust enum with the underlying type `ty`, assuming
+/// an LP64 target.
+fn enum_repr(ty: &Typ) -> Option<&'static str> {
+    let Typ::OtherType { clang_kind, .. } = ty.canonical() else {
+        return None;
+    };
+    let repr = match clang_kind {
+        TypeKind::CharS | TypeKind::SChar => "i8",
+        TypeKind::CharU | TypeKind::UChar => "u8",
+        TypeKind::Short => "i16",
+        TypeKind::UShort => "u16",
+        TypeKind::Int => "i32",
+        TypeKind::UInt => "u32",
+        TypeKind::Long | TypeKind::LongLong => "i64",
+        TypeKind::ULong | TypeKind::ULongLong => "u64",
+        _ => return None,
+    };
+    Some(repr)
+}
+
+/// (doc, name, value) of the variants of a Rust enum, `None` unless every
+/// constant has a known and distinct value.
+fn enum_variants(decls: &[EnumConstantDecl]) -> Option<Vec<(String, String, String)>> {
+    let mut values = HashSet::new();
+    decls
+        .iter()
+        .map(|decl| {
+            let value = decl.value.clone()?;
+            if !values.insert(value.clone()) {
+                return None;
+            }
+            Some((
+                doc_comment(decl.comment.as_deref()) + &location_comment(decl.location.as_ref()),
+                rust_ident(&decl.name)?,
+                value,
+            ))
+        })
+        .collect()
+}
+
+fn integer_literal(value: &str, rust_ty: &str) -> String {
+    if value.starts_with('-') {
+        format!("{}i64 as {}", value, rust_ty)
+    } else {
+        value.to_string()
+    }
+}
+
+fn scalar_literal(value: &InitValue, rust_ty: &str) -> Option<String> {
+    if rust_ty.starts_with(['*', '[']) || rust_ty.starts_with("Option") {
+        return None;
+    }
+    let literal = match value {
+        InitValue::Int(i) if rust_ty == "bool" => (*i != 0).to_string(),
+        InitValue::UInt(u) if rust_ty == "bool" => (*u != 0).to_string(),
+        InitValue::Int(i) => integer_literal(&i.to_string(), rust_ty),
+        InitValue::UInt(u) => u.to_string(),
+        InitValue::Bool(b) => b.to_string(),
+        InitValue::Float(f) if f.is_nan() => format!("{}::NAN", rust_ty),
+        InitValue::Float(f) if f.is_infinite() && *f > 0.0 => format!("{}::INFINITY", rust_ty),
+        InitValue::Float(f) if f.is_infinite() => format!("{}::NEG_INFINITY", rust_ty),
+        InitValue::Float(f) => format!("{:?}", f),
+        _ => return None,
+    };
+    Some(literal)
+}
@@ -0,0 +1,282 @@
+use clang::TypeKind;
+
+use super::RustCodegen;
+use crate::typ::Typ;
+
+/// Where a type is used. C decays arrays in parameters and `void` means
+/// "nothing" only in result position.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum TypeContext {
+    Field,
+    Param,
+    Result,
+    Pointee,
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+// Keywords that cannot be used as raw identifiers.
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super", "_"];
+
+pub(super) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c == '_' || c.is_ascii_alphabetic() => {}
+        _ => return false,
+    }
+    chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+/// Returns `name` as a usable Rust identifier, or `None` if `name` is not a
+/// C identifier at all (e.g. `struct (unnamed at t.h:1:9)`).
+pub(super) fn rust_ident(name: &str) -> Option<String> {
+    if !is_identifier(name) {
+        return None;
+    }
+    if NON_RAW_KEYWORDS.contains(&name) {
+        Some(format!("{}_", name))
+    } else if RUST_KEYWORDS.contains(&name) {
+        Some(format!("r#{}", name))
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Strips qualifiers and tag keywords from a clang display name,
+/// e.g. `const struct foo` -> `foo`.
+fn strip_display_name(name: &str) -> &str {
+    let mut name = name.trim();
+    loop {
+        let stripped = [
+            "const ",
+            "volatile ",
+            "restrict ",
+            "struct ",
+            "union ",
+            "enum ",
+        ]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix));
+        match stripped {
+            Some(rest) => name = rest.trim_start(),
+            None => break,
+        }
+    }
+    name.trim_end_matches(" const")
+        .trim_end_matches(" volatile")
+}
+
+fn well_known_typedef(name: &str) -> Option<&'static str> {
+    let ty = match name {
+        "int8_t" => "i8",
+        "int16_t" => "i16",
+        "int32_t" => "i32",
+        "int64_t" => "i64",
+        "uint8_t" => "u8",
+        "uint16_t" => "u16",
+        "uint32_t" => "u32",
+        "uint64_t" => "u64",
+        "size_t" | "uintptr_t" => "usize",
+        "ssize_t" | "intptr_t" | "ptrdiff_t" => "isize",
+        _ => return None,
+    };
+    Some(ty)
+}
+
+fn primitive(kind: TypeKind) -> Option<&'static str> {
+    let ty = match kind {
+        TypeKind::Void => "c_void",
+        TypeKind::Bool => "bool",
+        TypeKind::CharS | TypeKind::CharU => "c_char",
+        TypeKind::SChar => "c_schar",
+        TypeKind::UChar => "c_uchar",
+        TypeKind::WChar => "i32",
+        TypeKind::Char16 => "u16",
+        TypeKind::Char32 => "u32",
+        TypeKind::Short => "c_short",
+        TypeKind::UShort => "c_ushort",
+        TypeKind::Int => "c_int",
+        TypeKind::UInt => "c_uint",
+        TypeKind::Long => "c_long",
+        TypeKind::ULong => "c_ulong",
+        TypeKind::LongLong => "c_longlong",
+        TypeKind::ULongLong => "c_ulonglong",
+        TypeKind::Int128 => "i128",
+        TypeKind::UInt128 => "u128",
+        // storage-compatible only; Rust has no 16-bit or extended floats
+        TypeKind::Half | TypeKind::Float16 => "u16",
+        TypeKind::LongDouble | TypeKind::Float128 => "u128",
+        TypeKind::Float => "c_float",
+        TypeKind::Double => "c_double",
+        _ => return None,
+    };
+    Some(ty)
+}
+
+pub(super) fn is_const(ty: &Typ) -> bool {
+    match ty {
+        Typ::Pointer { is_const, .. }
+        | Typ::Reference { is_const, .. }
+        | Typ::FunctionPrototype { is_const, .. }
+        | Typ::CArray { is_const, .. }
+        | Typ::StructRecord { is_const, .. }
+        | Typ::UnionRecord { is_const, .. }
+        | Typ::ObjC { is_const, .. }
+        | Typ::Typedef { is_const, .. }
+        | Typ::OtherType { is_const, .. } => *is_const,
+        Typ::RecordIdent { .. } | Typ::TypeRef { .. } => false,
+    }
+}
+
+// A `Typ::RecordIdent` carries no qualifiers, so the constness of the
+// record it refers to is read from the pointer, e.g. `const struct node *`.
+fn is_const_pointee(pointer_name: &str, pointee: &Typ) -> bool {
+    match pointee {
+        Typ::RecordIdent { .. } => pointer_name
+            .rsplit_once('*')
+            .is_some_and(|(pointee, _)| pointee.split_whitespace().any(|token| token == "const")),
+        _ => is_const(pointee),
+    }
+}
+
+pub(super) fn is_char(ty: &Typ) -> bool {
+    matches!(
+        ty.canonical(),
+        Typ::OtherType {
+            clang_kind: TypeKind::CharS | TypeKind::CharU | TypeKind::SChar | TypeKind::UChar,
+            ..
+        }
+    )
+}
+
+impl RustCodegen {
+    /// Maps `ty` to a Rust type. `Err` carries the display name of the first
+    /// type that has no Rust counterpart.
+    pub(super) fn rust_type(&mut self, ty: &Typ, context: TypeContext) -> Result<String, String> {
+        match ty {
+            Typ::Pointer {
+                name,
+                clang_kind,
+                pointee_type,
+                ..
+            } => match clang_kind {
+                TypeKind::Pointer => {
+                    if let Typ::FunctionPrototype { .. } = pointee_type.as_ref() {
+                        let f = self.rust_type(pointee_type, TypeContext::Field)?;
+                        return Ok(format!("Option<{}>", f));
+                    }
+                    let mutability = if is_const_pointee(name, pointee_type) {
+                        "const"
+                    } else {
+                        "mut"
+                    };
+                    let pointee = self.rust_type(pointee_type, TypeContext::Pointee)?;
+                    Ok(format!("*{} {}", mutability, pointee))
+                }
+                TypeKind::ObjCObjectPointer | TypeKind::BlockPointer => {
+                    Ok("*mut c_void".to_string())
+                }
+                _ => Err(name.clone()),
+            },
+            // references are passed as pointers
+            Typ::Reference { pointee_type, .. } => {
+                let mutability = if is_const(pointee_type) {
+                    "const"
+                } else {
+                    "mut"
+                };
+                let pointee = self.rust_type(pointee_type, TypeContext::Pointee)?;
+                Ok(format!("*{} {}", mutability, pointee))
+            }
+            Typ::FunctionPrototype {
+                argument_types,
+                result_type,
+                ..
+            } => {
+                let mut args = vec![];
+                for arg in argument_types.iter().flatten() {
+                    args.push(self.rust_type(arg, TypeContext::Param)?);
+                }
+                let result = match result_type {
+                    Some(result_type) => self.result_type(result_type)?,
+                    None => String::new(),
+                };
+                Ok(format!(
+                    "unsafe extern \"C\" fn({}){}",
+                    args.join(", "),
+                    result
+                ))
+            }
+            Typ::CArray {
+                element_type, size, ..
+            } => {
+                let element = self.rust_type(element_type, TypeContext::Field)?;
+                match context {
+                    TypeContext::Param => {
+                        let mutability = if is_const(element_type) {
+                            "const"
+                        } else {
+                            "mut"
+                        };
+                        Ok(format!("*{} {}", mutability, element))
+                    }
+                    _ => Ok(format!("[{}; {}]", element, size.unwrap_or(0))),
+                }
+            }
+            Typ::StructRecord { .. } | Typ::UnionRecord { .. } => self.record_name(ty),
+            Typ::RecordIdent { ident } => rust_ident(ident).ok_or(ident.clone()),
+            // `generate_rust` inlines the type table
+            Typ::TypeRef { id } => Err(id.clone()),
+            Typ::ObjC { .. } => Ok("c_void".to_string()),
+            Typ::Typedef {
+                typedef_name,
+                underlying_type,
+                ..
+            } => {
+                if let Some(ty) = well_known_typedef(typedef_name) {
+                    return Ok(ty.to_string());
+                }
+                match rust_ident(typedef_name) {
+                    Some(ident) if self.emitted.contains(&ident) => Ok(ident),
+                    // declared in a header that is not generated
+                    _ => self.rust_type(underlying_type, context),
+                }
+            }
+            Typ::OtherType {
+                name, clang_kind, ..
+            } => match clang_kind {
+                TypeKind::Void if context == TypeContext::Result => Ok(String::new()),
+                TypeKind::Typedef => {
+                    let name = strip_display_name(name);
+                    if let Some(ty) = well_known_typedef(name) {
+                        return Ok(ty.to_string());
+                    }
+                    rust_ident(name).ok_or(name.to_string())
+                }
+                TypeKind::Enum => {
+                    // anonymous enums are plain integers
+                    Ok(rust_ident(strip_display_name(name)).unwrap_or("c_uint".to_string()))
+                }
+                kind => primitive(*kind)
+                    .map(|ty| ty.to_string())
+                    .ok_or(name.clone()),
+            },
+        }
+    }
+
+    /// Formats the ` -> T` suffix of a function signature.
+    pub(super) fn result_type(&mut self, ty: &Typ) -> Result<String, String> {
+        let result = self.rust_type(ty, TypeContext::Result)?;
+        if result.is_empty() {
+            Ok(result)
+        } else {
+            Ok(format!(" -> {}", result))
+        }
+    }
+}
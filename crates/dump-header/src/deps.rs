@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::framework::Framework;
+use crate::headerfiletree::{HeaderFile, HeaderFileTree};
+
+/// The `#include` graph of a header file or a framework. Nodes are header
+/// paths, or framework names when the graph is collapsed.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default)]
+pub struct IncludeGraph {
+    pub root: String,
+    pub nodes: Vec<String>,
+    pub edges: Vec<IncludeEdge>,
+    /// Strongly connected components with more than one node, or a node
+    /// including itself
+    pub cycles: Vec<Vec<String>>,
+    /// Headers that the root header does not include, directly or not
+    pub unreachable: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IncludeEdge {
+    pub from: String,
+    pub to: String,
+}
+
+impl IncludeGraph {
+    /// `headers` are the headers expected to be reachable from `root` in
+    /// addition to the given header files, e.g. all the headers of a framework.
+    pub fn from_header_files<'a>(
+        root: &Path,
+        header_files: impl IntoIterator<Item = &'a HeaderFile>,
+        headers: &[PathBuf],
+        collapse: bool,
+    ) -> Self {
+        let mut includes: BTreeMap<PathBuf, BTreeSet<PathBuf>> = BTreeMap::new();
+        header_files.into_iter().for_each(|header_file| {
+            let targets = includes.entry(header_file.path.clone()).or_default();
+            header_file
+                .get_include_directives()
+                .into_iter()
+                .for_each(|(_, path)| {
+                    targets.insert(path);
+                });
+        });
+
+        let reachable = reachable_from(root, &includes);
+        let unreachable: BTreeSet<String> = includes
+            .keys()
+            .chain(headers)
+            .filter(|path| !reachable.contains(*path))
+            .map(|path| path_name(path))
+            .collect();
+
+        let node_name = |path: &Path| {
+            if collapse {
+                framework_name(path).unwrap_or_else(|| path_name(path))
+            } else {
+                path_name(path)
+            }
+        };
+        let mut adjacency: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        includes.iter().for_each(|(from, targets)| {
+            let from = node_name(from);
+            adjacency.entry(from.clone()).or_default();
+            targets.iter().for_each(|to| {
+                let to = node_name(to);
+                adjacency.entry(to.clone()).or_default();
+                // a framework including its own headers
+                if !(collapse && from == to) {
+                    adjacency.get_mut(&from).unwrap().insert(to);
+                }
+            });
+        });
+
+        IncludeGraph {
+            root: node_name(root),
+            nodes: adjacency.keys().cloned().collect(),
+            edges: adjacency
+                .iter()
+                .flat_map(|(from, targets)| {
+                    targets.iter().map(|to| IncludeEdge {
+                        from: from.clone(),
+                        to: to.clone(),
+                    })
+                })
+                .collect(),
+            cycles: find_cycles(&adjacency),
+            unreachable: unreachable.into_iter().collect(),
+        }
+    }
+
+    pub fn from_header_file_tree(tree: &HeaderFileTree, collapse: bool) -> Self {
+        Self::from_header_files(tree.root_path(), tree.iter(), &[], collapse)
+    }
+
+    /// Also reports the headers in the directory of the umbrella header that
+    /// it does not include.
+    pub fn from_framework(framework: &Framework, collapse: bool) -> std::io::Result<Self> {
+        let headers = match framework.root_header().parent() {
+            Some(dir) => find_headers(dir)?,
+            None => vec![],
+        };
+        Ok(Self::from_header_files(
+            framework.root_header(),
+            framework.iter(),
+            &headers,
+            collapse,
+        ))
+    }
+
+    /// Renders the graph in Graphviz DOT. Nodes in a cycle are red and
+    /// unreachable headers dashed.
+    pub fn to_dot(&self) -> String {
+        let in_cycle: BTreeSet<&String> = self.cycles.iter().flatten().collect();
+        let mut dot = String::from("digraph includes {\n");
+        self.nodes
+            .iter()
+            .chain(
+                self.unreachable
+                    .iter()
+                    .filter(|node| !self.nodes.contains(node)),
+            )
+            .for_each(|node| {
+                let mut attributes = vec![];
+                if *node == self.root {
+                    attributes.push("shape=box");
+                }
+                if in_cycle.contains(node) {
+                    attributes.push("color=red");
+                }
+                if self.unreachable.contains(node) {
+                    attributes.push("style=dashed");
+                }
+                dot.push_str(&format!("    {}", dot_id(node)));
+                if !attributes.is_empty() {
+                    dot.push_str(&format!(" [{}]", attributes.join(", ")));
+                }
+                dot.push_str(";\n");
+            });
+        self.edges.iter().for_each(|edge| {
+            dot.push_str(&format!(
+                "    {} -> {};\n",
+                dot_id(&edge.from),
+                dot_id(&edge.to)
+            ));
+        });
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// `Foo` for `.../Foo.framework/Headers/Foo.h`.
+pub fn framework_name(path: &Path) -> Option<String> {
+    path.components()
+        .rev()
+        .find_map(|component| match component {
+            Component::Normal(name) => name.to_str()?.strip_suffix(".framework").map(String::from),
+            _ => None,
+        })
+}
+
+/// Lists the `*.h` files under `dir`, sorted.
+pub fn find_headers(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut headers = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            headers.extend(find_headers(&path)?);
+        } else if path.extension().map(|ext| ext == "h").unwrap_or(false) {
+            headers.push(path);
+        }
+    }
+    headers.sort();
+    Ok(headers)
+}
+
+fn path_name(path: &Path) -> String {
+    path.display().to_string()
+}
+
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn reachable_from(
+    root: &Path,
+    includes: &BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+) -> BTreeSet<PathBuf> {
+    let mut reachable = BTreeSet::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(path) = stack.pop() {
+        if reachable.contains(&path) {
+            continue;
+        }
+        if let Some(targets) = includes.get(&path) {
+            stack.extend(targets.iter().cloned());
+        }
+        reachable.insert(path);
+    }
+    reachable
+}
+
+// Tarjan's strongly connected components algorithm
+fn find_cycles(adjacency: &BTreeMap<String, BTreeSet<String>>) -> Vec<Vec<String>> {
+    struct State<'a> {
+        adjacency: &'a BTreeMap<String, BTreeSet<String>>,
+        index: BTreeMap<&'a str, usize>,
+        low_link: BTreeMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: BTreeSet<&'a str>,
+        cycles: Vec<Vec<String>>,
+    }
+
+    fn visit<'a>(state: &mut State<'a>, node: &'a str) {
+        let index = state.index.len();
+        state.index.insert(node, index);
+        state.low_link.insert(node, index);
+        state.stack.push(node);
+        state.on_stack.insert(node);
+        let adjacency = state.adjacency;
+        for target in adjacency.get(node).into_iter().flatten() {
+            let target = target.as_str();
+            if !state.index.contains_key(target) {
+                visit(state, target);
+                let low_link = state.low_link[node].min(state.low_link[target]);
+                state.low_link.insert(node, low_link);
+            } else if state.on_stack.contains(target) {
+                let low_link = state.low_link[node].min(state.index[target]);
+                state.low_link.insert(node, low_link);
+            }
+        }
+        if state.low_link[node] == state.index[node] {
+            let mut component = vec![];
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member.to_string());
+                if member == node {
+                    break;
+                }
+            }
+            let is_self_loop = adjacency
+                .get(node)
+                .map(|targets| targets.contains(node))
+                .unwrap_or(false);
+            if component.len() > 1 || is_self_loop {
+                component.sort();
+                state.cycles.push(component);
+            }
+        }
+    }
+
+    let mut state = State {
+        adjacency,
+        index: BTreeMap::new(),
+        low_link: BTreeMap::new(),
+        stack: vec![],
+        on_stack: BTreeSet::new(),
+        cycles: vec![],
+    };
+    adjacency.keys().for_each(|node| {
+        if !state.index.contains_key(node.as_str()) {
+            visit(&mut state, node);
+        }
+    });
+    state.cycles.sort();
+    state.cycles
+}
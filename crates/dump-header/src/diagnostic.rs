@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use clang::TranslationUnit;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::location::to_location;
+use crate::entity::{ConvertOptions, Location};
+use crate::error::Error;
+
+#[derive(
+    Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum Severity {
+    Note,
+    Warning,
+    Error,
+    Fatal,
+}
+
+impl Severity {
+    pub fn from(severity: clang::diagnostic::Severity) -> Option<Self> {
+        match severity {
+            clang::diagnostic::Severity::Ignored => None,
+            clang::diagnostic::Severity::Note => Some(Self::Note),
+            clang::diagnostic::Severity::Warning => Some(Self::Warning),
+            clang::diagnostic::Severity::Error => Some(Self::Error),
+            clang::diagnostic::Severity::Fatal => Some(Self::Fatal),
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Fatal => "fatal error",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub location: Option<Location>,
+    pub fix_its: Vec<FixIt>,
+}
+
+/// Replaces the `extent` of `location` with `replacement`. An insertion has
+/// an empty extent and a deletion an empty replacement.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct FixIt {
+    pub location: Option<Location>,
+    pub replacement: String,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity >= Severity::Error
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{}: ", location)?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+/// Collects the diagnostics of `tu` reported in the file `path`, or all of
+/// them if `path` is `None`.
+pub fn get_diagnostics(
+    tu: &TranslationUnit,
+    path: Option<&Path>,
+    options: &ConvertOptions,
+) -> Vec<Diagnostic> {
+    let root = options.location_root.as_deref();
+    tu.get_diagnostics()
+        .iter()
+        .filter(|diagnostic| {
+            path.map(|path| {
+                let (file, _, _) = diagnostic.get_location().get_presumed_location();
+                Path::new(&file) == path
+            })
+            .unwrap_or(true)
+        })
+        .filter_map(|diagnostic| {
+            let severity = Severity::from(diagnostic.get_severity())?;
+            let fix_its = diagnostic
+                .get_fix_its()
+                .into_iter()
+                .map(|fix_it| match fix_it {
+                    clang::diagnostic::FixIt::Deletion(range) => FixIt {
+                        location: to_location(range.get_start(), Some(range), root),
+                        replacement: String::new(),
+                    },
+                    clang::diagnostic::FixIt::Insertion(location, text) => FixIt {
+                        location: to_location(
+                            location,
+                            Some(clang::source::SourceRange::new(location, location)),
+                            root,
+                        ),
+                        replacement: text,
+                    },
+                    clang::diagnostic::FixIt::Replacement(range, text) => FixIt {
+                        location: to_location(range.get_start(), Some(range), root),
+                        replacement: text,
+                    },
+                })
+                .collect();
+            Some(Diagnostic {
+                severity,
+                message: diagnostic.get_text(),
+                location: to_location(
+                    diagnostic.get_location(),
+                    diagnostic.get_ranges().first().copied(),
+                    root,
+                ),
+                fix_its,
+            })
+        })
+        .collect()
+}
+
+/// Fails with `Error::Diagnostics` if any of `diagnostics` is an error.
+pub fn ensure_no_errors(diagnostics: &[Diagnostic]) -> Result<(), Error> {
+    let errors: Vec<Diagnostic> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        .cloned()
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Diagnostics(errors))
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::Format;
+use crate::entity::{Entry, ParmDecl, PlatformAvailability, Version};
+use crate::error::Error;
+use crate::format;
+use crate::item::{self, type_name, Item, ItemSource};
+use crate::typ::Typ;
+
+pub use crate::item::ItemKind;
+
+#[derive(
+    Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// The type, signature or value changed
+    Changed,
+    /// `availability` or `platform_availability` changed
+    AvailabilityChanged,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct ApiChange {
+    pub change: ChangeKind,
+    pub kind: ItemKind,
+    /// e.g. `point.x` for a field or `NSString -length` for a method
+    pub name: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    pub breaking: bool,
+}
+
+/// The API changes between two dumps, sorted by name.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default)]
+pub struct ApiDiff {
+    pub changes: Vec<ApiChange>,
+}
+
+impl ApiDiff {
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes.iter().any(|change| change.breaking)
+    }
+
+    /// One line per change, `+` added, `-` removed and `~` changed, with `!`
+    /// marking breaking changes.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        self.changes.iter().for_each(|change| {
+            let sign = match change.change {
+                ChangeKind::Added => '+',
+                ChangeKind::Removed => '-',
+                ChangeKind::Changed | ChangeKind::AvailabilityChanged => '~',
+            };
+            let breaking = if change.breaking { "!" } else { " " };
+            let kind = serde_json::to_value(change.kind)
+                .ok()
+                .and_then(|kind| kind.as_str().map(|s| s.replace('_', " ")))
+                .unwrap_or_default();
+            let detail = match (&change.old, &change.new) {
+                (Some(old), Some(new)) => format!(": {} -> {}", old, new),
+                (Some(value), None) | (None, Some(value)) if !value.is_empty() => {
+                    format!(": {}", value)
+                }
+                _ => String::new(),
+            };
+            text.push_str(&format!(
+                "{}{} {} {}{}\n",
+                sign, breaking, kind, change.name, detail
+            ));
+        });
+        text
+    }
+}
+
+// A declaration as compared by `diff`
+#[derive(Debug, Clone)]
+struct ApiItem {
+    signature: String,
+    availability: clang::Availability,
+    platform_availability: Vec<PlatformAvailability>,
+}
+
+/// Compares the declarations of two dumps.
+pub fn diff(old: &[Entry], new: &[Entry]) -> ApiDiff {
+    let old_items = collect_items(old);
+    let new_items = collect_items(new);
+    let mut changes = vec![];
+    old_items.iter().for_each(|(key, old_item)| {
+        let (kind, name) = key.clone();
+        let Some(new_item) = new_items.get(key) else {
+            changes.push(ApiChange {
+                change: ChangeKind::Removed,
+                kind,
+                name,
+                old: Some(old_item.signature.clone()),
+                new: None,
+                breaking: true,
+            });
+            return;
+        };
+        if old_item.signature != new_item.signature {
+            changes.push(ApiChange {
+                change: ChangeKind::Changed,
+                kind,
+                name: name.clone(),
+                old: Some(old_item.signature.clone()),
+                new: Some(new_item.signature.clone()),
+                breaking: true,
+            });
+        }
+        let old_availability = availability_text(old_item);
+        let new_availability = availability_text(new_item);
+        if old_availability != new_availability {
+            changes.push(ApiChange {
+                change: ChangeKind::AvailabilityChanged,
+                kind,
+                name,
+                old: Some(old_availability),
+                new: Some(new_availability),
+                breaking: is_availability_breaking(old_item, new_item),
+            });
+        }
+    });
+    new_items.iter().for_each(|(key, new_item)| {
+        if !old_items.contains_key(key) {
+            let (kind, name) = key.clone();
+            changes.push(ApiChange {
+                change: ChangeKind::Added,
+                kind,
+                name,
+                old: None,
+                new: Some(new_item.signature.clone()),
+                // changes the layout of the record
+                breaking: kind == ItemKind::Field,
+            });
+        }
+    });
+    changes.sort_by(|a, b| (&a.name, a.kind, a.change).cmp(&(&b.name, b.kind, b.change)));
+    ApiDiff { changes }
+}
+
+/// Loads the entries of a dump of a header file, a framework or a list of
+/// either, the header files of a framework in the order of their paths.
+pub fn load_entries(path: &Path, format: Format) -> Result<Vec<Entry>, Error> {
+    Ok(format::load_header_files(path, format)?
+        .into_iter()
+        .flat_map(|header_file| header_file.entries)
+        .collect())
+}
+
+type Items = BTreeMap<(ItemKind, String), ApiItem>;
+
+fn collect_items(entries: &[Entry]) -> Items {
+    let mut items = Items::new();
+    item::collect_items(entries).iter().for_each(|item| {
+        let platform_availability = match item.source {
+            // changes of the platforms of the parent are reported on the parent
+            ItemSource::Field { .. }
+            | ItemSource::EnumConstant(_)
+            | ItemSource::CxxField { .. } => {
+                vec![]
+            }
+            _ => item.platform_availability.to_vec(),
+        };
+        // redeclarations keep the first declaration
+        items
+            .entry((item.kind, item.name.clone()))
+            .or_insert_with(|| ApiItem {
+                signature: signature(item),
+                availability: item.availability,
+                platform_availability,
+            });
+    });
+    items
+}
+
+fn signature(item: &Item) -> String {
+    match item.source {
+        ItemSource::Entry(entry) => match entry {
+            Entry::FunctionDecl {
+                arguments,
+                result_type,
+                ..
+            } => function_signature(result_type, arguments),
+            Entry::VarDecl { ty, .. }
+            | Entry::TypedefDecl { ty, .. }
+            | Entry::TypeAliasDecl { ty, .. }
+            | Entry::EnumDecl { ty, .. } => type_name(ty).to_string(),
+            Entry::MacroDefinition { tokens, .. } => tokens.clone(),
+            Entry::ClassDecl { bases, .. } => {
+                let bases: Vec<&str> = bases.iter().map(|base| base.name.as_str()).collect();
+                bases.join(", ")
+            }
+            Entry::ObjCInterfaceDecl {
+                superclass,
+                protocols,
+                ..
+            } => {
+                let mut signature = superclass.clone();
+                if !protocols.is_empty() {
+                    signature.push_str(&format!(" <{}>", protocols.join(", ")));
+                }
+                signature
+            }
+            _ => String::new(),
+        },
+        ItemSource::Field { index, .. } => {
+            let ty = item.types.first().map_or("<anonymous>", |ty| type_name(ty));
+            format!("{} at {}", ty, index)
+        }
+        ItemSource::EnumConstant(decl) => decl.value.clone().unwrap_or_default(),
+        ItemSource::CxxField { field, index } => {
+            format!("{} at {}", type_name(&field.ty), index)
+        }
+        ItemSource::CxxMethod(method) => function_signature(&method.result_type, &method.arguments),
+        ItemSource::ObjCProperty(property) => {
+            let mut signature = type_name(&property.objc_type).to_string();
+            if property.optional {
+                signature = format!("@optional {}", signature);
+            }
+            if let Some(attributes) = &property.attributes {
+                if attributes.readonly {
+                    signature.push_str(" readonly");
+                }
+            }
+            signature
+        }
+        ItemSource::ObjCMethod { method, .. } => {
+            let mut signature = function_signature(&method.result_type, &method.arguments);
+            if method.optional {
+                signature = format!("@optional {}", signature);
+            }
+            signature
+        }
+    }
+}
+
+fn function_signature(result_type: &Typ, arguments: &[ParmDecl]) -> String {
+    let arguments: Vec<&str> = arguments
+        .iter()
+        .map(|arg| type_name(&arg.objc_type))
+        .collect();
+    format!("{} ({})", type_name(result_type), arguments.join(", "))
+}
+
+fn version_text(version: &Version) -> String {
+    let mut text = version.x.to_string();
+    if let Some(y) = version.y {
+        text.push_str(&format!(".{}", y));
+        if let Some(z) = version.z {
+            text.push_str(&format!(".{}", z));
+        }
+    }
+    text
+}
+
+fn availability_text(item: &ApiItem) -> String {
+    let mut text = format!("{:?}", item.availability);
+    item.platform_availability.iter().for_each(|availability| {
+        let mut parts = vec![];
+        if let Some(version) = &availability.introduced {
+            parts.push(format!("introduced {}", version_text(version)));
+        }
+        if let Some(version) = &availability.deprecated {
+            parts.push(format!("deprecated {}", version_text(version)));
+        }
+        if let Some(version) = &availability.obsoleted {
+            parts.push(format!("obsoleted {}", version_text(version)));
+        }
+        if availability.unavailable {
+            parts.push("unavailable".to_string());
+        }
+        text.push_str(&format!("; {} {}", availability.platform, parts.join(", ")));
+    });
+    text
+}
+
+// Whether the declaration can no longer be used where it could be.
+fn is_availability_breaking(old: &ApiItem, new: &ApiItem) -> bool {
+    let is_usable = |availability: clang::Availability| {
+        matches!(
+            availability,
+            clang::Availability::Available | clang::Availability::Deprecated
+        )
+    };
+    if is_usable(old.availability) && !is_usable(new.availability) {
+        return true;
+    }
+    new.platform_availability.iter().any(|new_platform| {
+        let old_platform = old
+            .platform_availability
+            .iter()
+            .find(|old_platform| old_platform.platform == new_platform.platform);
+        let was_unavailable = old_platform
+            .map(|p| p.unavailable || p.obsoleted.is_some())
+            .unwrap_or(false);
+        (new_platform.unavailable || new_platform.obsoleted.is_some()) && !was_unavailable
+    })
+}
@@ -1,6 +1,7 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct ObjCAttributes {
     pub readonly: bool,
     pub getter: bool,
@@ -34,3 +35,49 @@ impl ObjCAttributes {
         }
     }   
 }
+
+/// The spellings of the tokens of an attribute not exposed by libclang, e.g.
+/// `swift_name ( "foo()" )`, or of the macro expanding to it, e.g.
+/// `NS_SWIFT_NAME ( foo ( ) )`.
+pub(crate) fn attribute_tokens(attribute: &clang::Entity) -> Vec<String> {
+    attribute
+        .get_range()
+        .map(|range| {
+            range
+                .tokenize()
+                .iter()
+                .map(|token| token.get_spelling())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The tokens between the parentheses following the first of `names` in
+/// `tokens`, e.g. `["foo", "(", ")"]` for `NS_SWIFT_NAME` in
+/// `NS_SWIFT_NAME ( foo ( ) )`.
+pub(crate) fn attribute_arguments<'a>(
+    tokens: &'a [String],
+    names: &[&str],
+) -> Option<&'a [String]> {
+    let start = tokens
+        .iter()
+        .position(|token| names.contains(&token.as_str()))?
+        + 1;
+    if tokens.get(start).map(|token| token.as_str()) != Some("(") {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token.as_str() {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&tokens[start + 1..i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -1,14 +1,15 @@
 use clang::Availability;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Version {
     pub x: u32,
     pub y: Option<u32>,
     pub z: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct PlatformAvailability {
     pub platform: String,
     pub unavailable: bool,
@@ -41,7 +42,7 @@ impl PlatformAvailability {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(remote = "Availability")]
 pub enum AvailabilityDef {
     Available = 0,
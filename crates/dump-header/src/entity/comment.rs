@@ -0,0 +1,126 @@
+use clang::documentation::{CommentChild, ParameterDirection};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ConvertOptions;
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default)]
+pub struct Comment {
+    pub raw: String,
+    pub brief: Option<String>,
+    pub paragraphs: Vec<String>,
+    pub params: Vec<ParamComment>,
+    pub template_params: Vec<ParamComment>,
+    pub returns: Option<String>,
+    pub notes: Vec<String>,
+    pub deprecated: Option<String>,
+    /// Block commands without a dedicated field, e.g. `@see` or `@warning`
+    pub commands: Vec<BlockCommandComment>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct ParamComment {
+    pub name: String,
+    /// `in`, `out` or `in,out`
+    pub direction: Option<String>,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct BlockCommandComment {
+    pub command: String,
+    pub arguments: Vec<String>,
+    pub text: String,
+}
+
+pub fn get_comment(entity: &clang::Entity, options: &ConvertOptions) -> Option<Box<Comment>> {
+    if !options.comments {
+        return None;
+    }
+    let raw = entity.get_comment()?;
+    let mut comment = Comment {
+        raw,
+        brief: entity.get_comment_brief().filter(|brief| !brief.is_empty()),
+        ..Default::default()
+    };
+    let children = entity
+        .get_parsed_comment()
+        .map(|c| c.get_children())
+        .unwrap_or_default();
+    children.iter().for_each(|child| match child {
+        CommentChild::Paragraph(_) | CommentChild::Text(_) => {
+            let text = get_text(std::slice::from_ref(child));
+            if !text.is_empty() {
+                comment.paragraphs.push(text);
+            }
+        }
+        CommentChild::BlockCommand(command) => {
+            let text = get_text(&command.children);
+            match command.command.as_str() {
+                // already in `brief`
+                "brief" | "abstract" | "short" => {}
+                "return" | "returns" | "result" => comment.returns = Some(text),
+                "note" | "remark" | "remarks" => comment.notes.push(text),
+                "deprecated" => comment.deprecated = Some(text),
+                _ => comment.commands.push(BlockCommandComment {
+                    command: command.command.clone(),
+                    arguments: command.arguments.clone(),
+                    text,
+                }),
+            }
+        }
+        CommentChild::ParamCommand(param) => comment.params.push(ParamComment {
+            name: param.parameter.clone(),
+            direction: param.direction.map(|direction| {
+                match direction {
+                    ParameterDirection::In => "in",
+                    ParameterDirection::Out => "out",
+                    ParameterDirection::InOut => "in,out",
+                }
+                .to_string()
+            }),
+            text: get_text(&param.children),
+        }),
+        CommentChild::TParamCommand(param) => comment.template_params.push(ParamComment {
+            name: param.parameter.clone(),
+            direction: None,
+            text: get_text(&param.children),
+        }),
+        CommentChild::VerbatimCommand(lines) => comment.paragraphs.push(lines.join("\n")),
+        CommentChild::VerbatimLineCommand(line) => comment.paragraphs.push(line.trim().to_string()),
+        _ => {}
+    });
+    Some(Box::new(comment))
+}
+
+// clang splits text into one `Text` per line.
+fn get_text(children: &[CommentChild]) -> String {
+    let mut text = String::new();
+    let mut after_text = false;
+    children.iter().for_each(|child| match child {
+        CommentChild::Text(s) => {
+            if after_text {
+                text.push(' ');
+            }
+            text.push_str(s);
+            after_text = true;
+        }
+        CommentChild::InlineCommand(command) => {
+            text.push_str(&command.arguments.join(" "));
+            after_text = false;
+        }
+        CommentChild::Paragraph(children) => {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&get_text(children));
+            after_text = false;
+        }
+        _ => {}
+    });
+    text.split('\n')
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,142 @@
+use super::availability::get_platform_availability;
+use super::comment::get_comment;
+use super::entry::{
+    Access, BaseSpecifier, CXXFieldDecl, CXXMethodDecl, Entry, TemplateTypeParameter,
+};
+use super::location::get_location;
+use super::report::{required, ConversionReport};
+use super::{convert_entities_with_report, get_arguments, try_convert_entity, ConvertOptions};
+use crate::typ::Typ;
+
+pub fn get_namespace(
+    entity: &clang::Entity,
+    options: &ConvertOptions,
+    report: &mut ConversionReport,
+) -> Entry {
+    Entry::Namespace {
+        name: entity.get_name(),
+        is_inline: entity.is_inline_namespace(),
+        entries: convert_entities_with_report(&entity.get_children(), options, report),
+        comment: get_comment(entity, options),
+        location: get_location(entity, options),
+    }
+}
+
+/// Whether a `struct` needs `Entry::ClassDecl` rather than `Entry::StructDecl`.
+pub fn has_cxx_members(entity: &clang::Entity) -> bool {
+    entity.get_children().iter().any(|e| {
+        matches!(
+            e.get_kind(),
+            clang::EntityKind::Method
+                | clang::EntityKind::Constructor
+                | clang::EntityKind::Destructor
+                | clang::EntityKind::ConversionFunction
+                | clang::EntityKind::FunctionTemplate
+                | clang::EntityKind::BaseSpecifier
+        )
+    })
+}
+
+/// Converts a C++ class, leaving out and recording in `report` the members
+/// failing to convert.
+pub fn get_class_decl(
+    entity: &clang::Entity,
+    options: &ConvertOptions,
+    report: &mut ConversionReport,
+) -> Result<Entry, String> {
+    let kind = match entity.get_kind() {
+        clang::EntityKind::ClassTemplate => entity.get_template_kind(),
+        kind => Some(kind),
+    };
+    let mut template_args: Vec<TemplateTypeParameter> = vec![];
+    let mut bases: Vec<BaseSpecifier> = vec![];
+    let mut fields: Vec<CXXFieldDecl> = vec![];
+    let mut constructors: Vec<CXXMethodDecl> = vec![];
+    let mut destructor: Option<CXXMethodDecl> = None;
+    let mut methods: Vec<CXXMethodDecl> = vec![];
+    let mut entries: Vec<Entry> = vec![];
+    for e in entity.get_children() {
+        let member = match e.get_kind() {
+            clang::EntityKind::TemplateTypeParameter => {
+                template_args.push(TemplateTypeParameter {
+                    name: e.get_name().unwrap_or_default(),
+                    constraint: None,
+                });
+                Ok(())
+            }
+            clang::EntityKind::BaseSpecifier => {
+                bases.push(BaseSpecifier {
+                    name: e
+                        .get_type()
+                        .map(|t| t.get_display_name())
+                        .or(e.get_name())
+                        .unwrap_or_default(),
+                    access: e.get_accessibility().map(Access::from),
+                    is_virtual: e.is_virtual_base(),
+                });
+                Ok(())
+            }
+            clang::EntityKind::FieldDecl => required(e.get_type(), "type").map(|ty| {
+                fields.push(CXXFieldDecl {
+                    name: e.get_name().unwrap_or_default(),
+                    ty: Typ::from(ty),
+                    access: e.get_accessibility().map(Access::from),
+                    is_mutable: e.is_mutable(),
+                    comment: get_comment(&e, options),
+                    location: get_location(&e, options),
+                })
+            }),
+            clang::EntityKind::Constructor => {
+                get_method(&e, options).map(|method| constructors.push(method))
+            }
+            clang::EntityKind::Destructor => {
+                get_method(&e, options).map(|method| destructor = Some(method))
+            }
+            clang::EntityKind::Method | clang::EntityKind::ConversionFunction => {
+                get_method(&e, options).map(|method| methods.push(method))
+            }
+            clang::EntityKind::AccessSpecifier => Ok(()),
+            _ => try_convert_entity(&e, options, report).map(|entry| entries.extend(entry)),
+        };
+        if let Err(reason) = member {
+            report.skip(&e, reason, options);
+        }
+    }
+    Ok(Entry::ClassDecl {
+        name: if !entity.is_anonymous_record_decl() {
+            entity.get_name()
+        } else {
+            None
+        },
+        is_struct: kind == Some(clang::EntityKind::StructDecl),
+        template_args,
+        bases,
+        fields,
+        constructors,
+        destructor,
+        methods,
+        entries,
+        is_abstract: entity.is_abstract_record(),
+        platform_availability: get_platform_availability(entity),
+        availability: entity.get_availability(),
+        comment: get_comment(entity, options),
+        location: get_location(entity, options),
+    })
+}
+
+fn get_method(entity: &clang::Entity, options: &ConvertOptions) -> Result<CXXMethodDecl, String> {
+    Ok(CXXMethodDecl {
+        name: entity.get_name().unwrap_or_default(),
+        access: entity.get_accessibility().map(Access::from),
+        arguments: get_arguments(entity)?,
+        result_type: Typ::from(required(entity.get_result_type(), "result type")?),
+        is_static: entity.is_static_method(),
+        is_virtual: entity.is_virtual_method(),
+        is_pure_virtual: entity.is_pure_virtual_method(),
+        is_const: entity.is_const_method(),
+        platform_availability: get_platform_availability(entity),
+        availability: entity.get_availability(),
+        comment: get_comment(entity, options),
+        location: get_location(entity, options),
+    })
+}
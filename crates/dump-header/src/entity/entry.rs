@@ -1,17 +1,41 @@
 use std::path::PathBuf;
 
 use super::attributes::ObjCAttributes;
-use crate::typ::Typ;
+use super::comment::Comment;
+use super::enumdecl::{EnumExtensibility, EnumMacro};
+use super::function::{CallingConvention, FormatAttribute, StorageClass, Visibility};
+use super::location::Location;
+use super::objc::{MethodFamily, ReturnOwnership};
+use super::swift::SwiftAttribute;
+use crate::apinotes::ApiNote;
+use crate::typ::{FieldLayout, Nullability, RecordLayout, Typ};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::availability::{AvailabilityDef, PlatformAvailability};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(tag = "kind")]
 pub enum Entry {
     InclusionDirective {
         name: String,
         path: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+    },
+    MacroDefinition {
+        name: String,
+        /// `None` for object-like macros
+        parameters: Option<Vec<String>>,
+        tokens: String,
+        /// The value of an object-like macro, if clang can evaluate it
+        value: Option<InitValue>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
     },
     TypedefDecl {
         name: String,
@@ -19,51 +43,195 @@ pub enum Entry {
         platform_availability: Option<Vec<PlatformAvailability>>,
         #[serde(with = "AvailabilityDef")]
         availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swift_attributes: Vec<SwiftAttribute>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_notes: Vec<ApiNote>,
     },
     EnumDecl {
         decls: Vec<EnumConstantDecl>,
+        /// A placeholder, e.g. `(anonymous enum at t.h:3:1)`, for an
+        /// anonymous enum
         name: String,
+        #[serde(default)]
+        is_anonymous: bool,
+        /// The typedef naming the enum, e.g. `Foo` for `typedef enum { ... } Foo;`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        typedef_name: Option<String>,
         ty: Typ,
+        #[serde(default)]
+        is_scoped: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enum_macro: Option<EnumMacro>,
+        /// From `enum_extensibility` or implied by `enum_macro`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        extensibility: Option<EnumExtensibility>,
+        /// From `flag_enum` or an options macro: the constants are bit flags
+        #[serde(default)]
+        is_flag_enum: bool,
         platform_availability: Option<Vec<PlatformAvailability>>,
         #[serde(with = "AvailabilityDef")]
         availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swift_attributes: Vec<SwiftAttribute>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_notes: Vec<ApiNote>,
     },
     VarDecl {
         name: String,
         ty: Typ,
         init_expr: Option<InitExpr>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        storage_class: Option<StorageClass>,
+        /// `_Thread_local` or `__thread`
+        #[serde(default)]
+        is_thread_local: bool,
         platform_availability: Option<Vec<PlatformAvailability>>,
         #[serde(with = "AvailabilityDef")]
         availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swift_attributes: Vec<SwiftAttribute>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_notes: Vec<ApiNote>,
     },
     StructDecl {
         name: Option<String>,
         fields: Vec<Entry>,
         ty: Typ,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        layout: Option<RecordLayout>,
         platform_availability: Option<Vec<PlatformAvailability>>,
         #[serde(with = "AvailabilityDef")]
         availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swift_attributes: Vec<SwiftAttribute>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_notes: Vec<ApiNote>,
     },
     UnionDecl {
         name: Option<String>,
         fields: Vec<Entry>,
         ty: Typ,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        layout: Option<RecordLayout>,
         platform_availability: Option<Vec<PlatformAvailability>>,
         #[serde(with = "AvailabilityDef")]
         availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swift_attributes: Vec<SwiftAttribute>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_notes: Vec<ApiNote>,
     },
     FieldDecl {
         name: String,
         ty: Typ,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        layout: Option<FieldLayout>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
     },
     FunctionDecl {
         name: String,
         ty: Typ,
         arguments: Vec<ParmDecl>,
         result_type: Typ,
+        #[serde(default)]
+        is_variadic: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        storage_class: Option<StorageClass>,
+        #[serde(default)]
+        is_inline: bool,
+        /// `_Noreturn` or `__attribute__((noreturn))`
+        #[serde(default)]
+        is_noreturn: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        visibility: Option<Visibility>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        calling_convention: Option<CallingConvention>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        format_attributes: Vec<FormatAttribute>,
+        /// From `CF_RETURNS_RETAINED` and the like
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        return_ownership: Option<ReturnOwnership>,
+        #[serde(default)]
+        warn_unused_result: bool,
+        /// The 1-based indices of the arguments of `nonnull(...)`, empty
+        /// for `nonnull` applying to all the pointer arguments
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        nonnull: Option<Vec<usize>>,
         platform_availability: Option<Vec<PlatformAvailability>>,
         #[serde(with = "AvailabilityDef")]
         availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swift_attributes: Vec<SwiftAttribute>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_notes: Vec<ApiNote>,
+    },
+    Namespace {
+        name: Option<String>,
+        is_inline: bool,
+        entries: Vec<Entry>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+    },
+    ClassDecl {
+        name: Option<String>,
+        is_struct: bool,
+        template_args: Vec<TemplateTypeParameter>,
+        bases: Vec<BaseSpecifier>,
+        fields: Vec<CXXFieldDecl>,
+        constructors: Vec<CXXMethodDecl>,
+        destructor: Option<CXXMethodDecl>,
+        methods: Vec<CXXMethodDecl>,
+        /// Nested types, static data members and aliases
+        entries: Vec<Entry>,
+        is_abstract: bool,
+        platform_availability: Option<Vec<PlatformAvailability>>,
+        #[serde(with = "AvailabilityDef")]
+        availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+    },
+    TypeAliasDecl {
+        name: String,
+        ty: Typ,
+        platform_availability: Option<Vec<PlatformAvailability>>,
+        #[serde(with = "AvailabilityDef")]
+        availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
     },
     ObjCInterfaceDecl {
         name: String,
@@ -76,36 +244,196 @@ pub enum Entry {
         platform_availability: Option<Vec<PlatformAvailability>>,
         #[serde(with = "AvailabilityDef")]
         availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swift_attributes: Vec<SwiftAttribute>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_notes: Vec<ApiNote>,
     },
     ObjCCategoryDecl {
         name: Option<String>,
         class_name: String,
+        /// The adopted protocols
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        protocols: Vec<String>,
         properties: Vec<ObjCPropertyDecl>,
         instance_methods: Vec<ObjCMethodDecl>,
         class_methods: Vec<ObjCMethodDecl>,
         platform_availability: Option<Vec<PlatformAvailability>>,
         #[serde(with = "AvailabilityDef")]
         availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swift_attributes: Vec<SwiftAttribute>,
     },
     ObjCProtocolDecl {
         name: String,
+        /// The inherited protocols
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        protocols: Vec<String>,
         properties: Vec<ObjCPropertyDecl>,
         instance_methods: Vec<ObjCMethodDecl>,
         class_methods: Vec<ObjCMethodDecl>,
         platform_availability: Option<Vec<PlatformAvailability>>,
         #[serde(with = "AvailabilityDef")]
         availability: clang::Availability,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<Box<Comment>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        location: Option<Location>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swift_attributes: Vec<SwiftAttribute>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_notes: Vec<ApiNote>,
     },
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl Entry {
+    pub fn comment(&self) -> Option<&Comment> {
+        match self {
+            Entry::InclusionDirective { comment, .. }
+            | Entry::MacroDefinition { comment, .. }
+            | Entry::TypedefDecl { comment, .. }
+            | Entry::EnumDecl { comment, .. }
+            | Entry::VarDecl { comment, .. }
+            | Entry::StructDecl { comment, .. }
+            | Entry::UnionDecl { comment, .. }
+            | Entry::FieldDecl { comment, .. }
+            | Entry::FunctionDecl { comment, .. }
+            | Entry::Namespace { comment, .. }
+            | Entry::ClassDecl { comment, .. }
+            | Entry::TypeAliasDecl { comment, .. }
+            | Entry::ObjCInterfaceDecl { comment, .. }
+            | Entry::ObjCCategoryDecl { comment, .. }
+            | Entry::ObjCProtocolDecl { comment, .. } => comment.as_deref(),
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Entry::InclusionDirective { location, .. }
+            | Entry::MacroDefinition { location, .. }
+            | Entry::TypedefDecl { location, .. }
+            | Entry::EnumDecl { location, .. }
+            | Entry::VarDecl { location, .. }
+            | Entry::StructDecl { location, .. }
+            | Entry::UnionDecl { location, .. }
+            | Entry::FieldDecl { location, .. }
+            | Entry::FunctionDecl { location, .. }
+            | Entry::Namespace { location, .. }
+            | Entry::ClassDecl { location, .. }
+            | Entry::TypeAliasDecl { location, .. }
+            | Entry::ObjCInterfaceDecl { location, .. }
+            | Entry::ObjCCategoryDecl { location, .. }
+            | Entry::ObjCProtocolDecl { location, .. } => location.as_ref(),
+        }
+    }
+
+    /// Calls `f` on every type of this entry and of its nested entries.
+    pub fn for_each_type_mut(&mut self, f: &mut dyn FnMut(&mut Typ)) {
+        match self {
+            Entry::InclusionDirective { .. } | Entry::MacroDefinition { .. } => {}
+            Entry::TypedefDecl { ty, .. }
+            | Entry::VarDecl { ty, .. }
+            | Entry::FieldDecl { ty, .. }
+            | Entry::TypeAliasDecl { ty, .. } => f(ty),
+            Entry::EnumDecl { decls, ty, .. } => {
+                f(ty);
+                decls.iter_mut().for_each(|decl| f(&mut decl.objc_type));
+            }
+            Entry::StructDecl { fields, ty, .. } | Entry::UnionDecl { fields, ty, .. } => {
+                f(ty);
+                fields
+                    .iter_mut()
+                    .for_each(|field| field.for_each_type_mut(f));
+            }
+            Entry::FunctionDecl {
+                ty,
+                arguments,
+                result_type,
+                ..
+            } => {
+                f(ty);
+                arguments.iter_mut().for_each(|arg| f(&mut arg.objc_type));
+                f(result_type);
+            }
+            Entry::Namespace { entries, .. } => entries
+                .iter_mut()
+                .for_each(|entry| entry.for_each_type_mut(f)),
+            Entry::ClassDecl {
+                fields,
+                constructors,
+                destructor,
+                methods,
+                entries,
+                ..
+            } => {
+                fields.iter_mut().for_each(|field| f(&mut field.ty));
+                constructors
+                    .iter_mut()
+                    .chain(destructor.iter_mut())
+                    .chain(methods.iter_mut())
+                    .for_each(|method| {
+                        method
+                            .arguments
+                            .iter_mut()
+                            .for_each(|arg| f(&mut arg.objc_type));
+                        f(&mut method.result_type);
+                    });
+                entries
+                    .iter_mut()
+                    .for_each(|entry| entry.for_each_type_mut(f));
+            }
+            Entry::ObjCInterfaceDecl {
+                properties,
+                instance_methods,
+                class_methods,
+                ..
+            }
+            | Entry::ObjCCategoryDecl {
+                properties,
+                instance_methods,
+                class_methods,
+                ..
+            }
+            | Entry::ObjCProtocolDecl {
+                properties,
+                instance_methods,
+                class_methods,
+                ..
+            } => {
+                properties
+                    .iter_mut()
+                    .for_each(|property| f(&mut property.objc_type));
+                instance_methods
+                    .iter_mut()
+                    .chain(class_methods.iter_mut())
+                    .for_each(|method| {
+                        method
+                            .arguments
+                            .iter_mut()
+                            .for_each(|arg| f(&mut arg.objc_type));
+                        f(&mut method.result_type);
+                    });
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(tag = "kind")]
 pub enum InitExpr {
     Value(InitValue),
     InitListExpr(InitListExpr),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub enum InitValue {
     Int(i64),
     UInt(u64),
@@ -117,23 +445,74 @@ pub enum InitValue {
     Null,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct InitListExpr {
     pub values: Vec<InitValue>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct ObjCMethodDecl {
+    /// The selector, e.g. `initWithFoo:bar:`
     pub name: String,
+    /// The selector split at the colons, e.g. `["initWithFoo", "bar"]`
+    #[serde(default)]
+    pub selector_pieces: Vec<String>,
     pub arguments: Vec<ParmDecl>,
+    #[serde(default)]
+    pub is_variadic: bool,
     pub result_type: Typ,
+    #[serde(default)]
+    pub returns_instancetype: bool,
     pub optional: bool,
+    /// From the selector or `objc_method_family`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<MethodFamily>,
+    /// From `NS_RETURNS_RETAINED` and the like
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_ownership: Option<ReturnOwnership>,
+    /// `NS_DESIGNATED_INITIALIZER`
+    #[serde(default)]
+    pub is_designated_initializer: bool,
+    /// `objc_direct`: called directly rather than through `objc_msgSend`
+    #[serde(default)]
+    pub is_direct: bool,
+    /// From `NS_SWIFT_NAME`, e.g. `init(foo:bar:)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swift_name: Option<String>,
     pub platform_availability: Option<Vec<PlatformAvailability>>,
     #[serde(with = "AvailabilityDef")]
     pub availability: clang::Availability,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<Box<Comment>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub swift_attributes: Vec<SwiftAttribute>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_notes: Vec<ApiNote>,
+}
+
+impl ObjCMethodDecl {
+    /// Replaces the Swift name, both `swift_name` and the `swift_name` entry
+    /// of `swift_attributes`, e.g. with the `SwiftName` of the API notes.
+    pub fn set_swift_name(&mut self, swift_name: &str) {
+        self.swift_name = Some(swift_name.to_string());
+        let arguments = vec![swift_name.to_string()];
+        match self
+            .swift_attributes
+            .iter_mut()
+            .find(|attribute| attribute.name == "swift_name")
+        {
+            Some(attribute) => attribute.arguments = arguments,
+            None => self.swift_attributes.push(SwiftAttribute {
+                name: "swift_name".to_string(),
+                arguments,
+            }),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct ObjCPropertyDecl {
     pub name: String,
     pub objc_type: Typ,
@@ -142,29 +521,113 @@ pub struct ObjCPropertyDecl {
     pub platform_availability: Option<Vec<PlatformAvailability>>,
     #[serde(with = "AvailabilityDef")]
     pub availability: clang::Availability,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<Box<Comment>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub swift_attributes: Vec<SwiftAttribute>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_notes: Vec<ApiNote>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct ParmDecl {
     pub name: Option<String>,
     pub objc_type: Typ,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nullability: Option<Nullability>,
+    /// `NS_NOESCAPE`: the argument does not outlive the call
+    #[serde(default)]
+    pub is_noescape: bool,
+    /// `NS_RELEASES_ARGUMENT`: the callee takes ownership of the argument
+    #[serde(default)]
+    pub is_ns_consumed: bool,
+    /// `CF_CONSUMED`: the callee takes ownership of the argument
+    #[serde(default)]
+    pub is_cf_consumed: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_notes: Vec<ApiNote>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct TemplateTypeParameter {
     pub name: String,
     pub constraint: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct EnumConstantDecl {
     pub name: String,
     pub value: Option<String>,
     pub objc_type: Typ,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<Box<Comment>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub swift_attributes: Vec<SwiftAttribute>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_notes: Vec<ApiNote>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct FieldDecl {
     pub name: String,
     pub objc_type: Typ,
 }
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub enum Access {
+    Public,
+    Protected,
+    Private,
+}
+
+impl Access {
+    pub fn from(accessibility: clang::Accessibility) -> Self {
+        match accessibility {
+            clang::Accessibility::Public => Self::Public,
+            clang::Accessibility::Protected => Self::Protected,
+            clang::Accessibility::Private => Self::Private,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct BaseSpecifier {
+    pub name: String,
+    pub access: Option<Access>,
+    pub is_virtual: bool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct CXXFieldDecl {
+    pub name: String,
+    pub ty: Typ,
+    pub access: Option<Access>,
+    pub is_mutable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<Box<Comment>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct CXXMethodDecl {
+    pub name: String,
+    pub access: Option<Access>,
+    pub arguments: Vec<ParmDecl>,
+    pub result_type: Typ,
+    pub is_static: bool,
+    pub is_virtual: bool,
+    pub is_pure_virtual: bool,
+    pub is_const: bool,
+    pub platform_availability: Option<Vec<PlatformAvailability>>,
+    #[serde(with = "AvailabilityDef")]
+    pub availability: clang::Availability,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<Box<Comment>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::attributes::{attribute_arguments, attribute_tokens};
+use super::availability::get_platform_availability;
+use super::comment::get_comment;
+use super::entry::{Entry, EnumConstantDecl};
+use super::location::get_location;
+use super::report::required;
+use super::swift::get_swift_attributes;
+use super::ConvertOptions;
+use crate::typ::Typ;
+
+/// The Foundation or Core Foundation macro declaring an enum.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumMacro {
+    NSEnum,
+    NSOptions,
+    NSClosedEnum,
+    NSErrorEnum,
+    CFEnum,
+    CFOptions,
+    CFClosedEnum,
+}
+
+impl EnumMacro {
+    pub fn from(name: &str) -> Option<Self> {
+        let enum_macro = match name {
+            "NS_ENUM" => Self::NSEnum,
+            "NS_OPTIONS" => Self::NSOptions,
+            "NS_CLOSED_ENUM" => Self::NSClosedEnum,
+            "NS_ERROR_ENUM" => Self::NSErrorEnum,
+            "CF_ENUM" => Self::CFEnum,
+            "CF_OPTIONS" => Self::CFOptions,
+            "CF_CLOSED_ENUM" => Self::CFClosedEnum,
+            _ => return None,
+        };
+        Some(enum_macro)
+    }
+
+    /// The `enum_extensibility` the macro declares the enum with.
+    pub fn extensibility(&self) -> EnumExtensibility {
+        match self {
+            Self::NSClosedEnum | Self::CFClosedEnum => EnumExtensibility::Closed,
+            _ => EnumExtensibility::Open,
+        }
+    }
+
+    pub fn is_options(&self) -> bool {
+        matches!(self, Self::NSOptions | Self::CFOptions)
+    }
+}
+
+/// `__attribute__((enum_extensibility(...)))`. Values of a closed enum are
+/// always one of its constants.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumExtensibility {
+    Open,
+    Closed,
+}
+
+/// Converts an enum, `None` for an enum without constants, e.g. a forward
+/// declaration.
+pub fn get_enum_decl(
+    entity: &clang::Entity,
+    options: &ConvertOptions,
+) -> Result<Option<Entry>, String> {
+    let mut decls: Vec<EnumConstantDecl> = vec![];
+    let mut is_flag_enum = false;
+    let mut extensibility = None;
+    for e in entity.get_children() {
+        match e.get_kind() {
+            clang::EntityKind::EnumConstantDecl => {
+                let value = if let Some(child) = e.get_child(0) {
+                    match child.evaluate() {
+                        Some(clang::EvaluationResult::SignedInteger(value)) => {
+                            Some(value.to_string())
+                        }
+                        Some(clang::EvaluationResult::UnsignedInteger(value)) => {
+                            Some(value.to_string())
+                        }
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+                decls.push(EnumConstantDecl {
+                    name: required(e.get_name(), "constant name")?,
+                    value,
+                    objc_type: Typ::from(required(e.get_type(), "constant type")?),
+                    comment: get_comment(&e, options),
+                    location: get_location(&e, options),
+                    swift_attributes: get_swift_attributes(&e),
+                    api_notes: vec![],
+                });
+            }
+            clang::EntityKind::FlagEnum => is_flag_enum = true,
+            // libclang does not expose `enum_extensibility`
+            clang::EntityKind::UnexposedAttr => {
+                extensibility = extensibility.or_else(|| get_extensibility(&e));
+            }
+            _ => {}
+        }
+    }
+    if decls.is_empty() {
+        return Ok(None);
+    }
+    let enum_macro = get_enum_macro(entity);
+    if let Some(enum_macro) = enum_macro {
+        extensibility = extensibility.or(Some(enum_macro.extensibility()));
+        is_flag_enum |= enum_macro.is_options();
+    }
+    let name = entity
+        .get_name()
+        .filter(|name| !entity.is_anonymous() && !name.is_empty() && !name.contains(' '));
+    Ok(Some(Entry::EnumDecl {
+        decls,
+        is_anonymous: name.is_none(),
+        name: name.unwrap_or_else(|| anonymous_enum_name(entity)),
+        typedef_name: None,
+        ty: Typ::from(required(
+            entity.get_enum_underlying_type(),
+            "underlying type",
+        )?),
+        is_scoped: entity.is_scoped(),
+        enum_macro,
+        extensibility,
+        is_flag_enum,
+        platform_availability: get_platform_availability(entity),
+        availability: entity.get_availability(),
+        comment: get_comment(entity, options),
+        location: get_location(entity, options),
+        swift_attributes: get_swift_attributes(entity),
+        api_notes: vec![],
+    }))
+}
+
+/// Maps the enums of `entities` to the names of their typedefs, e.g. `Foo`
+/// for `typedef enum { ... } Foo;` and for `NS_ENUM(NSInteger, Foo)`.
+pub fn get_enum_typedef_names<'tu>(
+    entities: &[clang::Entity<'tu>],
+) -> HashMap<clang::Entity<'tu>, String> {
+    entities
+        .iter()
+        .filter(|entity| entity.get_kind() == clang::EntityKind::TypedefDecl)
+        .filter_map(|typedef| {
+            let declaration = typedef
+                .get_typedef_underlying_type()?
+                .get_canonical_type()
+                .get_declaration()?;
+            if declaration.get_kind() != clang::EntityKind::EnumDecl {
+                return None;
+            }
+            Some((declaration.get_canonical_entity(), typedef.get_name()?))
+        })
+        .collect()
+}
+
+// The macro of `typedef NS_ENUM(NSInteger, Foo) { ... };`, whose extent
+// starts at the macro name.
+fn get_enum_macro(entity: &clang::Entity) -> Option<EnumMacro> {
+    let tokens = entity.get_range()?.tokenize();
+    tokens
+        .iter()
+        .take_while(|token| token.get_spelling() != "{")
+        .find_map(|token| EnumMacro::from(&token.get_spelling()))
+}
+
+fn get_extensibility(attribute: &clang::Entity) -> Option<EnumExtensibility> {
+    let tokens = attribute_tokens(attribute);
+    match attribute_arguments(&tokens, &["enum_extensibility"])? {
+        [argument] if argument == "open" => Some(EnumExtensibility::Open),
+        [argument] if argument == "closed" => Some(EnumExtensibility::Closed),
+        _ => None,
+    }
+}
+
+// e.g. `(anonymous enum at t.h:3:1)`, stable across checkouts.
+fn anonymous_enum_name(entity: &clang::Entity) -> String {
+    let Some(location) = entity.get_location() else {
+        return "(anonymous enum)".to_string();
+    };
+    let (file, line, column) = location.get_presumed_location();
+    let file = Path::new(&file)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or(file);
+    format!("(anonymous enum at {}:{}:{})", file, line, column)
+}
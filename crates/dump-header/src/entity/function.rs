@@ -0,0 +1,262 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::attributes::{attribute_arguments, attribute_tokens};
+use super::availability::get_platform_availability;
+use super::comment::get_comment;
+use super::entry::Entry;
+use super::location::get_location;
+use super::objc::ReturnOwnership;
+use super::report::required;
+use super::swift::get_swift_attributes;
+use super::{get_arguments, ConvertOptions};
+use crate::typ::Typ;
+
+/// The storage class specifier of a function or variable declaration.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageClass {
+    Extern,
+    Static,
+    /// `__private_extern__`
+    PrivateExtern,
+    Auto,
+    Register,
+}
+
+impl StorageClass {
+    /// `None` without a storage class specifier.
+    pub fn from(storage_class: clang::StorageClass) -> Option<Self> {
+        let storage_class = match storage_class {
+            clang::StorageClass::Extern => Self::Extern,
+            clang::StorageClass::Static => Self::Static,
+            clang::StorageClass::PrivateExtern => Self::PrivateExtern,
+            clang::StorageClass::Auto => Self::Auto,
+            clang::StorageClass::Register => Self::Register,
+            clang::StorageClass::None | clang::StorageClass::OpenClWorkGroupLocal => return None,
+        };
+        Some(storage_class)
+    }
+}
+
+/// The visibility of the symbol of a declaration, from
+/// `__attribute__((visibility(...)))` or `-fvisibility`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Default,
+    Hidden,
+    Protected,
+}
+
+impl Visibility {
+    pub fn from(visibility: clang::Visibility) -> Self {
+        match visibility {
+            clang::Visibility::Default => Self::Default,
+            clang::Visibility::Hidden => Self::Hidden,
+            clang::Visibility::Protected => Self::Protected,
+        }
+    }
+}
+
+/// The calling convention of a function type, `C` unless specified with an
+/// attribute such as `__attribute__((swiftcall))` or `__stdcall`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallingConvention {
+    C,
+    Fastcall,
+    Pascal,
+    Stdcall,
+    Thiscall,
+    Vectorcall,
+    Swift,
+    PreserveMost,
+    PreserveAll,
+    Aapcs,
+    AapcsVfp,
+    IntelOcl,
+    RegCall,
+    SysV64,
+    Win64,
+    Unexposed,
+}
+
+impl CallingConvention {
+    pub fn from(calling_convention: clang::CallingConvention) -> Self {
+        match calling_convention {
+            clang::CallingConvention::Cdecl => Self::C,
+            clang::CallingConvention::Fastcall => Self::Fastcall,
+            clang::CallingConvention::Pascal => Self::Pascal,
+            clang::CallingConvention::Stdcall => Self::Stdcall,
+            clang::CallingConvention::Thiscall => Self::Thiscall,
+            clang::CallingConvention::Vectorcall => Self::Vectorcall,
+            clang::CallingConvention::Swift => Self::Swift,
+            clang::CallingConvention::PreserveMost => Self::PreserveMost,
+            clang::CallingConvention::PreserveAll => Self::PreserveAll,
+            clang::CallingConvention::Aapcs => Self::Aapcs,
+            clang::CallingConvention::AapcsVfp => Self::AapcsVfp,
+            clang::CallingConvention::IntelOcl => Self::IntelOcl,
+            clang::CallingConvention::RegCall => Self::RegCall,
+            clang::CallingConvention::SysV64 => Self::SysV64,
+            clang::CallingConvention::Win64 => Self::Win64,
+            clang::CallingConvention::Unexposed => Self::Unexposed,
+        }
+    }
+}
+
+/// `__attribute__((format(archetype, format_index, first_argument)))`, e.g.
+/// `NSString` for `NS_FORMAT_FUNCTION(1, 2)`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct FormatAttribute {
+    /// Without the surrounding underscores, e.g. `printf` or `CFString`
+    pub archetype: String,
+    /// The 1-based index of the format string argument
+    pub format_index: usize,
+    /// The 1-based index of the first formatted argument, 0 for a `va_list`
+    pub first_argument: usize,
+}
+
+// The macros expanding to `format` attributes with their archetype.
+const FORMAT_MACROS: &[(&str, &str)] = &[
+    ("NS_FORMAT_FUNCTION", "NSString"),
+    ("CF_FORMAT_FUNCTION", "CFString"),
+    ("__printflike", "printf"),
+    ("__scanflike", "scanf"),
+];
+
+impl FormatAttribute {
+    /// The format attribute spelled by `tokens`, e.g. `format ( printf , 1 ,
+    /// 2 )` or `NS_FORMAT_FUNCTION ( 1 , 2 )`, `None` for another attribute
+    /// or indices that are not integer literals.
+    pub fn from_tokens(tokens: &[String]) -> Option<Self> {
+        let first = tokens.first()?;
+        let (archetype, indices) =
+            if let Some((name, archetype)) = FORMAT_MACROS.iter().find(|(name, _)| name == first) {
+                (archetype.to_string(), attribute_arguments(tokens, &[name])?)
+            } else {
+                let arguments = attribute_arguments(tokens, &["format", "__format__"])?;
+                let (archetype, indices) = arguments.split_first()?;
+                (
+                    archetype.trim_matches('_').to_string(),
+                    indices.strip_prefix(&[",".to_string()])?,
+                )
+            };
+        let [format_index, comma, first_argument] = indices else {
+            return None;
+        };
+        if comma != "," {
+            return None;
+        }
+        Some(Self {
+            archetype,
+            format_index: format_index.parse().ok()?,
+            first_argument: first_argument.parse().ok()?,
+        })
+    }
+}
+
+/// The ownership named by an attribute spelled `tokens`, e.g.
+/// `CF_RETURNS_RETAINED` or `cf_returns_not_retained`. libclang exposes the
+/// `ns_returns_*` attributes but not the `cf_returns_*` ones.
+pub(crate) fn get_return_ownership(tokens: &[String]) -> Option<ReturnOwnership> {
+    tokens.iter().find_map(|token| match token.as_str() {
+        "CF_RETURNS_RETAINED" | "cf_returns_retained" | "__cf_returns_retained__" => {
+            Some(ReturnOwnership::Retained)
+        }
+        "CF_RETURNS_NOT_RETAINED" | "cf_returns_not_retained" | "__cf_returns_not_retained__" => {
+            Some(ReturnOwnership::NotRetained)
+        }
+        _ => None,
+    })
+}
+
+pub(crate) fn get_function_decl(
+    entity: &clang::Entity,
+    options: &ConvertOptions,
+) -> Result<Entry, String> {
+    let ty = required(entity.get_type(), "type")?;
+    let mut return_ownership = None;
+    let mut warn_unused_result = false;
+    // `__attribute__((noreturn))` is part of the function type
+    let mut is_noreturn = ty.get_display_name().contains("__attribute__((noreturn))");
+    let mut format_attributes = vec![];
+    let mut nonnull: Option<Vec<usize>> = None;
+    for e in entity.get_children() {
+        match e.get_kind() {
+            clang::EntityKind::NSReturnsRetained => {
+                return_ownership = Some(ReturnOwnership::Retained)
+            }
+            clang::EntityKind::NSReturnsNotRetained => {
+                return_ownership = Some(ReturnOwnership::NotRetained)
+            }
+            clang::EntityKind::NSReturnsAutoreleased => {
+                return_ownership = Some(ReturnOwnership::Autoreleased)
+            }
+            clang::EntityKind::WarnUnusedResultAttr => warn_unused_result = true,
+            clang::EntityKind::UnexposedAttr => {
+                let tokens = attribute_tokens(&e);
+                if let Some(ownership) = get_return_ownership(&tokens) {
+                    return_ownership = Some(ownership);
+                }
+                is_noreturn |= tokens.iter().any(|token| {
+                    matches!(token.as_str(), "_Noreturn" | "noreturn" | "__noreturn__")
+                });
+                if let Some(format) = FormatAttribute::from_tokens(&tokens) {
+                    format_attributes.push(format);
+                }
+                if let Some(arguments) = get_nonnull_arguments(&tokens) {
+                    nonnull = Some(match nonnull {
+                        // `nonnull` without indices applies to all the
+                        // pointer arguments
+                        Some(indices) if indices.is_empty() => indices,
+                        _ if arguments.is_empty() => arguments,
+                        Some(mut indices) => {
+                            indices.extend(arguments);
+                            indices.sort();
+                            indices.dedup();
+                            indices
+                        }
+                        None => arguments,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(Entry::FunctionDecl {
+        name: required(entity.get_name(), "name")?,
+        ty: Typ::from(ty),
+        arguments: get_arguments(entity)?,
+        result_type: Typ::from(required(entity.get_result_type(), "result type")?),
+        is_variadic: entity.is_variadic(),
+        storage_class: entity.get_storage_class().and_then(StorageClass::from),
+        is_inline: entity.is_inline_function(),
+        is_noreturn,
+        visibility: entity.get_visibility().map(Visibility::from),
+        calling_convention: ty.get_calling_convention().map(CallingConvention::from),
+        format_attributes,
+        return_ownership,
+        warn_unused_result,
+        nonnull,
+        platform_availability: get_platform_availability(entity),
+        availability: entity.get_availability(),
+        comment: get_comment(entity, options),
+        location: get_location(entity, options),
+        swift_attributes: get_swift_attributes(entity),
+        api_notes: vec![],
+    })
+}
+
+// The 1-based indices of `nonnull(...)`, empty for `nonnull` without
+// indices, `None` for another attribute.
+fn get_nonnull_arguments(tokens: &[String]) -> Option<Vec<usize>> {
+    let first = tokens.first()?;
+    if first != "nonnull" && first != "__nonnull__" {
+        return None;
+    }
+    Some(
+        attribute_arguments(tokens, &[first])
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|token| token.parse().ok())
+            .collect(),
+    )
+}
@@ -0,0 +1,88 @@
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ConvertOptions;
+
+/// Where a declaration or a diagnostic is, as presumed by clang, i.e. after
+/// `#line` directives.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: PathBuf,
+    pub line: u32,
+    pub column: u32,
+    /// The source range of the whole declaration
+    pub extent: Option<Extent>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct Extent {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+pub fn get_location(entity: &clang::Entity, options: &ConvertOptions) -> Option<Location> {
+    if !options.locations {
+        return None;
+    }
+    to_location(
+        entity.get_location()?,
+        entity.get_range(),
+        options.location_root.as_deref(),
+    )
+}
+
+/// `None` for a location outside any file, e.g. of a command line argument.
+pub(crate) fn to_location(
+    location: clang::source::SourceLocation,
+    range: Option<clang::source::SourceRange>,
+    root: Option<&Path>,
+) -> Option<Location> {
+    let (file, line, column) = location.get_presumed_location();
+    if file.is_empty() {
+        return None;
+    }
+    let extent = range.map(|range| {
+        let (_, start_line, start_column) = range.get_start().get_presumed_location();
+        let (_, end_line, end_column) = range.get_end().get_presumed_location();
+        Extent {
+            start: Position {
+                line: start_line,
+                column: start_column,
+            },
+            end: Position {
+                line: end_line,
+                column: end_column,
+            },
+        }
+    });
+    Some(Location {
+        file: relative_path(PathBuf::from(file), root),
+        line,
+        column,
+        extent,
+    })
+}
+
+fn relative_path(path: PathBuf, root: Option<&Path>) -> PathBuf {
+    match root {
+        Some(root) => path
+            .strip_prefix(root)
+            .map(|p| p.to_path_buf())
+            .unwrap_or(path),
+        None => path,
+    }
+}
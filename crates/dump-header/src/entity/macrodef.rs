@@ -0,0 +1,135 @@
+use std::path::Path;
+
+use clang::token::Token;
+
+use super::comment::Comment;
+use super::entry::Entry;
+use super::location::Location;
+use super::vardecl::evaluate;
+use crate::{cli::Lang, error::Error, parser::Parser};
+
+const MACRO_VAR_PREFIX: &str = "__dump_header_macro_";
+
+pub fn get_macro_definition(
+    entity: &clang::Entity,
+    comment: Option<Box<Comment>>,
+    location: Option<Location>,
+) -> Option<Entry> {
+    let name = entity.get_name()?;
+    let tokens = entity
+        .get_range()
+        .map(|range| range.tokenize())
+        .unwrap_or_default();
+    // skip the macro name
+    let mut body = tokens.iter().skip(1);
+    let parameters = if entity.is_function_like_macro() {
+        let mut parameters = vec![];
+        // skip "("
+        body.next();
+        for token in body.by_ref() {
+            match token.get_spelling().as_str() {
+                ")" => break,
+                "," => {}
+                parameter => parameters.push(parameter.to_string()),
+            }
+        }
+        Some(parameters)
+    } else {
+        None
+    };
+    let body: Vec<&Token> = body.collect();
+    Some(Entry::MacroDefinition {
+        name,
+        parameters,
+        tokens: join_tokens(&body),
+        value: None,
+        comment,
+        location,
+    })
+}
+
+// Keeps a single space where the source has whitespace between tokens.
+fn join_tokens(tokens: &[&Token]) -> String {
+    let mut text = String::new();
+    let mut prev_end = None;
+    tokens.iter().for_each(|token| {
+        let range = token.get_range();
+        let start = range.get_start().get_file_location().offset;
+        if prev_end.map(|end| end < start).unwrap_or(false) {
+            text.push(' ');
+        }
+        text.push_str(&token.get_spelling());
+        prev_end = Some(range.get_end().get_file_location().offset);
+    });
+    text
+}
+
+/// Fills in the `value` of the object-like `MacroDefinition` entries.
+///
+/// libclang cannot evaluate a macro directly, so each macro is expanded into
+/// the initializer of a variable in a translation unit that includes
+/// `header`, and clang evaluates the variables instead. Macros which expand
+/// to attributes or pragmas are skipped, and the error limit is lifted so the
+/// remaining non-expression macros do not stop clang before the last
+/// variable.
+pub fn evaluate_macros<'e>(
+    parser: &Parser,
+    header: &Path,
+    entries: impl IntoIterator<Item = &'e mut Entry>,
+) -> Result<(), Error> {
+    let mut macros: Vec<&mut Entry> = entries
+        .into_iter()
+        .filter(|entry| {
+            matches!(entry, Entry::MacroDefinition {
+                parameters: None,
+                tokens,
+                value: None,
+                ..
+            } if !tokens.is_empty() && !is_annotation(tokens))
+        })
+        .collect();
+    if macros.is_empty() {
+        return Ok(());
+    }
+    let auto_type = match parser.config().lang {
+        Lang::Cpp => "auto",
+        _ => "__auto_type",
+    };
+    let mut content = format!("#include \"{}\"\n", header.display());
+    macros.iter().enumerate().for_each(|(i, entry)| {
+        if let Entry::MacroDefinition { name, .. } = entry {
+            content.push_str(&format!(
+                "{} {}{} = {};\n",
+                auto_type, MACRO_VAR_PREFIX, i, name
+            ));
+        }
+    });
+    let (tu, path) = parser.parse_content_with_arguments(&content, &["-ferror-limit=0"])?;
+    tu.get_entity().get_children().iter().for_each(|entity| {
+        if entity.get_kind() != clang::EntityKind::VarDecl
+            || !crate::utils::is_in_file(entity, &path)
+        {
+            return;
+        }
+        let index = entity
+            .get_name()
+            .and_then(|name| name.strip_prefix(MACRO_VAR_PREFIX)?.parse::<usize>().ok());
+        if let Some(Entry::MacroDefinition { value, .. }) = index
+            .and_then(|i| macros.get_mut(i))
+            .map(|entry| &mut **entry)
+        {
+            if !entity.is_invalid_declaration() {
+                *value = evaluate(entity);
+            }
+        }
+    });
+    Ok(())
+}
+
+// e.g. `__attribute__((visibility("default")))` or
+// `_Pragma("clang assume_nonnull begin")`, which are never values.
+fn is_annotation(tokens: &str) -> bool {
+    ["__attribute__", "__declspec", "_Pragma"]
+        .iter()
+        .any(|prefix| tokens.starts_with(prefix))
+}
@@ -1,95 +1,199 @@
-use crate::typ::Typ;
+use std::path::PathBuf;
+
+use crate::typ::{FieldLayout, Nullability, RecordLayout, Typ};
 
 mod attributes;
 mod availability;
+mod comment;
+mod cxx;
 mod entry;
+mod enumdecl;
+mod function;
+pub(crate) mod location;
+mod macrodef;
+mod objc;
+mod report;
+mod swift;
 mod vardecl;
 
 use attributes::ObjCAttributes;
+pub(crate) use attributes::{attribute_arguments, attribute_tokens};
 use availability::get_platform_availability;
+pub use availability::{PlatformAvailability, Version};
 pub use entry::{
-    Entry, EnumConstantDecl, ObjCMethodDecl, ObjCPropertyDecl, ParmDecl, TemplateTypeParameter,
+    Access, BaseSpecifier, CXXFieldDecl, CXXMethodDecl, Entry, EnumConstantDecl, FieldDecl,
+    InitExpr, InitListExpr, InitValue, ObjCMethodDecl, ObjCPropertyDecl, ParmDecl,
+    TemplateTypeParameter,
 };
 
+pub use comment::{BlockCommandComment, Comment, ParamComment};
+pub use enumdecl::{EnumExtensibility, EnumMacro};
+pub use function::{CallingConvention, FormatAttribute, StorageClass, Visibility};
+pub use location::{Extent, Location, Position};
+pub use macrodef::evaluate_macros;
+pub use objc::{MethodFamily, ReturnOwnership};
+pub use report::{ConversionReport, SkippedEntity};
+pub use swift::SwiftAttribute;
+
+use self::comment::get_comment;
+use self::location::get_location;
+
+use self::cxx::{get_class_decl, get_namespace, has_cxx_members};
+use self::enumdecl::{get_enum_decl, get_enum_typedef_names};
+use self::function::get_function_decl;
+use self::macrodef::get_macro_definition;
+use self::objc::get_objc_method;
+use self::report::required;
+use self::swift::get_swift_attributes;
 use self::vardecl::get_init_expr;
 
-pub fn convert_entity(entity: &clang::Entity) -> Option<Entry> {
+/// Selects the optional information attached to the converted entries.
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptions {
+    /// Attach documentation comments
+    pub comments: bool,
+    /// Attach source locations
+    pub locations: bool,
+    /// Make the paths of source locations relative to this directory
+    pub location_root: Option<PathBuf>,
+}
+
+/// Converts `entities`, looking through `extern "C" { ... }` blocks.
+pub fn convert_entities(entities: &[clang::Entity], options: &ConvertOptions) -> Vec<Entry> {
+    convert_entities_with_report(entities, options, &mut ConversionReport::default())
+}
+
+/// Converts `entities` like `convert_entities`, recording in `report` the
+/// entities it skipped or ignored.
+pub fn convert_entities_with_report(
+    entities: &[clang::Entity],
+    options: &ConvertOptions,
+    report: &mut ConversionReport,
+) -> Vec<Entry> {
+    let mut entries = vec![];
+    let enum_typedef_names = get_enum_typedef_names(entities);
+    entities.iter().for_each(|entity| {
+        if entity.get_kind() == clang::EntityKind::LinkageSpec {
+            entries.extend(convert_entities_with_report(
+                &entity.get_children(),
+                options,
+                report,
+            ));
+            return;
+        }
+        match try_convert_entity(entity, options, report) {
+            Ok(Some(mut entry)) => {
+                if let Entry::EnumDecl { typedef_name, .. } = &mut entry {
+                    *typedef_name = enum_typedef_names
+                        .get(&entity.get_canonical_entity())
+                        .cloned();
+                }
+                entries.push(entry);
+            }
+            Ok(None) => {}
+            Err(reason) => report.skip(entity, reason, options),
+        }
+    });
+    entries
+}
+
+/// Converts `entity`, `None` if it has no `Entry` or could not be converted.
+pub fn convert_entity(entity: &clang::Entity, options: &ConvertOptions) -> Option<Entry> {
+    try_convert_entity(entity, options, &mut ConversionReport::default())
+        .ok()
+        .flatten()
+}
+
+/// Converts `entity`, failing with the reason it could not be converted.
+/// Members failing to convert are left out and recorded in `report`.
+pub(crate) fn try_convert_entity(
+    entity: &clang::Entity,
+    options: &ConvertOptions,
+    report: &mut ConversionReport,
+) -> Result<Option<Entry>, String> {
     let name = entity.get_name();
     let kind = entity.get_kind();
     let platform_availability = get_platform_availability(entity);
     let availability = entity.get_availability();
-    match kind {
+    let comment = get_comment(entity, options);
+    let location = get_location(entity, options);
+    let entry = match kind {
         clang::EntityKind::InclusionDirective => {
             let path: Option<std::path::PathBuf> = entity.get_file().map(|f| f.get_path());
-            path.map(|path| Entry::InclusionDirective {
-                name: name.unwrap(),
-                path,
-            })
+            match path {
+                Some(path) => Some(Entry::InclusionDirective {
+                    name: required(name, "name")?,
+                    path,
+                    comment,
+                    location,
+                }),
+                None => None,
+            }
         }
+        clang::EntityKind::MacroDefinition => get_macro_definition(entity, comment, location),
         clang::EntityKind::TypedefDecl => Some(Entry::TypedefDecl {
-            name: name.unwrap(),
-            ty: entity
-                .get_typedef_underlying_type()
-                .map(|t| Typ::from(t))
-                .unwrap(),
+            name: required(name, "name")?,
+            ty: Typ::from(required(
+                entity.get_typedef_underlying_type(),
+                "underlying type",
+            )?),
             platform_availability,
             availability,
+            comment,
+            location,
+            swift_attributes: get_swift_attributes(entity),
+            api_notes: vec![],
         }),
-        clang::EntityKind::EnumDecl => {
-            let mut decls: Vec<EnumConstantDecl> = vec![];
-            entity.get_children().iter().for_each(|e| {
-                if let clang::EntityKind::EnumConstantDecl = e.get_kind() {
-                    let value = if let Some(child) = e.get_child(0) {
-                        match child.evaluate() {
-                            Some(clang::EvaluationResult::SignedInteger(value)) => {
-                                Some(value.to_string())
-                            }
-                            Some(clang::EvaluationResult::UnsignedInteger(value)) => {
-                                Some(value.to_string())
-                            }
-                            _ => None,
-                        }
-                    } else {
-                        None
-                    };
-                    decls.push(EnumConstantDecl {
-                        name: e.get_name().unwrap(),
-                        value,
-                        objc_type: Typ::from(e.get_type().unwrap()),
-                    });
-                }
-            });
-            if decls.is_empty() {
-                return None;
-            }
-            Some(Entry::EnumDecl {
-                decls,
-                name: name.unwrap(),
-                ty: Typ::from(entity.get_enum_underlying_type().unwrap()),
-                platform_availability,
-                availability,
-            })
-        }
+        clang::EntityKind::EnumDecl => get_enum_decl(entity, options)?,
         clang::EntityKind::VarDecl => {
             let init_expr = get_init_expr(entity);
             Some(Entry::VarDecl {
-                name: name.unwrap(),
-                ty: Typ::from(entity.get_type().unwrap()),
+                name: required(name, "name")?,
+                ty: Typ::from(required(entity.get_type(), "type")?),
                 init_expr,
+                storage_class: entity.get_storage_class().and_then(StorageClass::from),
+                is_thread_local: entity.get_tls_kind().is_some(),
                 platform_availability,
                 availability,
+                comment,
+                location,
+                swift_attributes: get_swift_attributes(entity),
+                api_notes: vec![],
             })
         }
+        clang::EntityKind::StructDecl if has_cxx_members(entity) => {
+            Some(get_class_decl(entity, options, report)?)
+        }
+        clang::EntityKind::ClassDecl | clang::EntityKind::ClassTemplate => {
+            Some(get_class_decl(entity, options, report)?)
+        }
+        clang::EntityKind::Namespace => Some(get_namespace(entity, options, report)),
+        clang::EntityKind::TypeAliasDecl => Some(Entry::TypeAliasDecl {
+            name: required(name, "name")?,
+            ty: Typ::from(required(
+                entity.get_typedef_underlying_type(),
+                "underlying type",
+            )?),
+            platform_availability,
+            availability,
+            comment,
+            location,
+        }),
         clang::EntityKind::StructDecl => Some(Entry::StructDecl {
             name: if !entity.is_anonymous_record_decl() {
                 name
             } else {
                 None
             },
-            fields: get_fields(entity),
-            ty: Typ::from(entity.get_type().unwrap()),
+            fields: get_fields(entity, options, report),
+            ty: Typ::from(required(entity.get_type(), "type")?),
+            layout: entity.get_type().and_then(RecordLayout::from),
             platform_availability,
             availability,
+            comment,
+            location,
+            swift_attributes: get_swift_attributes(entity),
+            api_notes: vec![],
         }),
         clang::EntityKind::UnionDecl => Some(Entry::UnionDecl {
             name: if !entity.is_anonymous_record_decl() {
@@ -97,23 +201,24 @@ pub fn convert_entity(entity: &clang::Entity) -> Option<Entry> {
             } else {
                 None
             },
-            fields: get_fields(entity),
-            ty: Typ::from(entity.get_type().unwrap()),
+            fields: get_fields(entity, options, report),
+            ty: Typ::from(required(entity.get_type(), "type")?),
+            layout: entity.get_type().and_then(RecordLayout::from),
             platform_availability,
             availability,
+            comment,
+            location,
+            swift_attributes: get_swift_attributes(entity),
+            api_notes: vec![],
         }),
         clang::EntityKind::FieldDecl => Some(Entry::FieldDecl {
-            name: name.unwrap(),
-            ty: Typ::from(entity.get_type().unwrap()),
-        }),
-        clang::EntityKind::FunctionDecl => Some(Entry::FunctionDecl {
-            name: name.unwrap(),
-            ty: Typ::from(entity.get_type().unwrap()),
-            arguments: get_arguments(entity),
-            result_type: Typ::from(entity.get_result_type().unwrap()),
-            platform_availability,
-            availability,
+            name: required(name, "name")?,
+            ty: Typ::from(required(entity.get_type(), "type")?),
+            layout: FieldLayout::from(entity),
+            comment,
+            location,
         }),
+        clang::EntityKind::FunctionDecl => Some(get_function_decl(entity, options)?),
         clang::EntityKind::ObjCInterfaceDecl
         | clang::EntityKind::ObjCCategoryDecl
         | clang::EntityKind::ObjCProtocolDecl => {
@@ -124,60 +229,42 @@ pub fn convert_entity(entity: &clang::Entity) -> Option<Entry> {
             let mut instance_methods: Vec<ObjCMethodDecl> = vec![];
             let mut class_methods: Vec<ObjCMethodDecl> = vec![];
             let mut properties: Vec<ObjCPropertyDecl> = vec![];
-            entity
-                .get_children()
-                .iter()
-                .for_each(|e| match e.get_kind() {
+            for e in entity.get_children() {
+                let member = match e.get_kind() {
                     clang::EntityKind::ObjCProtocolRef => {
-                        protocols.push(e.get_name().unwrap());
+                        required(e.get_name(), "name").map(|name| protocols.push(name))
                     }
                     clang::EntityKind::TemplateTypeParameter => {
-                        let constraint = e.get_child(0).map(|x| x.get_name().unwrap());
-                        template_args.push(TemplateTypeParameter {
-                            name: e.get_name().unwrap(),
-                            constraint,
-                        });
+                        get_template_type_parameter(&e).map(|arg| template_args.push(arg))
                     }
                     clang::EntityKind::ObjCSuperClassRef => {
-                        superclass = e.get_name().unwrap();
+                        required(e.get_name(), "name").map(|name| superclass = name)
                     }
                     clang::EntityKind::ObjCClassRef => {
-                        class_name = e.get_name().unwrap();
+                        required(e.get_name(), "name").map(|name| class_name = name)
                     }
                     clang::EntityKind::ObjCInstanceMethodDecl
                     | clang::EntityKind::ObjCClassMethodDecl => {
-                        let arguments: Vec<ParmDecl> = get_arguments(entity);
-                        let method = ObjCMethodDecl {
-                            name: e.get_name().unwrap(),
-                            arguments,
-                            result_type: Typ::from(e.get_result_type().unwrap()),
-                            optional: e.is_objc_optional(),
-                            platform_availability: get_platform_availability(e),
-                            availability: e.get_availability(),
-                        };
-                        if let clang::EntityKind::ObjCInstanceMethodDecl = e.get_kind() {
-                            instance_methods.push(method);
-                        } else {
-                            class_methods.push(method);
-                        }
+                        get_objc_method(&e, options).map(|method| {
+                            if let clang::EntityKind::ObjCInstanceMethodDecl = e.get_kind() {
+                                instance_methods.push(method);
+                            } else {
+                                class_methods.push(method);
+                            }
+                        })
                     }
                     clang::EntityKind::ObjCPropertyDecl => {
-                        let attributes = e.get_objc_attributes().map(ObjCAttributes::from);
-                        let property = ObjCPropertyDecl {
-                            name: e.get_name().unwrap(),
-                            objc_type: Typ::from(e.get_type().unwrap()),
-                            optional: e.is_objc_optional(),
-                            attributes,
-                            platform_availability: get_platform_availability(e),
-                            availability: e.get_availability(),
-                        };
-                        properties.push(property);
+                        get_objc_property(&e, options).map(|property| properties.push(property))
                     }
-                    _ => {}
-                });
+                    _ => Ok(()),
+                };
+                if let Err(reason) = member {
+                    report.skip(&e, reason, options);
+                }
+            }
             match kind {
                 clang::EntityKind::ObjCInterfaceDecl => Some(Entry::ObjCInterfaceDecl {
-                    name: name.unwrap(),
+                    name: required(name, "name")?,
                     template_args,
                     superclass,
                     instance_methods,
@@ -186,52 +273,140 @@ pub fn convert_entity(entity: &clang::Entity) -> Option<Entry> {
                     protocols,
                     platform_availability,
                     availability,
+                    comment,
+                    location,
+                    swift_attributes: get_swift_attributes(entity),
+                    api_notes: vec![],
                 }),
                 clang::EntityKind::ObjCCategoryDecl => Some(Entry::ObjCCategoryDecl {
                     name,
                     class_name,
+                    protocols,
                     instance_methods,
                     class_methods,
                     properties,
                     platform_availability,
                     availability,
+                    comment,
+                    location,
+                    swift_attributes: get_swift_attributes(entity),
                 }),
                 clang::EntityKind::ObjCProtocolDecl => Some(Entry::ObjCProtocolDecl {
-                    name: name.unwrap(),
+                    name: required(name, "name")?,
+                    protocols,
                     instance_methods,
                     class_methods,
                     properties,
                     platform_availability,
                     availability,
+                    comment,
+                    location,
+                    swift_attributes: get_swift_attributes(entity),
+                    api_notes: vec![],
                 }),
-                _ => panic!("Invalid ObjC entity kind: {:?}", kind),
+                _ => unreachable!(),
             }
         }
-        _ => None,
-    }
+        _ => {
+            report.ignore(entity);
+            None
+        }
+    };
+    Ok(entry)
 }
 
-fn get_arguments(entity: &clang::Entity) -> Vec<ParmDecl> {
+fn get_template_type_parameter(entity: &clang::Entity) -> Result<TemplateTypeParameter, String> {
+    let constraint = match entity.get_child(0) {
+        Some(child) => Some(required(child.get_name(), "constraint name")?),
+        None => None,
+    };
+    Ok(TemplateTypeParameter {
+        name: required(entity.get_name(), "name")?,
+        constraint,
+    })
+}
+
+fn get_objc_property(
+    entity: &clang::Entity,
+    options: &ConvertOptions,
+) -> Result<ObjCPropertyDecl, String> {
+    let attributes = entity.get_objc_attributes().map(ObjCAttributes::from);
+    Ok(ObjCPropertyDecl {
+        name: required(entity.get_name(), "name")?,
+        objc_type: Typ::from(required(entity.get_type(), "type")?),
+        optional: entity.is_objc_optional(),
+        attributes,
+        platform_availability: get_platform_availability(entity),
+        availability: entity.get_availability(),
+        comment: get_comment(entity, options),
+        location: get_location(entity, options),
+        swift_attributes: get_swift_attributes(entity),
+        api_notes: vec![],
+    })
+}
+
+fn get_arguments(entity: &clang::Entity) -> Result<Vec<ParmDecl>, String> {
     let mut arguments: Vec<ParmDecl> = vec![];
     if let Some(args) = entity.get_arguments() {
-        args.iter().for_each(|arg| {
+        for arg in args {
             if let clang::EntityKind::ParmDecl = arg.get_kind() {
-                arguments.push(ParmDecl {
-                    name: arg.get_name(),
-                    objc_type: Typ::from(arg.get_type().unwrap()),
-                });
+                arguments.push(get_parm_decl(&arg)?);
+            }
+        }
+    }
+    Ok(arguments)
+}
+
+fn get_parm_decl(entity: &clang::Entity) -> Result<ParmDecl, String> {
+    let ty = required(entity.get_type(), "argument type")?;
+    let mut is_noescape = false;
+    let mut is_ns_consumed = false;
+    let mut is_cf_consumed = false;
+    for e in entity.get_children() {
+        match e.get_kind() {
+            clang::EntityKind::NSConsumed => is_ns_consumed = true,
+            // libclang does not expose `noescape` nor `cf_consumed`
+            clang::EntityKind::UnexposedAttr => {
+                let tokens = attribute_tokens(&e);
+                is_noescape |= tokens
+                    .iter()
+                    .any(|token| token == "noescape" || token == "NS_NOESCAPE");
+                is_cf_consumed |= tokens
+                    .iter()
+                    .any(|token| token == "cf_consumed" || token == "CF_CONSUMED");
             }
-        });
+            _ => {}
+        }
     }
-    arguments
+    Ok(ParmDecl {
+        name: entity.get_name(),
+        objc_type: Typ::from(ty),
+        nullability: ty.get_nullability().map(Nullability::from),
+        is_noescape,
+        is_ns_consumed,
+        is_cf_consumed,
+        api_notes: vec![],
+    })
 }
 
-fn get_fields(entity: &clang::Entity) -> Vec<Entry> {
+fn get_fields(
+    entity: &clang::Entity,
+    options: &ConvertOptions,
+    report: &mut ConversionReport,
+) -> Vec<Entry> {
     entity
         .get_children()
         .iter()
         .filter_map(|e| match e.get_kind() {
-            clang::EntityKind::FieldDecl | clang::EntityKind::UnionDecl => convert_entity(e),
+            clang::EntityKind::FieldDecl | clang::EntityKind::UnionDecl => {
+                match try_convert_entity(e, options, report) {
+                    Ok(entry) => entry,
+                    Err(reason) => {
+                        report.skip(e, reason, options);
+                        None
+                    }
+                }
+            }
             _ => None,
         })
         .collect()
@@ -0,0 +1,150 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::attributes::{attribute_arguments, attribute_tokens};
+use super::availability::get_platform_availability;
+use super::comment::get_comment;
+use super::entry::ObjCMethodDecl;
+use super::function::get_return_ownership;
+use super::location::get_location;
+use super::report::required;
+use super::swift::get_swift_attributes;
+use super::{get_arguments, ConvertOptions};
+use crate::typ::Typ;
+
+/// The memory management family of a method, as ARC infers it from the
+/// selector.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodFamily {
+    Alloc,
+    Copy,
+    Init,
+    MutableCopy,
+    New,
+}
+
+impl MethodFamily {
+    /// The family of `selector`, whose first word, after any leading
+    /// underscores, is the name of the family, e.g. `Init` for
+    /// `initWithFoo:` but none for `initialize`.
+    pub fn from_selector(selector: &str) -> Option<Self> {
+        let word = selector.trim_start_matches('_');
+        [
+            ("alloc", Self::Alloc),
+            ("copy", Self::Copy),
+            ("init", Self::Init),
+            ("mutableCopy", Self::MutableCopy),
+            ("new", Self::New),
+        ]
+        .into_iter()
+        .find_map(|(name, family)| {
+            let rest = word.strip_prefix(name)?;
+            match rest.chars().next() {
+                Some(c) if c.is_ascii_lowercase() => None,
+                _ => Some(family),
+            }
+        })
+    }
+
+    /// The family named in `objc_method_family(...)`, `None` for `none`.
+    pub fn from_attribute(name: &str) -> Option<Self> {
+        let family = match name {
+            "alloc" => Self::Alloc,
+            "copy" => Self::Copy,
+            "init" => Self::Init,
+            "mutableCopy" => Self::MutableCopy,
+            "new" => Self::New,
+            _ => return None,
+        };
+        Some(family)
+    }
+}
+
+/// The ownership of a returned object, overriding the convention of the
+/// method family.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnOwnership {
+    Retained,
+    NotRetained,
+    Autoreleased,
+}
+
+pub(crate) fn get_objc_method(
+    entity: &clang::Entity,
+    options: &ConvertOptions,
+) -> Result<ObjCMethodDecl, String> {
+    let name = required(entity.get_name(), "name")?;
+    let result_type = required(entity.get_result_type(), "result type")?;
+    let mut family = MethodFamily::from_selector(&name);
+    let mut return_ownership = None;
+    let mut is_designated_initializer = false;
+    let mut is_direct = false;
+    for e in entity.get_children() {
+        match e.get_kind() {
+            clang::EntityKind::NSReturnsRetained => {
+                return_ownership = Some(ReturnOwnership::Retained)
+            }
+            clang::EntityKind::NSReturnsNotRetained => {
+                return_ownership = Some(ReturnOwnership::NotRetained)
+            }
+            clang::EntityKind::NSReturnsAutoreleased => {
+                return_ownership = Some(ReturnOwnership::Autoreleased)
+            }
+            clang::EntityKind::ObjCDesignatedInitializer => is_designated_initializer = true,
+            // libclang does not expose `objc_direct`, `objc_method_family` nor
+            // `cf_returns_retained`
+            clang::EntityKind::UnexposedAttr => {
+                let tokens = attribute_tokens(&e);
+                if let Some(ownership) = get_return_ownership(&tokens) {
+                    return_ownership = Some(ownership);
+                }
+                is_direct |= tokens
+                    .iter()
+                    .any(|token| token == "objc_direct" || token == "NS_DIRECT");
+                if let Some(arguments) = attribute_arguments(&tokens, &["objc_method_family"]) {
+                    family = arguments
+                        .first()
+                        .and_then(|name| MethodFamily::from_attribute(name));
+                }
+            }
+            _ => {}
+        }
+    }
+    let swift_attributes = get_swift_attributes(entity);
+    let swift_name = swift_attributes
+        .iter()
+        .find(|attribute| attribute.name == "swift_name")
+        .and_then(|attribute| attribute.arguments.first().cloned());
+    Ok(ObjCMethodDecl {
+        selector_pieces: name.split_terminator(':').map(String::from).collect(),
+        name,
+        arguments: get_arguments(entity)?,
+        is_variadic: entity.is_variadic(),
+        returns_instancetype: is_instancetype(result_type),
+        result_type: Typ::from(result_type),
+        optional: entity.is_objc_optional(),
+        family,
+        return_ownership,
+        is_designated_initializer,
+        is_direct,
+        swift_name,
+        platform_availability: get_platform_availability(entity),
+        availability: entity.get_availability(),
+        comment: get_comment(entity, options),
+        location: get_location(entity, options),
+        swift_attributes,
+        api_notes: vec![],
+    })
+}
+
+// `instancetype`, possibly with a nullability, e.g. `nullable instancetype`.
+fn is_instancetype(ty: clang::Type) -> bool {
+    let mut ty = ty;
+    while ty.get_kind() == clang::TypeKind::Attributed {
+        match ty.get_modified_type() {
+            Some(modified_type) => ty = modified_type,
+            None => return false,
+        }
+    }
+    ty.get_kind() == clang::TypeKind::Typedef && ty.get_display_name() == "instancetype"
+}
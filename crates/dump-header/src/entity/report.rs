@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::location::{to_location, Location};
+use super::ConvertOptions;
+
+/// An entity left out of the dump because it could not be converted.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct SkippedEntity {
+    /// The `clang::EntityKind` of the entity, e.g. `VarDecl`
+    pub kind: String,
+    pub name: Option<String>,
+    pub reason: String,
+    /// Always present, whether or not locations are attached to the entries
+    pub location: Option<Location>,
+}
+
+impl std::fmt::Display for SkippedEntity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{}: ", location)?;
+        }
+        write!(f, "skipped {}", self.kind)?;
+        if let Some(name) = &self.name {
+            write!(f, " {}", name)?;
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+/// What a conversion left out: the entities that failed to convert and the
+/// number of entities of each `clang::EntityKind` without an `Entry`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub skipped: Vec<SkippedEntity>,
+    pub ignored_kinds: BTreeMap<String, usize>,
+}
+
+impl ConversionReport {
+    pub fn is_empty(&self) -> bool {
+        self.skipped.is_empty() && self.ignored_kinds.is_empty()
+    }
+
+    pub fn merge(&mut self, other: ConversionReport) {
+        self.skipped.extend(other.skipped);
+        other.ignored_kinds.into_iter().for_each(|(kind, count)| {
+            *self.ignored_kinds.entry(kind).or_default() += count;
+        });
+    }
+
+    pub(crate) fn skip(
+        &mut self,
+        entity: &clang::Entity,
+        reason: String,
+        options: &ConvertOptions,
+    ) {
+        self.skipped.push(SkippedEntity {
+            kind: format!("{:?}", entity.get_kind()),
+            name: entity.get_name(),
+            reason,
+            location: entity.get_location().and_then(|location| {
+                to_location(
+                    location,
+                    entity.get_range(),
+                    options.location_root.as_deref(),
+                )
+            }),
+        });
+    }
+
+    pub(crate) fn ignore(&mut self, entity: &clang::Entity) {
+        // macro expansions are in every detailed preprocessing record
+        if entity.get_kind() == clang::EntityKind::MacroExpansion {
+            return;
+        }
+        *self
+            .ignored_kinds
+            .entry(format!("{:?}", entity.get_kind()))
+            .or_default() += 1;
+    }
+}
+
+/// Fails with "no `what`" if `value` is `None`.
+pub(crate) fn required<T>(value: Option<T>, what: &str) -> Result<T, String> {
+    value.ok_or_else(|| format!("no {}", what))
+}
@@ -0,0 +1,125 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::attributes::{attribute_arguments, attribute_tokens};
+
+/// A Swift interop or concurrency attribute, named as in clang without the
+/// surrounding underscores, e.g. `swift_attr` with `@Sendable` for
+/// `NS_SWIFT_SENDABLE`, or `availability` with `swift` and `unavailable` for
+/// `NS_SWIFT_UNAVAILABLE`. An unknown `*_SWIFT_*` macro is named by itself.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct SwiftAttribute {
+    pub name: String,
+    /// Without the quotes of string literals, e.g. `init(foo:)` for
+    /// `NS_SWIFT_NAME(init(foo:))` and for `swift_name("init(foo:)")`
+    pub arguments: Vec<String>,
+}
+
+// The Foundation and Core Foundation macros with the attribute they expand to
+// and the arguments preceding theirs.
+const MACROS: &[(&str, &str, &[&str])] = &[
+    ("NS_SWIFT_SENDABLE", "swift_attr", &["@Sendable"]),
+    ("NS_SWIFT_NONSENDABLE", "swift_attr", &["@_nonSendable"]),
+    ("NS_SWIFT_UI_ACTOR", "swift_attr", &["@UIActor"]),
+    ("NS_SWIFT_NONISOLATED", "swift_attr", &["nonisolated"]),
+    ("NS_REFINED_FOR_SWIFT", "swift_private", &[]),
+    ("CF_REFINED_FOR_SWIFT", "swift_private", &[]),
+    ("NS_SWIFT_NAME", "swift_name", &[]),
+    ("CF_SWIFT_NAME", "swift_name", &[]),
+    ("NS_SWIFT_ASYNC", "swift_async", &["not_swift_private"]),
+    ("NS_SWIFT_ASYNC_NAME", "swift_async_name", &[]),
+    ("NS_SWIFT_DISABLE_ASYNC", "swift_async", &["none"]),
+    ("NS_SWIFT_NOTHROW", "swift_error", &["none"]),
+    (
+        "NS_SWIFT_UNAVAILABLE",
+        "availability",
+        &["swift", "unavailable"],
+    ),
+    (
+        "CF_SWIFT_UNAVAILABLE",
+        "availability",
+        &["swift", "unavailable"],
+    ),
+];
+
+impl SwiftAttribute {
+    /// The Swift attribute spelled by `tokens`, e.g. `NS_SWIFT_NAME ( foo )`
+    /// or `__swift_attr__ ( "@Sendable" )`, `None` for another attribute.
+    pub fn from_tokens(tokens: &[String]) -> Option<Self> {
+        let first = tokens.first()?;
+        if let Some((_, name, arguments)) = MACROS.iter().find(|(name, ..)| name == first) {
+            let mut arguments: Vec<String> = arguments.iter().map(|a| a.to_string()).collect();
+            if let Some(tokens) = attribute_arguments(tokens, &[first.as_str()]) {
+                let message = split_arguments(tokens);
+                if *name == "availability" {
+                    arguments.extend(message.into_iter().map(|m| format!("message={}", m)));
+                } else {
+                    arguments.extend(message);
+                }
+            }
+            return Some(Self {
+                name: name.to_string(),
+                arguments,
+            });
+        }
+        let name = first.trim_matches('_');
+        let arguments = attribute_arguments(tokens, &[first.as_str()])
+            .map(split_arguments)
+            .unwrap_or_default();
+        let is_swift = name.starts_with("swift_")
+            || (name == "availability" && arguments.first().map(|a| a.as_str()) == Some("swift"))
+            || first.contains("_SWIFT_");
+        is_swift.then(|| Self {
+            name: name.to_string(),
+            arguments,
+        })
+    }
+}
+
+/// The Swift attributes of `entity`, in order and without duplicates, e.g.
+/// from a macro expanding to several attributes.
+pub(crate) fn get_swift_attributes(entity: &clang::Entity) -> Vec<SwiftAttribute> {
+    let mut attributes: Vec<SwiftAttribute> = vec![];
+    entity
+        .get_children()
+        .iter()
+        .filter(|e| e.get_kind() == clang::EntityKind::UnexposedAttr)
+        .filter_map(|e| SwiftAttribute::from_tokens(&attribute_tokens(e)))
+        .for_each(|attribute| {
+            if !attributes.contains(&attribute) {
+                attributes.push(attribute);
+            }
+        });
+    attributes
+}
+
+// Splits `tokens` at the top-level commas, joining the tokens of each
+// argument without the quotes of string literals.
+fn split_arguments(tokens: &[String]) -> Vec<String> {
+    let mut arguments = vec![];
+    let mut argument = String::new();
+    let mut depth = 0;
+    for token in tokens {
+        match token.as_str() {
+            "," if depth == 0 => {
+                arguments.push(std::mem::take(&mut argument));
+                continue;
+            }
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            _ => {}
+        }
+        argument.push_str(unquote(token));
+    }
+    if !argument.is_empty() {
+        arguments.push(argument);
+    }
+    arguments
+}
+
+fn unquote(token: &str) -> &str {
+    token
+        .strip_prefix('"')
+        .and_then(|token| token.strip_suffix('"'))
+        .unwrap_or(token)
+}
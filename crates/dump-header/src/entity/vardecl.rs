@@ -15,7 +15,7 @@ pub fn get_init_expr(entity: &clang::Entity) -> Option<InitExpr> {
     }
 }
 
-fn evaluate(entity: &clang::Entity) -> Option<InitValue> {
+pub(super) fn evaluate(entity: &clang::Entity) -> Option<InitValue> {
     if let Some(value) = entity.evaluate() {
         match value {
             clang::EvaluationResult::SignedInteger(value) => Some(InitValue::Int(value)),
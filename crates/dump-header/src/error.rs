@@ -1,10 +1,16 @@
-use clang::SourceError;
+use clang::{SaveError, SourceError};
 use thiserror::Error;
 
+use crate::diagnostic::Diagnostic;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
+    #[error("Framework not found: {0}")]
+    FrameworkNotFound(String),
+    #[error("clang reported {} error(s)", .0.len())]
+    Diagnostics(Vec<Diagnostic>),
     #[error("Source error")]
     Source {
         source: SourceError
@@ -12,7 +18,15 @@ pub enum Error {
     #[error("IO error")]
     Io {
         source: std::io::Error
-    }
+    },
+    #[error("Save error")]
+    Save {
+        source: SaveError
+    },
+    #[error("Serialization error")]
+    Serialization {
+        source: Box<dyn std::error::Error + Send + Sync>
+    },
 }
 
 #[derive(Error, Debug)]
@@ -0,0 +1,377 @@
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+
+use crate::ast::AstNode;
+use crate::cli::Format;
+use crate::diagnostic::Diagnostic;
+use crate::entity::{ConversionReport, Entry};
+use crate::error::Error;
+use crate::framework::Framework;
+use crate::headerfiletree::{HeaderFile, HeaderFileTree};
+use crate::typ::TypeTable;
+
+/// Version of the dump format, bumped on changes that break consumers.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// A value that can be written in any `Format`.
+///
+/// Newline-delimited JSON writes one entry per line, so a document is
+/// flattened into records: a header record, e.g. of a header file, followed
+/// by the records of its entries.
+pub trait Document: Serialize + DeserializeOwned {
+    fn to_records(&self) -> Vec<Record>;
+    fn from_records(records: Vec<Record>) -> Result<Self, Error>;
+}
+
+/// A line of newline-delimited JSON.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Record {
+    Header(RecordHeader),
+    Entry(Box<Entry>),
+    AstNode(AstNode),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RecordHeader {
+    Framework {
+        #[serde(default)]
+        format_version: u32,
+        name: String,
+        root_header: PathBuf,
+        root_path: PathBuf,
+        #[serde(default, skip_serializing_if = "TypeTable::is_empty")]
+        types: TypeTable,
+    },
+    HeaderFile {
+        #[serde(default)]
+        format_version: u32,
+        path: PathBuf,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        diagnostics: Vec<Diagnostic>,
+        #[serde(default, skip_serializing_if = "TypeTable::is_empty")]
+        types: TypeTable,
+        #[serde(default, skip_serializing_if = "ConversionReport::is_empty")]
+        report: ConversionReport,
+    },
+}
+
+pub fn write<T: Document>(writer: impl Write, value: &T, format: Format) -> Result<(), Error> {
+    let mut writer = std::io::BufWriter::new(writer);
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut writer, value).map_err(serialization_error)?;
+            writeln!(writer).map_err(|e| Error::Io { source: e })?;
+        }
+        Format::JsonCompact => {
+            serde_json::to_writer(&mut writer, value).map_err(serialization_error)?;
+            writeln!(writer).map_err(|e| Error::Io { source: e })?;
+        }
+        Format::Ndjson => {
+            for record in value.to_records() {
+                serde_json::to_writer(&mut writer, &record).map_err(serialization_error)?;
+                writeln!(writer).map_err(|e| Error::Io { source: e })?;
+            }
+        }
+        // through JSON values, as serde_yaml writes the enums inside the
+        // internally tagged `Entry` as YAML tags that it cannot read back
+        Format::Yaml => {
+            let value = serde_json::to_value(value).map_err(serialization_error)?;
+            serde_yaml::to_writer(&mut writer, &value).map_err(serialization_error)?
+        }
+        Format::Cbor => ciborium::into_writer(value, &mut writer).map_err(serialization_error)?,
+        // as maps, so that the field names are kept
+        Format::MessagePack => value
+            .serialize(&mut rmp_serde::Serializer::new(&mut writer).with_struct_map())
+            .map_err(serialization_error)?,
+    }
+    writer.flush().map_err(|e| Error::Io { source: e })
+}
+
+pub fn read<T: Document>(reader: impl Read, format: Format) -> Result<T, Error> {
+    let reader = std::io::BufReader::new(reader);
+    match format {
+        Format::Json | Format::JsonCompact => {
+            serde_json::from_reader(reader).map_err(serialization_error)
+        }
+        Format::Ndjson => {
+            let mut records = vec![];
+            for line in reader.lines() {
+                let line = line.map_err(|e| Error::Io { source: e })?;
+                if !line.trim().is_empty() {
+                    records.push(serde_json::from_str(&line).map_err(serialization_error)?);
+                }
+            }
+            T::from_records(records)
+        }
+        Format::Yaml => {
+            let value: serde_json::Value =
+                serde_yaml::from_reader(reader).map_err(serialization_error)?;
+            serde_json::from_value(value).map_err(serialization_error)
+        }
+        Format::Cbor => ciborium::from_reader(reader).map_err(serialization_error),
+        Format::MessagePack => rmp_serde::from_read(reader).map_err(serialization_error),
+    }
+}
+
+pub fn to_vec<T: Document>(value: &T, format: Format) -> Result<Vec<u8>, Error> {
+    let mut buf = vec![];
+    write(&mut buf, value, format)?;
+    Ok(buf)
+}
+
+pub fn from_slice<T: Document>(bytes: &[u8], format: Format) -> Result<T, Error> {
+    read(bytes, format)
+}
+
+/// Loads the header files of a dump of a header file, a framework or a list
+/// of either, with the named records inlined. The header files of a
+/// framework are in the order of their paths.
+pub fn load_header_files(path: &Path, format: Format) -> Result<Vec<HeaderFile>, Error> {
+    let bytes = std::fs::read(path).map_err(|e| Error::Io { source: e })?;
+    let mut header_files = match from_slice::<Dump>(&bytes, format)? {
+        Dump::HeaderFiles(header_files) => header_files,
+        Dump::Frameworks(frameworks) => frameworks
+            .into_iter()
+            .flat_map(|mut framework| {
+                framework.inline_types();
+                let mut header_files: Vec<HeaderFile> = framework.iter().cloned().collect();
+                header_files.sort_by(|a, b| a.path.cmp(&b.path));
+                header_files
+            })
+            .collect(),
+    };
+    header_files
+        .iter_mut()
+        .for_each(|header_file| header_file.inline_types());
+    Ok(header_files)
+}
+
+fn serialization_error(e: impl std::error::Error + Send + Sync + 'static) -> Error {
+    Error::Serialization {
+        source: Box::new(e),
+    }
+}
+
+fn invalid_records(message: &str) -> Error {
+    Error::Serialization {
+        source: message.into(),
+    }
+}
+
+fn header_file_records(header_file: &HeaderFile, records: &mut Vec<Record>) {
+    records.push(Record::Header(RecordHeader::HeaderFile {
+        format_version: header_file.format_version,
+        path: header_file.path.clone(),
+        diagnostics: header_file.diagnostics.clone(),
+        types: header_file.types.clone(),
+        report: header_file.report.clone(),
+    }));
+    records.extend(
+        header_file
+            .entries
+            .iter()
+            .map(|entry| Record::Entry(Box::new(entry.clone()))),
+    );
+}
+
+fn framework_records(framework: &Framework, records: &mut Vec<Record>) {
+    records.push(Record::Header(RecordHeader::Framework {
+        format_version: framework.format_version(),
+        name: framework.name().to_string(),
+        root_header: framework.root_header().to_path_buf(),
+        root_path: framework.header_file_tree().root_path().to_path_buf(),
+        types: framework.types().clone(),
+    }));
+    let mut header_files: Vec<&HeaderFile> = framework.iter().collect();
+    header_files.sort_by(|a, b| a.path.cmp(&b.path));
+    header_files
+        .into_iter()
+        .for_each(|header_file| header_file_records(header_file, records));
+}
+
+// Groups the entries following each header file record.
+fn collect_header_files(
+    records: &mut std::iter::Peekable<impl Iterator<Item = Record>>,
+) -> Vec<HeaderFile> {
+    let mut header_files = vec![];
+    while let Some(Record::Header(RecordHeader::HeaderFile {
+        format_version,
+        path,
+        diagnostics,
+        types,
+        report,
+    })) =
+        records.next_if(|record| matches!(record, Record::Header(RecordHeader::HeaderFile { .. })))
+    {
+        let mut header_file = HeaderFile::new(path, vec![]);
+        header_file.format_version = format_version;
+        header_file.diagnostics = diagnostics;
+        header_file.types = types;
+        header_file.report = report;
+        while let Some(Record::Entry(entry)) =
+            records.next_if(|record| matches!(record, Record::Entry(_)))
+        {
+            header_file.entries.push(*entry);
+        }
+        header_files.push(header_file);
+    }
+    header_files
+}
+
+impl Document for HeaderFile {
+    fn to_records(&self) -> Vec<Record> {
+        let mut records = vec![];
+        header_file_records(self, &mut records);
+        records
+    }
+
+    fn from_records(records: Vec<Record>) -> Result<Self, Error> {
+        let mut records = records.into_iter().peekable();
+        let mut header_files = collect_header_files(&mut records);
+        if header_files.len() != 1 || records.next().is_some() {
+            return Err(invalid_records("expected a single header file"));
+        }
+        Ok(header_files.remove(0))
+    }
+}
+
+impl Document for Vec<HeaderFile> {
+    fn to_records(&self) -> Vec<Record> {
+        let mut records = vec![];
+        self.iter()
+            .for_each(|header_file| header_file_records(header_file, &mut records));
+        records
+    }
+
+    fn from_records(records: Vec<Record>) -> Result<Self, Error> {
+        let mut records = records.into_iter().peekable();
+        let header_files = collect_header_files(&mut records);
+        if records.next().is_some() {
+            return Err(invalid_records("expected header files"));
+        }
+        Ok(header_files)
+    }
+}
+
+impl Document for Vec<Framework> {
+    fn to_records(&self) -> Vec<Record> {
+        let mut records = vec![];
+        self.iter()
+            .for_each(|framework| framework_records(framework, &mut records));
+        records
+    }
+
+    fn from_records(records: Vec<Record>) -> Result<Self, Error> {
+        let mut records = records.into_iter().peekable();
+        let mut frameworks = vec![];
+        while let Some(record) = records.next() {
+            let Record::Header(RecordHeader::Framework {
+                format_version,
+                name,
+                root_header,
+                root_path,
+                types,
+            }) = record
+            else {
+                return Err(invalid_records("expected a framework"));
+            };
+            let mut tree = HeaderFileTree::new(&root_path);
+            collect_header_files(&mut records)
+                .into_iter()
+                .for_each(|header_file| tree.insert(header_file));
+            let mut framework = Framework::new(name, root_header, tree);
+            framework.format_version = format_version;
+            framework.types = types;
+            frameworks.push(framework);
+        }
+        Ok(frameworks)
+    }
+}
+
+impl Document for Framework {
+    fn to_records(&self) -> Vec<Record> {
+        let mut records = vec![];
+        framework_records(self, &mut records);
+        records
+    }
+
+    fn from_records(records: Vec<Record>) -> Result<Self, Error> {
+        let mut frameworks = Vec::<Framework>::from_records(records)?;
+        if frameworks.len() != 1 {
+            return Err(invalid_records("expected a single framework"));
+        }
+        Ok(frameworks.remove(0))
+    }
+}
+
+impl Document for Vec<AstNode> {
+    fn to_records(&self) -> Vec<Record> {
+        self.iter().cloned().map(Record::AstNode).collect()
+    }
+
+    fn from_records(records: Vec<Record>) -> Result<Self, Error> {
+        records
+            .into_iter()
+            .map(|record| match record {
+                Record::AstNode(node) => Ok(node),
+                _ => Err(invalid_records("expected an AST node")),
+            })
+            .collect()
+    }
+}
+
+// A dump of unknown shape, told apart by its first record so that it is
+// parsed once
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Dump {
+    HeaderFiles(Vec<HeaderFile>),
+    Frameworks(Vec<Framework>),
+}
+
+impl<'de> Deserialize<'de> for Dump {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+        use serde_json::Value;
+
+        let value = Value::deserialize(deserializer)?;
+        let first = match &value {
+            Value::Array(values) => values.first(),
+            value => Some(value),
+        };
+        let is_framework = first
+            .and_then(Value::as_object)
+            .is_some_and(|object| object.contains_key("header_file_tree"));
+        let dump = match (value, is_framework) {
+            (value @ Value::Array(_), true) => serde_json::from_value(value).map(Dump::Frameworks),
+            (value @ Value::Array(_), false) => {
+                serde_json::from_value(value).map(Dump::HeaderFiles)
+            }
+            (value, true) => serde_json::from_value(value).map(|f| Dump::Frameworks(vec![f])),
+            (value, false) => serde_json::from_value(value).map(|h| Dump::HeaderFiles(vec![h])),
+        };
+        dump.map_err(D::Error::custom)
+    }
+}
+
+impl Document for Dump {
+    fn to_records(&self) -> Vec<Record> {
+        match self {
+            Dump::HeaderFiles(header_files) => header_files.to_records(),
+            Dump::Frameworks(frameworks) => frameworks.to_records(),
+        }
+    }
+
+    fn from_records(records: Vec<Record>) -> Result<Self, Error> {
+        match records.first() {
+            Some(Record::Header(RecordHeader::Framework { .. })) => {
+                Vec::<Framework>::from_records(records).map(Dump::Frameworks)
+            }
+            _ => Vec::<HeaderFile>::from_records(records).map(Dump::HeaderFiles),
+        }
+    }
+}
@@ -1,29 +1,51 @@
 use clang::TranslationUnit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
+use std::collections::BTreeMap;
+
 use crate::{
-    error::Error, headerfiletree::{HeaderFile, HeaderFileTree}, parser::Parser
+    apinotes::{load_api_notes, ApiNotes},
+    diagnostic::{get_diagnostics, Diagnostic},
+    entity::ConvertOptions, error::Error, format::FORMAT_VERSION, headerfiletree::{HeaderFile, HeaderFileTree}, parser::Parser, typ::TypeTable
 };
 
 // static FRAMEWORKS: &[&str] = &["Foundation", "UIKit"];
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Framework {
+    /// `FORMAT_VERSION` of the dump, 0 before versioning
+    #[serde(default)]
+    pub(crate) format_version: u32,
     name: String,
     root_header: PathBuf,
     header_file_tree: HeaderFileTree,
+    /// Named records referred to by `Typ::TypeRef` in the header files
+    #[serde(default, skip_serializing_if = "TypeTable::is_empty")]
+    pub(crate) types: TypeTable,
 }
 
 impl Framework {
-    fn new(name: String, root_header: PathBuf, header_file_tree: HeaderFileTree) -> Self {
+    pub(crate) fn new(
+        name: String,
+        root_header: PathBuf,
+        header_file_tree: HeaderFileTree,
+    ) -> Self {
         Framework {
+            format_version: FORMAT_VERSION,
             name,
             root_header,
             header_file_tree,
+            types: TypeTable::default(),
         }
     }
 
+    pub fn format_version(&self) -> u32 {
+        self.format_version
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -35,6 +57,93 @@ impl Framework {
     pub fn iter(&self) -> impl Iterator<Item = &HeaderFile> {
         self.header_file_tree.iter()
     }
+
+    pub fn header_file_tree(&self) -> &HeaderFileTree {
+        &self.header_file_tree
+    }
+
+    pub fn types(&self) -> &TypeTable {
+        &self.types
+    }
+
+    /// Moves the named records of all the header files into a single table,
+    /// the header files in the order of their paths.
+    pub fn use_type_table(&mut self) {
+        let mut header_files: Vec<&mut HeaderFile> = self.header_file_tree.iter_mut().collect();
+        header_files.sort_by(|a, b| a.path.cmp(&b.path));
+        header_files.into_iter().for_each(|header_file| {
+            self.types.merge(std::mem::take(&mut header_file.types));
+            self.types.intern_entries(&mut header_file.entries);
+        });
+    }
+
+    /// Replaces the references to the type tables with the records.
+    pub fn inline_types(&mut self) {
+        let types = std::mem::take(&mut self.types);
+        self.header_file_tree.iter_mut().for_each(|header_file| {
+            header_file.inline_types();
+            types.inline_entries(&mut header_file.entries);
+        });
+    }
+
+    pub fn evaluate_macros(&mut self, parser: &Parser) -> Result<(), Error> {
+        self.header_file_tree.evaluate_macros(parser)
+    }
+
+    /// Merges the API notes files in the directories of the header files,
+    /// e.g. `Headers/Foo.apinotes`, into their entries.
+    pub fn apply_api_notes(&mut self) -> Result<(), Error> {
+        let mut api_notes_by_dir: BTreeMap<PathBuf, Vec<ApiNotes>> = BTreeMap::new();
+        for header_file in self.header_file_tree.iter_mut() {
+            let Some(dir) = header_file.path.parent() else {
+                continue;
+            };
+            if !api_notes_by_dir.contains_key(dir) {
+                api_notes_by_dir.insert(dir.to_path_buf(), load_api_notes(dir)?);
+            }
+            api_notes_by_dir[dir]
+                .iter()
+                .for_each(|api_notes| api_notes.apply(&mut header_file.entries));
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+}
+
+/// Lists the names of the `*.framework` bundles with an umbrella header
+/// (`Foo.framework/Headers/Foo.h`) in `search_paths`, sorted and without
+/// duplicates.
+pub fn find_frameworks(search_paths: &[PathBuf]) -> std::io::Result<Vec<String>> {
+    let mut names = vec![];
+    for search_path in search_paths {
+        for entry in std::fs::read_dir(search_path)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .map(|ext| ext == "framework")
+                .unwrap_or(false)
+            {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    if path.join("Headers").join(format!("{}.h", name)).is_file() {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
+    }
+    names.sort();
+    names.dedup();
+    Ok(names)
 }
 
 #[derive(Debug)]
@@ -67,23 +176,20 @@ impl<'a> FrameworkUnit<'a> {
     ) -> Result<Self, Error> {
         let root_header = format!("#include <{}/{}.h>", name, name);
         let (tu, _) = parser.parse_content(&root_header)?;
-        let root_header = Self::get_root_header(&tu);
+        let root_header =
+            Self::get_root_header(&tu).ok_or(Error::FrameworkNotFound(name.to_string()))?;
         let framework = Self::new(name.to_string(), root_header, tu);
         Ok(framework)
     }
 
-    fn get_root_header(tu: &TranslationUnit<'a>) -> PathBuf {
-        tu.get_entity()
-            .get_children()
-            .iter()
-            .find_map(|entity| {
-                if entity.get_kind() == clang::EntityKind::InclusionDirective {
-                    entity.get_file().map(|f| f.get_path())
-                } else {
-                    None
-                }
-            })
-            .unwrap()
+    fn get_root_header(tu: &TranslationUnit<'a>) -> Option<PathBuf> {
+        tu.get_entity().get_children().iter().find_map(|entity| {
+            if entity.get_kind() == clang::EntityKind::InclusionDirective {
+                entity.get_file().map(|f| f.get_path())
+            } else {
+                None
+            }
+        })
     }
 
     pub fn root_dir(&self) -> &Path {
@@ -94,10 +200,17 @@ impl<'a> FrameworkUnit<'a> {
         path.starts_with(self.root_dir())
     }
 
-    pub fn dump(&self) -> Framework {
+    /// All the diagnostics reported while parsing the framework.
+    pub fn diagnostics(&self, options: &ConvertOptions) -> Vec<Diagnostic> {
+        get_diagnostics(&self.tu, None, options)
+    }
+
+    pub fn dump(&self, options: &ConvertOptions) -> Framework {
         let root_header = self.root_header.clone();
         let header_file_tree =
-            HeaderFileTree::from_root_header(&root_header, &self.tu, |path| self.include(path));
+            HeaderFileTree::from_root_header(&root_header, &self.tu, options, |path| {
+                self.include(path)
+            });
         Framework::new(self.name.clone(), root_header, header_file_tree)
     }
 }
@@ -1,41 +1,121 @@
 use std::io::Write;
 use std::path::PathBuf;
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
 
 use clang::TranslationUnit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::entity::{convert_entity, Entry};
+use crate::apinotes::load_api_notes;
+use crate::diagnostic::{get_diagnostics, Diagnostic};
+use crate::entity::{
+    convert_entities_with_report, evaluate_macros, ConversionReport, ConvertOptions, Entry,
+};
+use crate::error::Error;
+use crate::format::FORMAT_VERSION;
+use crate::parser::Parser;
+use crate::typ::TypeTable;
 use crate::utils::{get_file_location_path, is_in_file};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct HeaderFile {
+    /// `FORMAT_VERSION` of the dump, 0 before versioning
+    #[serde(default)]
+    pub format_version: u32,
     pub path: PathBuf,
     pub entries: Vec<Entry>,
+    /// Diagnostics reported in this header file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
+    /// Named records referred to by `Typ::TypeRef` in `entries`
+    #[serde(default, skip_serializing_if = "TypeTable::is_empty")]
+    pub types: TypeTable,
+    /// Entities of this header file left out of `entries`
+    #[serde(default, skip_serializing_if = "ConversionReport::is_empty")]
+    pub report: ConversionReport,
 }
 
 impl HeaderFile {
     pub fn new(path: PathBuf, entries: Vec<Entry>) -> Self {
-        HeaderFile { entries, path }
+        HeaderFile {
+            format_version: FORMAT_VERSION,
+            entries,
+            path,
+            diagnostics: vec![],
+            types: TypeTable::default(),
+            report: ConversionReport::default(),
+        }
     }
 
     pub fn from_path(path: &PathBuf, tu: &TranslationUnit) -> Self {
-        let mut entries = vec![];
-        tu.get_entity().get_children().iter().for_each(|entity| {
-            if is_in_file(entity, path) {
-                if let Some(entry) = convert_entity(entity) {
-                    entries.push(entry);
-                }
-            }
-        });
-        Self::new(path.clone(), entries)
+        Self::from_path_with_options(path, tu, &ConvertOptions::default())
+    }
+
+    pub fn from_path_with_options(
+        path: &PathBuf,
+        tu: &TranslationUnit,
+        options: &ConvertOptions,
+    ) -> Self {
+        let entities: Vec<clang::Entity> = tu
+            .get_entity()
+            .get_children()
+            .into_iter()
+            .filter(|entity| is_in_file(entity, path))
+            .collect();
+        Self::from_entities(path, &entities, tu, options)
+    }
+
+    /// Converts `entities`, the top-level entities of `tu` in the file `path`.
+    pub fn from_entities(
+        path: &Path,
+        entities: &[clang::Entity],
+        tu: &TranslationUnit,
+        options: &ConvertOptions,
+    ) -> Self {
+        let mut report = ConversionReport::default();
+        let entries = convert_entities_with_report(entities, options, &mut report);
+        let mut header_file = Self::new(path.to_path_buf(), entries);
+        header_file.report = report;
+        header_file.diagnostics = get_diagnostics(tu, Some(path), options);
+        header_file
+    }
+
+    /// Evaluates the object-like macros of this header file with `parser`.
+    pub fn evaluate_macros(&mut self, parser: &Parser) -> Result<(), Error> {
+        evaluate_macros(parser, &self.path, self.entries.iter_mut())
+    }
+
+    /// Merges the API notes files in the directory of this header file into
+    /// the entries.
+    pub fn apply_api_notes(&mut self) -> Result<(), Error> {
+        let Some(dir) = self.path.parent() else {
+            return Ok(());
+        };
+        load_api_notes(dir)?
+            .iter()
+            .for_each(|api_notes| api_notes.apply(&mut self.entries));
+        Ok(())
+    }
+
+    /// Moves the named records of the entries into `types`.
+    pub fn use_type_table(&mut self) {
+        self.types.intern_entries(&mut self.entries);
+    }
+
+    /// Replaces the references to `types` with the records, as in a dump
+    /// without a type table.
+    pub fn inline_types(&mut self) {
+        std::mem::take(&mut self.types).inline_entries(&mut self.entries);
     }
 
     pub fn get_include_directives(&self) -> Vec<(String, PathBuf)> {
         self.entries
             .iter()
             .filter_map(|entry| match entry {
-                Entry::InclusionDirective { name, path } => Some((name.clone(), path.clone())),
+                Entry::InclusionDirective { name, path, .. } => Some((name.clone(), path.clone())),
                 _ => None,
             })
             .collect()
@@ -46,49 +126,70 @@ impl HeaderFile {
         file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
         Ok(())
     }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct HeaderFileTree {
     root_path: PathBuf,
-    path_entry_hash_map: HashMap<PathBuf, HeaderFile>,
+    path_entry_hash_map: BTreeMap<PathBuf, HeaderFile>,
 }
 
 impl HeaderFileTree {
-    fn new(root_filepath: &Path) -> Self {
-        let path_entry_hash_map: HashMap<PathBuf, HeaderFile> = HashMap::new();
+    pub(crate) fn new(root_filepath: &Path) -> Self {
+        let path_entry_hash_map: BTreeMap<PathBuf, HeaderFile> = BTreeMap::new();
         HeaderFileTree {
             root_path: root_filepath.to_path_buf(),
             path_entry_hash_map,
         }
     }
 
-    pub fn from_root_header<F>(root_header: &Path, tu: &TranslationUnit, include_cb: F) -> Self
+    pub fn from_root_header<F>(
+        root_header: &Path,
+        tu: &TranslationUnit,
+        options: &ConvertOptions,
+        include_cb: F,
+    ) -> Self
     where
         F: Fn(&Path) -> bool,
     {
         let mut tree = Self::new(root_header);
-        tu.get_entity().get_children().iter().for_each(|entity| {
-            if let Some(header_file_path) = get_file_location_path(entity) {
-                if tree.get(&header_file_path).is_none() && include_cb(&header_file_path) {
-                    if std::env::var("DEBUG").is_ok() {
-                        eprintln!("Adding header file: {:?}", header_file_path)
-                    }
-                    let header_file = HeaderFile::from_path(&header_file_path, tu);
-                    tree.insert(header_file);
+        partition_by_file(tu, include_cb)
+            .iter()
+            .for_each(|(header_file_path, entities)| {
+                if std::env::var("DEBUG").is_ok() {
+                    eprintln!("Adding header file: {:?}", header_file_path)
                 }
-            }
-        });
+                let header_file =
+                    HeaderFile::from_entities(header_file_path, entities, tu, options);
+                tree.insert(header_file);
+            });
         tree
     }
 
-    pub fn get(&self, path: &PathBuf) -> Option<HeaderFileNode> {
+    /// Evaluates the object-like macros of all the header files in a single
+    /// translation unit of the root header.
+    pub fn evaluate_macros(&mut self, parser: &Parser) -> Result<(), Error> {
+        evaluate_macros(
+            parser,
+            &self.root_path,
+            self.path_entry_hash_map
+                .values_mut()
+                .flat_map(|header_file| header_file.entries.iter_mut()),
+        )
+    }
+
+    pub fn get(&self, path: &PathBuf) -> Option<HeaderFileNode<'_>> {
         self.path_entry_hash_map
             .get(path)
             .map(|hf| HeaderFileNode::new(hf, &self.path_entry_hash_map))
     }
 
-    fn insert(&mut self, file: HeaderFile) {
+    pub(crate) fn insert(&mut self, file: HeaderFile) {
         self.path_entry_hash_map.insert(file.path.clone(), file);
     }
 
@@ -96,21 +197,60 @@ impl HeaderFileTree {
         self.path_entry_hash_map.values()
     }
 
-    pub fn get_root(&self) -> Option<HeaderFileNode> {
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut HeaderFile> {
+        self.path_entry_hash_map.values_mut()
+    }
+
+    pub fn root_path(&self) -> &Path {
+        &self.root_path
+    }
+
+    pub fn get_root(&self) -> Option<HeaderFileNode<'_>> {
         self.get(&self.root_path)
     }
 }
 
+/// Groups the top-level entities of `tu` by the file they are in, in a single
+/// pass, keeping the files for which `include_cb` is true.
+pub fn partition_by_file<'tu, F>(
+    tu: &'tu TranslationUnit,
+    include_cb: F,
+) -> BTreeMap<PathBuf, Vec<clang::Entity<'tu>>>
+where
+    F: Fn(&Path) -> bool,
+{
+    let mut files: BTreeMap<PathBuf, Vec<clang::Entity<'tu>>> = BTreeMap::new();
+    let mut excluded: BTreeSet<PathBuf> = BTreeSet::new();
+    tu.get_entity()
+        .get_children()
+        .into_iter()
+        .for_each(|entity| {
+            let Some(path) = get_file_location_path(&entity) else {
+                return;
+            };
+            if let Some(entities) = files.get_mut(&path) {
+                entities.push(entity);
+            } else if !excluded.contains(&path) {
+                if include_cb(&path) {
+                    files.insert(path, vec![entity]);
+                } else {
+                    excluded.insert(path);
+                }
+            }
+        });
+    files
+}
+
 #[derive(Debug, Clone)]
 pub struct HeaderFileNode<'a> {
     header_file: &'a HeaderFile,
-    path_entry_hash_map: &'a HashMap<PathBuf, HeaderFile>,
+    path_entry_hash_map: &'a BTreeMap<PathBuf, HeaderFile>,
 }
 
 impl<'a> HeaderFileNode<'a> {
     pub fn new(
         header_file: &'a HeaderFile,
-        path_entry_hash_map: &'a HashMap<PathBuf, HeaderFile>,
+        path_entry_hash_map: &'a BTreeMap<PathBuf, HeaderFile>,
     ) -> Self {
         HeaderFileNode {
             header_file,
@@ -126,7 +266,7 @@ impl<'a> HeaderFileNode<'a> {
         &self.header_file.path
     }
 
-    pub fn get_children(&self) -> Vec<HeaderFileNode> {
+    pub fn get_children(&self) -> Vec<HeaderFileNode<'_>> {
         self.header_file
             .get_include_directives()
             .iter()
@@ -0,0 +1,398 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{Entry, ObjCMethodDecl, ObjCPropertyDecl};
+use crate::headerfiletree::HeaderFile;
+use crate::item::type_name;
+use crate::query::selector_text;
+
+/// The kind of declaration a member of a resolved interface comes from.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerKind {
+    Interface,
+    Category,
+    /// A category without a name, `@interface Foo ()`
+    Extension,
+    Protocol,
+}
+
+/// Where a member of a resolved interface is declared.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct MemberOrigin {
+    pub kind: ContainerKind,
+    /// Named as in `query`, e.g. `NSString`, `NSString (Extras)` or
+    /// `<NSCopying>`
+    pub container: String,
+    /// The class of the hierarchy declaring the member, or adopting the
+    /// protocol declaring it
+    pub class_name: String,
+    pub header: PathBuf,
+}
+
+/// A property or method of a resolved interface.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct ResolvedMember<T> {
+    /// The nearest declaration, from the class to its root class
+    pub decl: T,
+    pub origin: MemberOrigin,
+    /// The other declarations of the member, hidden by the nearest one,
+    /// e.g. in a superclass or an adopted protocol
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<MemberOrigin>,
+}
+
+/// The complete interface of an Objective-C class: the members declared by
+/// the class, its categories, its superclasses and their categories, and by
+/// the protocols any of them adopts.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct ResolvedInterface {
+    pub name: String,
+    pub header: PathBuf,
+    /// From the superclass to the root class
+    pub superclasses: Vec<String>,
+    /// Adopted by the class, its superclasses or their categories, or
+    /// inherited by those protocols
+    pub protocols: Vec<String>,
+    /// The categories of the class, e.g. `NSString (Extras)`
+    pub categories: Vec<String>,
+    pub properties: Vec<ResolvedMember<ObjCPropertyDecl>>,
+    pub instance_methods: Vec<ResolvedMember<ObjCMethodDecl>>,
+    pub class_methods: Vec<ResolvedMember<ObjCMethodDecl>>,
+    /// The superclasses and protocols, e.g. `<NSCopying>`, missing from the
+    /// header files, whose members are left out
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unresolved: Vec<String>,
+}
+
+/// The Objective-C classes, categories and protocols of header files, e.g.
+/// of `Framework::iter` or `HeaderFileTree::iter`, by name.
+pub struct InterfaceIndex<'a> {
+    classes: HashMap<&'a str, (&'a Entry, &'a Path)>,
+    categories: HashMap<&'a str, Vec<(&'a Entry, &'a Path)>>,
+    protocols: HashMap<&'a str, (&'a Entry, &'a Path)>,
+}
+
+// The members collected so far, by name, with the nearest declaration first.
+#[derive(Default)]
+struct Members {
+    properties: Vec<ResolvedMember<ObjCPropertyDecl>>,
+    instance_methods: Vec<ResolvedMember<ObjCMethodDecl>>,
+    class_methods: Vec<ResolvedMember<ObjCMethodDecl>>,
+    index: HashMap<(char, String), usize>,
+}
+
+impl<'a> InterfaceIndex<'a> {
+    /// Indexes the declarations of `header_files`. The first declaration of
+    /// a class or protocol wins; the categories are kept in order.
+    pub fn new(header_files: impl IntoIterator<Item = &'a HeaderFile>) -> Self {
+        let mut index = Self {
+            classes: HashMap::new(),
+            categories: HashMap::new(),
+            protocols: HashMap::new(),
+        };
+        for header_file in header_files {
+            let header = header_file.path.as_path();
+            for entry in &header_file.entries {
+                match entry {
+                    Entry::ObjCInterfaceDecl { name, .. } => {
+                        index.classes.entry(name).or_insert((entry, header));
+                    }
+                    Entry::ObjCCategoryDecl { class_name, .. } => index
+                        .categories
+                        .entry(class_name)
+                        .or_default()
+                        .push((entry, header)),
+                    Entry::ObjCProtocolDecl { name, .. } => {
+                        index.protocols.entry(name).or_insert((entry, header));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        index
+    }
+
+    /// The names of the indexed classes, sorted.
+    pub fn class_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.classes.keys().copied().collect();
+        names.sort();
+        names
+    }
+
+    /// The interface of `class_name`, `None` if the class is not declared.
+    ///
+    /// For each class from `class_name` to the root class, the members of
+    /// the class come first, then those of its categories and then those of
+    /// the protocols they adopt, so that a member is attributed to its
+    /// nearest declaration.
+    pub fn resolve(&self, class_name: &str) -> Option<ResolvedInterface> {
+        let (_, header) = self.classes.get(class_name)?;
+        let mut resolved = ResolvedInterface {
+            name: class_name.to_string(),
+            header: header.to_path_buf(),
+            superclasses: vec![],
+            protocols: vec![],
+            categories: vec![],
+            properties: vec![],
+            instance_methods: vec![],
+            class_methods: vec![],
+            unresolved: vec![],
+        };
+        let mut members = Members::default();
+        let mut seen_classes: HashSet<&str> = HashSet::new();
+        let mut seen_protocols: HashSet<&str> = HashSet::new();
+        let mut next = Some(class_name);
+        while let Some(name) = next.take() {
+            // a cycle in an invalid hierarchy
+            if !seen_classes.insert(name) {
+                break;
+            }
+            if name != class_name {
+                resolved.superclasses.push(name.to_string());
+            }
+            let Some((
+                Entry::ObjCInterfaceDecl {
+                    superclass,
+                    protocols,
+                    properties,
+                    instance_methods,
+                    class_methods,
+                    ..
+                },
+                header,
+            )) = self.classes.get(name)
+            else {
+                resolved.unresolved.push(name.to_string());
+                break;
+            };
+            let origin = MemberOrigin {
+                kind: ContainerKind::Interface,
+                container: name.to_string(),
+                class_name: name.to_string(),
+                header: header.to_path_buf(),
+            };
+            members.add(&origin, properties, instance_methods, class_methods);
+            let mut adopted: Vec<&str> = protocols.iter().map(String::as_str).collect();
+            for (category, header) in self.categories.get(name).into_iter().flatten() {
+                let Entry::ObjCCategoryDecl {
+                    name: category_name,
+                    protocols,
+                    properties,
+                    instance_methods,
+                    class_methods,
+                    ..
+                } = category
+                else {
+                    continue;
+                };
+                let category_name = category_name.as_deref().unwrap_or("");
+                let origin = MemberOrigin {
+                    kind: if category_name.is_empty() {
+                        ContainerKind::Extension
+                    } else {
+                        ContainerKind::Category
+                    },
+                    container: format!("{} ({})", name, category_name),
+                    class_name: name.to_string(),
+                    header: header.to_path_buf(),
+                };
+                if name == class_name {
+                    resolved.categories.push(origin.container.clone());
+                }
+                members.add(&origin, properties, instance_methods, class_methods);
+                adopted.extend(protocols.iter().map(String::as_str));
+            }
+            for protocol in adopted {
+                self.add_protocol(
+                    protocol,
+                    name,
+                    &mut seen_protocols,
+                    &mut members,
+                    &mut resolved,
+                );
+            }
+            next = Some(superclass.as_str()).filter(|superclass| !superclass.is_empty());
+        }
+        resolved.properties = members.properties;
+        resolved.instance_methods = members.instance_methods;
+        resolved.class_methods = members.class_methods;
+        Some(resolved)
+    }
+
+    // Adds the members of `protocol`, adopted by `class_name`, and of the
+    // protocols it inherits, unless already added.
+    fn add_protocol(
+        &self,
+        protocol: &'a str,
+        class_name: &str,
+        seen_protocols: &mut HashSet<&'a str>,
+        members: &mut Members,
+        resolved: &mut ResolvedInterface,
+    ) {
+        if !seen_protocols.insert(protocol) {
+            return;
+        }
+        resolved.protocols.push(protocol.to_string());
+        let Some((
+            Entry::ObjCProtocolDecl {
+                protocols,
+                properties,
+                instance_methods,
+                class_methods,
+                ..
+            },
+            header,
+        )) = self.protocols.get(protocol)
+        else {
+            resolved.unresolved.push(format!("<{}>", protocol));
+            return;
+        };
+        let origin = MemberOrigin {
+            kind: ContainerKind::Protocol,
+            container: format!("<{}>", protocol),
+            class_name: class_name.to_string(),
+            header: header.to_path_buf(),
+        };
+        members.add(&origin, properties, instance_methods, class_methods);
+        for inherited in protocols {
+            self.add_protocol(inherited, class_name, seen_protocols, members, resolved);
+        }
+    }
+}
+
+impl Members {
+    fn add(
+        &mut self,
+        origin: &MemberOrigin,
+        properties: &[ObjCPropertyDecl],
+        instance_methods: &[ObjCMethodDecl],
+        class_methods: &[ObjCMethodDecl],
+    ) {
+        for property in properties {
+            add_member(
+                &mut self.properties,
+                &mut self.index,
+                ('.', property.name.clone()),
+                property,
+                origin,
+            );
+        }
+        for method in instance_methods {
+            add_member(
+                &mut self.instance_methods,
+                &mut self.index,
+                ('-', method.name.clone()),
+                method,
+                origin,
+            );
+        }
+        for method in class_methods {
+            add_member(
+                &mut self.class_methods,
+                &mut self.index,
+                ('+', method.name.clone()),
+                method,
+                origin,
+            );
+        }
+    }
+}
+
+fn add_member<T: Clone>(
+    members: &mut Vec<ResolvedMember<T>>,
+    index: &mut HashMap<(char, String), usize>,
+    key: (char, String),
+    decl: &T,
+    origin: &MemberOrigin,
+) {
+    match index.get(&key) {
+        Some(i) => {
+            let member = &mut members[*i];
+            // e.g. a property redeclared readwrite in an extension
+            if member.origin != *origin && !member.overrides.contains(origin) {
+                member.overrides.push(origin.clone());
+            }
+        }
+        None => {
+            index.insert(key, members.len());
+            members.push(ResolvedMember {
+                decl: decl.clone(),
+                origin: origin.clone(),
+                overrides: vec![],
+            });
+        }
+    }
+}
+
+impl ResolvedInterface {
+    /// The interface in Objective-C syntax, each member followed by a
+    /// comment naming where it is declared, e.g.
+    /// `- (NSUInteger)length; // NSString, /F/NSString.h`.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("@interface {}", self.name);
+        if let Some(superclass) = self.superclasses.first() {
+            text.push_str(&format!(" : {}", superclass));
+        }
+        if !self.protocols.is_empty() {
+            text.push_str(&format!(" <{}>", self.protocols.join(", ")));
+        }
+        text.push_str(&format!(" // {}\n", self.header.display()));
+        for (label, names) in [
+            ("superclasses", &self.superclasses),
+            ("categories", &self.categories),
+            ("unresolved", &self.unresolved),
+        ] {
+            if !names.is_empty() {
+                text.push_str(&format!("// {}: {}\n", label, names.join(", ")));
+            }
+        }
+        for property in &self.properties {
+            let declaration = format!(
+                "@property {} {};",
+                type_name(&property.decl.objc_type),
+                property.decl.name
+            );
+            text.push_str(&member_line(declaration, property.decl.optional, property));
+        }
+        for (prefix, methods) in [("-", &self.instance_methods), ("+", &self.class_methods)] {
+            for method in methods {
+                let declaration = format!(
+                    "{} ({}){};",
+                    prefix,
+                    type_name(&method.decl.result_type),
+                    selector_text(&method.decl)
+                );
+                text.push_str(&member_line(declaration, method.decl.optional, method));
+            }
+        }
+        text.push_str("@end\n");
+        text
+    }
+}
+
+// e.g. `@optional - (void)foo; // <FooDelegate>, /F/Foo.h, overrides Bar`
+fn member_line<T>(declaration: String, optional: bool, member: &ResolvedMember<T>) -> String {
+    let mut line = String::new();
+    if optional {
+        line.push_str("@optional ");
+    }
+    line.push_str(&declaration);
+    line.push_str(&format!(
+        " // {}, {}",
+        member.origin.container,
+        member.origin.header.display()
+    ));
+    if !member.overrides.is_empty() {
+        let overrides: Vec<&str> = member
+            .overrides
+            .iter()
+            .map(|origin| origin.container.as_str())
+            .collect();
+        line.push_str(&format!(", overrides {}", overrides.join(", ")));
+    }
+    line.push('\n');
+    line
+}
@@ -0,0 +1,439 @@
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{
+    CXXFieldDecl, CXXMethodDecl, Entry, EnumConstantDecl, ObjCMethodDecl, ObjCPropertyDecl,
+    PlatformAvailability,
+};
+use crate::typ::Typ;
+
+#[derive(
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    ValueEnum,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum ItemKind {
+    Function,
+    Variable,
+    Typedef,
+    Struct,
+    Union,
+    Field,
+    Enum,
+    EnumConstant,
+    Macro,
+    Class,
+    Method,
+    Property,
+    Protocol,
+    Category,
+}
+
+/// A declaration of a dump, possibly a member of another, as compared by
+/// `diff` and searched by `query`.
+#[derive(Debug, Clone)]
+pub struct Item<'a> {
+    pub kind: ItemKind,
+    /// e.g. `point.x` for a field or `NSString -length` for a method
+    pub name: String,
+    /// The record, class or protocol of a member, e.g. `point` or
+    /// `<NSCopying>`, the class extended by a category for its members
+    pub container: Option<String>,
+    /// The types named by the declaration, e.g. the result and argument types
+    /// of a function. The fields of records are items of their own.
+    pub types: Vec<&'a Typ>,
+    /// Those of the parent for the fields and the enum constants
+    pub availability: clang::Availability,
+    pub platform_availability: &'a [PlatformAvailability],
+    pub source: ItemSource<'a>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ItemSource<'a> {
+    Entry(&'a Entry),
+    /// A `FieldDecl`, or the `UnionDecl` of an anonymous union member, at
+    /// `index` among the fields of its record
+    Field {
+        field: &'a Entry,
+        index: usize,
+    },
+    EnumConstant(&'a EnumConstantDecl),
+    CxxField {
+        field: &'a CXXFieldDecl,
+        index: usize,
+    },
+    CxxMethod(&'a CXXMethodDecl),
+    ObjCProperty(&'a ObjCPropertyDecl),
+    ObjCMethod {
+        method: &'a ObjCMethodDecl,
+        is_class_method: bool,
+    },
+}
+
+impl ItemSource<'_> {
+    /// The declaration as JSON.
+    pub fn to_value(&self) -> serde_json::Value {
+        let value = match self {
+            ItemSource::Entry(entry) | ItemSource::Field { field: entry, .. } => {
+                serde_json::to_value(entry)
+            }
+            ItemSource::EnumConstant(decl) => serde_json::to_value(decl),
+            ItemSource::CxxField { field, .. } => serde_json::to_value(field),
+            ItemSource::CxxMethod(method) => serde_json::to_value(method),
+            ItemSource::ObjCProperty(property) => serde_json::to_value(property),
+            ItemSource::ObjCMethod { method, .. } => serde_json::to_value(method),
+        };
+        value.unwrap_or_default()
+    }
+}
+
+/// The items of `entries` and of their members, in the order of the
+/// declarations. Namespaces and classes scope the names of their entries,
+/// e.g. `ns::f`.
+pub fn collect_items(entries: &[Entry]) -> Vec<Item<'_>> {
+    let mut items = vec![];
+    collect_entries(entries, "", &mut items);
+    items
+}
+
+pub(crate) fn type_name(ty: &Typ) -> &str {
+    ty.name().unwrap_or("<anonymous>")
+}
+
+fn platforms(platform_availability: &Option<Vec<PlatformAvailability>>) -> &[PlatformAvailability] {
+    platform_availability.as_deref().unwrap_or_default()
+}
+
+fn collect_entries<'a>(entries: &'a [Entry], scope: &str, items: &mut Vec<Item<'a>>) {
+    entries.iter().for_each(|entry| match entry {
+        Entry::FunctionDecl {
+            name,
+            arguments,
+            result_type,
+            ..
+        } => {
+            let types = std::iter::once(result_type)
+                .chain(arguments.iter().map(|arg| &arg.objc_type))
+                .collect();
+            items.push(entry_item(
+                entry,
+                ItemKind::Function,
+                format!("{}{}", scope, name),
+                types,
+            ));
+        }
+        Entry::VarDecl { name, ty, .. } => items.push(entry_item(
+            entry,
+            ItemKind::Variable,
+            format!("{}{}", scope, name),
+            vec![ty],
+        )),
+        Entry::TypedefDecl { name, ty, .. } | Entry::TypeAliasDecl { name, ty, .. } => {
+            items.push(entry_item(
+                entry,
+                ItemKind::Typedef,
+                format!("{}{}", scope, name),
+                vec![ty],
+            ))
+        }
+        Entry::StructDecl {
+            name: Some(name),
+            fields,
+            ..
+        }
+        | Entry::UnionDecl {
+            name: Some(name),
+            fields,
+            ..
+        } => {
+            let kind = if matches!(entry, Entry::UnionDecl { .. }) {
+                ItemKind::Union
+            } else {
+                ItemKind::Struct
+            };
+            let name = format!("{}{}", scope, name);
+            let (availability, platform_availability) = entry_availability(entry);
+            items.push(entry_item(entry, kind, name.clone(), vec![]));
+            fields.iter().enumerate().for_each(|(index, field)| {
+                let (field_name, ty) = match field {
+                    Entry::FieldDecl { name, ty, .. } => (name.clone(), ty),
+                    // an anonymous union member
+                    Entry::UnionDecl { ty, .. } => (format!("<anonymous {}>", index), ty),
+                    _ => return,
+                };
+                items.push(Item {
+                    kind: ItemKind::Field,
+                    name: format!("{}.{}", name, field_name),
+                    container: Some(name.clone()),
+                    types: vec![ty],
+                    availability,
+                    platform_availability,
+                    source: ItemSource::Field { field, index },
+                });
+            });
+        }
+        Entry::EnumDecl {
+            decls,
+            name,
+            is_anonymous,
+            typedef_name,
+            ty,
+            availability,
+            platform_availability,
+            ..
+        } => {
+            // the placeholder of an anonymous enum changes with its line
+            let enum_name = if *is_anonymous {
+                typedef_name.as_ref()
+            } else {
+                Some(name)
+            };
+            if let Some(enum_name) = enum_name {
+                items.push(entry_item(
+                    entry,
+                    ItemKind::Enum,
+                    format!("{}{}", scope, enum_name),
+                    vec![ty],
+                ));
+            }
+            // the constants are in the scope enclosing the enum
+            decls.iter().for_each(|decl| {
+                items.push(Item {
+                    kind: ItemKind::EnumConstant,
+                    name: format!("{}{}", scope, decl.name),
+                    container: None,
+                    types: vec![&decl.objc_type],
+                    availability: *availability,
+                    platform_availability: platforms(platform_availability),
+                    source: ItemSource::EnumConstant(decl),
+                });
+            });
+        }
+        Entry::MacroDefinition { name, .. } => {
+            items.push(entry_item(entry, ItemKind::Macro, name.clone(), vec![]))
+        }
+        Entry::Namespace {
+            name: Some(name),
+            entries,
+            ..
+        } => collect_entries(entries, &format!("{}{}::", scope, name), items),
+        Entry::ClassDecl {
+            name: Some(name),
+            fields,
+            constructors,
+            methods,
+            entries,
+            availability,
+            platform_availability,
+            ..
+        } => {
+            let name = format!("{}{}", scope, name);
+            items.push(entry_item(entry, ItemKind::Class, name.clone(), vec![]));
+            fields.iter().enumerate().for_each(|(index, field)| {
+                items.push(Item {
+                    kind: ItemKind::Field,
+                    name: format!("{}.{}", name, field.name),
+                    container: Some(name.clone()),
+                    types: vec![&field.ty],
+                    availability: *availability,
+                    platform_availability: platforms(platform_availability),
+                    source: ItemSource::CxxField { field, index },
+                })
+            });
+            constructors.iter().chain(methods).for_each(|method| {
+                let types = std::iter::once(&method.result_type)
+                    .chain(method.arguments.iter().map(|arg| &arg.objc_type))
+                    .collect();
+                // overloads differ in their argument types
+                let arguments: Vec<&str> = method
+                    .arguments
+                    .iter()
+                    .map(|arg| type_name(&arg.objc_type))
+                    .collect();
+                items.push(Item {
+                    kind: ItemKind::Method,
+                    name: format!("{}::{}({})", name, method.name, arguments.join(", ")),
+                    container: Some(name.clone()),
+                    types,
+                    availability: method.availability,
+                    platform_availability: platforms(&method.platform_availability),
+                    source: ItemSource::CxxMethod(method),
+                })
+            });
+            collect_entries(entries, &format!("{}::", name), items);
+        }
+        Entry::ObjCInterfaceDecl {
+            name,
+            properties,
+            instance_methods,
+            class_methods,
+            ..
+        } => {
+            items.push(entry_item(entry, ItemKind::Class, name.clone(), vec![]));
+            collect_objc_members(name, properties, instance_methods, class_methods, items);
+        }
+        Entry::ObjCCategoryDecl {
+            name,
+            class_name,
+            properties,
+            instance_methods,
+            class_methods,
+            ..
+        } => {
+            let category = format!("{} ({})", class_name, name.as_deref().unwrap_or(""));
+            items.push(entry_item(entry, ItemKind::Category, category, vec![]));
+            // members of categories extend the class
+            collect_objc_members(
+                class_name,
+                properties,
+                instance_methods,
+                class_methods,
+                items,
+            );
+        }
+        Entry::ObjCProtocolDecl {
+            name,
+            properties,
+            instance_methods,
+            class_methods,
+            ..
+        } => {
+            items.push(entry_item(entry, ItemKind::Protocol, name.clone(), vec![]));
+            collect_objc_members(
+                &format!("<{}>", name),
+                properties,
+                instance_methods,
+                class_methods,
+                items,
+            );
+        }
+        _ => {}
+    });
+}
+
+fn collect_objc_members<'a>(
+    container: &str,
+    properties: &'a [ObjCPropertyDecl],
+    instance_methods: &'a [ObjCMethodDecl],
+    class_methods: &'a [ObjCMethodDecl],
+    items: &mut Vec<Item<'a>>,
+) {
+    properties.iter().for_each(|property| {
+        items.push(Item {
+            kind: ItemKind::Property,
+            name: format!("{}.{}", container, property.name),
+            container: Some(container.to_string()),
+            types: vec![&property.objc_type],
+            availability: property.availability,
+            platform_availability: platforms(&property.platform_availability),
+            source: ItemSource::ObjCProperty(property),
+        })
+    });
+    [(false, instance_methods), (true, class_methods)]
+        .iter()
+        .for_each(|(is_class_method, methods)| {
+            methods.iter().for_each(|method| {
+                let types = std::iter::once(&method.result_type)
+                    .chain(method.arguments.iter().map(|arg| &arg.objc_type))
+                    .collect();
+                let prefix = if *is_class_method { "+" } else { "-" };
+                items.push(Item {
+                    kind: ItemKind::Method,
+                    name: format!("{} {}{}", container, prefix, method.name),
+                    container: Some(container.to_string()),
+                    types,
+                    availability: method.availability,
+                    platform_availability: platforms(&method.platform_availability),
+                    source: ItemSource::ObjCMethod {
+                        method,
+                        is_class_method: *is_class_method,
+                    },
+                })
+            })
+        });
+}
+
+fn entry_item<'a>(entry: &'a Entry, kind: ItemKind, name: String, types: Vec<&'a Typ>) -> Item<'a> {
+    let (availability, platform_availability) = entry_availability(entry);
+    Item {
+        kind,
+        name,
+        container: None,
+        types,
+        availability,
+        platform_availability,
+        source: ItemSource::Entry(entry),
+    }
+}
+
+fn entry_availability(entry: &Entry) -> (clang::Availability, &[PlatformAvailability]) {
+    match entry {
+        Entry::TypedefDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::EnumDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::VarDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::StructDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::UnionDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::FunctionDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::ClassDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::TypeAliasDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::ObjCInterfaceDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::ObjCCategoryDecl {
+            availability,
+            platform_availability,
+            ..
+        }
+        | Entry::ObjCProtocolDecl {
+            availability,
+            platform_availability,
+            ..
+        } => (*availability, platforms(platform_availability)),
+        _ => (clang::Availability::Available, &[]),
+    }
+}
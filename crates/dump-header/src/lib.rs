@@ -1,11 +1,24 @@
+pub mod apinotes;
+pub mod ast;
+pub mod cache;
 pub mod cli;
+pub mod codegen;
+pub mod deps;
+pub mod diagnostic;
+pub mod diff;
+pub mod driver;
 pub mod entity;
 pub mod error;
 #[cfg(feature = "dev")]
 pub mod fixture;
+pub mod format;
 pub mod framework;
 pub mod headerfiletree;
+pub mod interface;
+pub mod item;
 pub mod parser;
+pub mod query;
+pub mod schema;
 pub mod typ;
 pub mod utils;
 
@@ -15,6 +28,3 @@ pub mod utils;
 //   - sdk path
 // - framework's dependencies
 // - add debug print
-//
-// - parse c++ header?
-// - formats except json?
@@ -12,6 +12,7 @@ use crate::{
 pub struct Parser<'a> {
     index: Index<'a>,
     config: ParserConfig,
+    prefix_pch: Option<PathBuf>,
 }
 
 pub struct ParserConfig {
@@ -19,24 +20,80 @@ pub struct ParserConfig {
     pub lang: Lang,
     pub std: Option<Std>,
     pub target: Option<String>,
+    pub framework_paths: Vec<PathBuf>,
+}
+
+// TODO: Use https://crates.io/crates/apple-sdk
+const DEFAULT_MACOS_SYSROOT: &str =
+    "/Applications/Xcode.app/Contents/Developer/Platforms/MacOSX.platform/Developer/SDKs/MacOSX.sdk/";
+
+impl ParserConfig {
+    pub fn sysroot(&self) -> Option<PathBuf> {
+        if let Some(isysroot) = &self.isysroot {
+            Some(isysroot.clone())
+        } else if cfg!(target_os = "macos") {
+            Some(PathBuf::from(DEFAULT_MACOS_SYSROOT))
+        } else {
+            None
+        }
+    }
+
+    /// The `-F` paths followed by the system framework directory of the sysroot.
+    pub fn framework_search_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.framework_paths.clone();
+        if let Some(sysroot) = self.sysroot() {
+            let system_frameworks = sysroot.join("System/Library/Frameworks");
+            if system_frameworks.is_dir() {
+                paths.push(system_frameworks);
+            }
+        }
+        paths
+    }
 }
 
 impl<'a> Parser<'a> {
     pub fn from(clang: &'a Clang, config: ParserConfig) -> Self {
         let index = Index::new(clang, true, true);
-        Self { index, config }
+        Self {
+            index,
+            config,
+            prefix_pch: None,
+        }
+    }
+
+    pub fn config(&self) -> &ParserConfig {
+        &self.config
+    }
+
+    /// Uses the precompiled header `pch` of a prefix header, as `-include-pch`,
+    /// in the translation units parsed afterwards.
+    pub fn set_prefix_pch(&mut self, pch: Option<PathBuf>) {
+        self.prefix_pch = pch;
+    }
+
+    pub fn prefix_pch(&self) -> Option<&Path> {
+        self.prefix_pch.as_deref()
+    }
+
+    /// The arguments passed to clang to parse a header file.
+    pub fn arguments(&self) -> Result<Vec<String>, Error> {
+        self.arguments_for(self.lang_name(), self.prefix_pch.as_deref())
+    }
+
+    fn lang_name(&self) -> &'static str {
+        match self.config.lang {
+            Lang::C => "c",
+            Lang::ObjC => "objective-c",
+            Lang::Cpp => "c++",
+        }
     }
 
     // https://clang.llvm.org/docs/UsersManual.html
     // https://clang.llvm.org/docs/CommandGuide/clang.html
     // https://clang.llvm.org/docs/ClangCommandLineReference.html
-    pub fn parse(&'a self, filename: &Path) -> Result<TranslationUnit<'a>, Error> {
+    fn arguments_for(&self, lang: &str, pch: Option<&Path>) -> Result<Vec<String>, Error> {
         let mut args = vec![];
         args.push("-x");
-        let lang = match self.config.lang {
-            Lang::C => "c",
-            Lang::ObjC => "objective-c",
-        };
         args.push(lang);
         if let Some(target) = &self.config.target {
             args.push("-target");
@@ -60,9 +117,16 @@ impl<'a> Parser<'a> {
                     .ok_or(Error::InvalidArgument("Invalid isysroot".to_string()))?,
             );
         } else if cfg!(target_os = "macos") {
-            // TODO: Use https://crates.io/crates/apple-sdk
             args.push("-isysroot");
-            args.push("/Applications/Xcode.app/Contents/Developer/Platforms/MacOSX.platform/Developer/SDKs/MacOSX.sdk/");
+            args.push(DEFAULT_MACOS_SYSROOT);
+        }
+        for framework_path in &self.config.framework_paths {
+            args.push("-F");
+            args.push(
+                framework_path
+                    .to_str()
+                    .ok_or(Error::InvalidArgument("Invalid framework path".to_string()))?,
+            );
         }
         if let Lang::ObjC = self.config.lang {
             args.extend(vec![
@@ -79,6 +143,39 @@ impl<'a> Parser<'a> {
             "-D",
             "__SWIFT_ATTR_SUPPORTS_SENDABLE_DECLS=1",
         ]);
+        if let Some(pch) = pch {
+            args.push("-include-pch");
+            args.push(
+                pch.to_str()
+                    .ok_or(Error::InvalidArgument("Invalid pch path".to_string()))?,
+            );
+        }
+        Ok(args.into_iter().map(String::from).collect())
+    }
+
+    pub fn parse(&'a self, filename: &Path) -> Result<TranslationUnit<'a>, Error> {
+        self.parse_with_arguments(filename, &self.arguments()?)
+    }
+
+    /// Precompiles the prefix header `header` into `output`, to be shared by
+    /// the header files including it with `set_prefix_pch`, and returns the
+    /// translation unit of `header`.
+    pub fn precompile(
+        &'a self,
+        header: &Path,
+        output: &Path,
+    ) -> Result<TranslationUnit<'a>, Error> {
+        let lang = format!("{}-header", self.lang_name());
+        let tu = self.parse_with_arguments(header, &self.arguments_for(&lang, None)?)?;
+        tu.save(output).map_err(|e| Error::Save { source: e })?;
+        Ok(tu)
+    }
+
+    fn parse_with_arguments(
+        &'a self,
+        filename: &Path,
+        args: &[String],
+    ) -> Result<TranslationUnit<'a>, Error> {
         if std::env::var("DEBUG").is_ok() {
             eprintln!("Parser args: {:#?}", args);
         }
@@ -94,15 +191,26 @@ impl<'a> Parser<'a> {
             .visit_implicit_attributes(true)
             // .ignore_non_errors_from_included_files(true)
             .retain_excluded_conditional_blocks(true)
-            .arguments(&args)
+            .arguments(args)
             .parse();
         tu.map_err(|e| Error::Source { source: e })
     }
 
     pub fn parse_content(&'a self, content: &str) -> Result<(TranslationUnit<'a>, PathBuf), Error> {
+        self.parse_content_with_arguments(content, &[])
+    }
+
+    /// Parses `content` with `extra_args` appended to the arguments.
+    pub fn parse_content_with_arguments(
+        &'a self,
+        content: &str,
+        extra_args: &[&str],
+    ) -> Result<(TranslationUnit<'a>, PathBuf), Error> {
         let dir = TempDir::new().map_err(|e| Error::Io { source: e })?;
         let heder_file = dir.child("t.h");
         std::fs::write(&heder_file, content).map_err(|e| Error::Io { source: e })?;
-        Ok((self.parse(&heder_file)?, heder_file))
+        let mut args = self.arguments()?;
+        args.extend(extra_args.iter().map(|arg| arg.to_string()));
+        Ok((self.parse_with_arguments(&heder_file, &args)?, heder_file))
     }
 }
@@ -0,0 +1,304 @@
+use std::path::PathBuf;
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::AvailabilityFilter;
+use crate::entity::{Entry, ObjCMethodDecl, ParmDecl, PlatformAvailability};
+use crate::error::Error;
+use crate::headerfiletree::HeaderFile;
+use crate::item::{collect_items, type_name, Item, ItemKind, ItemSource};
+use crate::typ::Typ;
+
+pub use crate::format::load_header_files;
+
+/// Selects the declarations of dumps. Every filter that is set must match.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    /// Any kind if empty
+    pub kinds: Vec<ItemKind>,
+    /// Matched against the names as in `diff`, e.g. `NSString -length`
+    pub name: Option<Regex>,
+    /// Matched against the paths of the header files
+    pub header: Option<Regex>,
+    pub availability: Option<AvailabilityFilter>,
+    /// The platform `availability` applies to, any if `None`
+    pub platform: Option<String>,
+    /// A type named in the signature, e.g. `CFStringRef` or `NSString`
+    pub mentions: Option<String>,
+}
+
+/// A declaration selected by a `Query`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct QueryMatch {
+    pub kind: ItemKind,
+    /// e.g. `point.x` for a field or `NSString -length` for a method
+    pub name: String,
+    pub header: PathBuf,
+    /// One line in C or Objective-C syntax, e.g. `NSString -(NSUInteger)length`
+    pub signature: String,
+    /// The `Entry`, or the member of a class, of the declaration
+    pub entry: serde_json::Value,
+}
+
+impl QueryMatch {
+    /// The signature prefixed with the header path.
+    pub fn to_line(&self) -> String {
+        format!("{}: {}", self.header.display(), self.signature)
+    }
+}
+
+/// Compiles `pattern`, failing with `Error::InvalidArgument`.
+pub fn regex(pattern: &str) -> Result<Regex, Error> {
+    Regex::new(pattern).map_err(|e| Error::InvalidArgument(e.to_string()))
+}
+
+impl Query {
+    /// The matching declarations of `header_files`, in the order of the
+    /// header files then of the declarations.
+    pub fn run(&self, header_files: &[HeaderFile]) -> Vec<QueryMatch> {
+        let mut matches = vec![];
+        for header_file in header_files {
+            if let Some(header) = &self.header {
+                if !header.is_match(&header_file.path.to_string_lossy()) {
+                    continue;
+                }
+            }
+            collect_items(&header_file.entries)
+                .into_iter()
+                .filter(|item| self.is_match(item))
+                .for_each(|item| {
+                    matches.push(QueryMatch {
+                        kind: item.kind,
+                        signature: signature(&item),
+                        name: item.name,
+                        header: header_file.path.clone(),
+                        entry: item.source.to_value(),
+                    })
+                });
+        }
+        matches
+    }
+
+    fn is_match(&self, item: &Item) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&item.kind) {
+            return false;
+        }
+        if let Some(name) = &self.name {
+            if !name.is_match(&item.name) {
+                return false;
+            }
+        }
+        if let Some(availability) = self.availability {
+            if !self.is_availability_match(item, availability) {
+                return false;
+            }
+        }
+        if let Some(mentioned) = &self.mentions {
+            if !item.types.iter().any(|ty| mentions(ty, mentioned)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn is_availability_match(&self, item: &Item, availability: AvailabilityFilter) -> bool {
+        let platforms: Vec<&PlatformAvailability> = item
+            .platform_availability
+            .iter()
+            .filter(|p| {
+                self.platform
+                    .as_ref()
+                    .is_none_or(|name| p.platform == *name)
+            })
+            .collect();
+        let is_unavailable = matches!(
+            item.availability,
+            clang::Availability::Unavailable | clang::Availability::Inaccessible
+        ) || platforms
+            .iter()
+            .any(|p| p.unavailable || p.obsoleted.is_some());
+        let is_deprecated = item.availability == clang::Availability::Deprecated
+            || platforms.iter().any(|p| p.deprecated.is_some());
+        match availability {
+            AvailabilityFilter::Available => !is_unavailable,
+            AvailabilityFilter::Deprecated => is_deprecated,
+            AvailabilityFilter::Unavailable => is_unavailable,
+        }
+    }
+}
+
+// Whether `ty`, or a type it is built from, names `name`, e.g. `NSString` in
+// `NSArray<NSString *> *`. The fields of records are not searched.
+fn mentions(ty: &Typ, name: &str) -> bool {
+    let is_named = ty.name().is_some_and(|ty_name| {
+        ty_name
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .any(|word| word == name)
+    });
+    is_named
+        || match ty {
+            Typ::Pointer { pointee_type, .. } | Typ::Reference { pointee_type, .. } => {
+                mentions(pointee_type, name)
+            }
+            Typ::FunctionPrototype {
+                argument_types,
+                result_type,
+                ..
+            } => {
+                argument_types.iter().flatten().any(|ty| mentions(ty, name))
+                    || result_type.iter().any(|ty| mentions(ty, name))
+            }
+            Typ::CArray { element_type, .. } => mentions(element_type, name),
+            Typ::ObjC {
+                objc_type_arguments,
+                ..
+            } => objc_type_arguments.iter().any(|ty| mentions(ty, name)),
+            Typ::Typedef {
+                typedef_name,
+                underlying_type,
+                ..
+            } => typedef_name == name || mentions(underlying_type, name),
+            Typ::StructRecord { .. }
+            | Typ::UnionRecord { .. }
+            | Typ::RecordIdent { .. }
+            | Typ::TypeRef { .. }
+            | Typ::OtherType { .. } => false,
+        }
+}
+
+// One line in C or Objective-C syntax
+fn signature(item: &Item) -> String {
+    let name = &item.name;
+    let container = item.container.as_deref().unwrap_or_default();
+    match item.source {
+        ItemSource::Entry(entry) => match entry {
+            Entry::FunctionDecl {
+                arguments,
+                result_type,
+                is_variadic,
+                ..
+            } => {
+                let mut arguments_text = arguments_text(arguments);
+                if *is_variadic {
+                    arguments_text.push_str(if arguments.is_empty() { "..." } else { ", ..." });
+                }
+                format!("{} {}({})", type_name(result_type), name, arguments_text)
+            }
+            Entry::VarDecl { ty, .. } => format!("{} {}", type_name(ty), name),
+            Entry::TypedefDecl { ty, .. } | Entry::TypeAliasDecl { ty, .. } => {
+                format!("typedef {} {}", type_name(ty), name)
+            }
+            Entry::StructDecl { .. } => format!("struct {}", name),
+            Entry::UnionDecl { .. } => format!("union {}", name),
+            Entry::EnumDecl { ty, .. } => format!("enum {} : {}", name, type_name(ty)),
+            Entry::MacroDefinition {
+                parameters, tokens, ..
+            } => {
+                let parameters = parameters
+                    .as_ref()
+                    .map(|parameters| format!("({})", parameters.join(", ")))
+                    .unwrap_or_default();
+                format!("#define {}{} {}", name, parameters, tokens)
+            }
+            Entry::ClassDecl {
+                is_struct, bases, ..
+            } => {
+                let keyword = if *is_struct { "struct" } else { "class" };
+                let mut signature = format!("{} {}", keyword, name);
+                if !bases.is_empty() {
+                    let bases: Vec<&str> = bases.iter().map(|base| base.name.as_str()).collect();
+                    signature.push_str(&format!(" : {}", bases.join(", ")));
+                }
+                signature
+            }
+            Entry::ObjCInterfaceDecl {
+                superclass,
+                protocols,
+                ..
+            } => {
+                let mut signature = format!("@interface {}", name);
+                if !superclass.is_empty() {
+                    signature.push_str(&format!(" : {}", superclass));
+                }
+                if !protocols.is_empty() {
+                    signature.push_str(&format!(" <{}>", protocols.join(", ")));
+                }
+                signature
+            }
+            Entry::ObjCCategoryDecl { .. } => format!("@interface {}", name),
+            Entry::ObjCProtocolDecl { .. } => format!("@protocol {}", name),
+            _ => name.clone(),
+        },
+        ItemSource::Field { .. } => {
+            let ty = item.types.first().map_or("<anonymous>", |ty| type_name(ty));
+            format!("{} {}", ty, name)
+        }
+        ItemSource::EnumConstant(decl) => match &decl.value {
+            Some(value) => format!("{} = {}", name, value),
+            None => name.clone(),
+        },
+        ItemSource::CxxField { field, .. } => {
+            format!("{} {}::{}", type_name(&field.ty), container, field.name)
+        }
+        ItemSource::CxxMethod(method) => format!(
+            "{} {}::{}({})",
+            type_name(&method.result_type),
+            container,
+            method.name,
+            arguments_text(&method.arguments)
+        ),
+        ItemSource::ObjCProperty(property) => format!(
+            "{} @property {} {}",
+            container,
+            type_name(&property.objc_type),
+            property.name
+        ),
+        ItemSource::ObjCMethod {
+            method,
+            is_class_method,
+        } => format!(
+            "{} {}({}){}",
+            container,
+            if is_class_method { "+" } else { "-" },
+            type_name(&method.result_type),
+            selector_text(method)
+        ),
+    }
+}
+
+fn arguments_text(arguments: &[ParmDecl]) -> String {
+    let arguments: Vec<String> = arguments
+        .iter()
+        .map(|arg| match &arg.name {
+            Some(name) => format!("{} {}", type_name(&arg.objc_type), name),
+            None => type_name(&arg.objc_type).to_string(),
+        })
+        .collect();
+    arguments.join(", ")
+}
+
+// e.g. `initWithName:(NSString *)name count:(NSUInteger)count`
+pub(crate) fn selector_text(method: &ObjCMethodDecl) -> String {
+    if method.arguments.is_empty() {
+        return method.name.clone();
+    }
+    let mut parts: Vec<String> = method
+        .selector_pieces
+        .iter()
+        .zip(&method.arguments)
+        .map(|(piece, arg)| {
+            format!(
+                "{}:({}){}",
+                piece,
+                type_name(&arg.objc_type),
+                arg.name.as_deref().unwrap_or("")
+            )
+        })
+        .collect();
+    if method.is_variadic {
+        parts.push("...".to_string());
+    }
+    parts.join(" ")
+}
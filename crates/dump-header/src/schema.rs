@@ -0,0 +1,25 @@
+use schemars::{schema::RootSchema, schema_for};
+
+use crate::ast::AstNode;
+use crate::cli::SchemaKind;
+use crate::deps::IncludeGraph;
+use crate::format::FORMAT_VERSION;
+use crate::framework::Framework;
+use crate::headerfiletree::HeaderFile;
+use crate::interface::ResolvedInterface;
+use crate::query::QueryMatch;
+
+/// Generates the JSON Schema of a document from its serde types.
+pub fn schema(kind: SchemaKind) -> RootSchema {
+    let mut schema = match kind {
+        SchemaKind::HeaderFile => schema_for!(HeaderFile),
+        SchemaKind::Framework => schema_for!(Framework),
+        SchemaKind::Ast => schema_for!(Vec<AstNode>),
+        SchemaKind::Deps => schema_for!(IncludeGraph),
+        SchemaKind::Query => schema_for!(Vec<QueryMatch>),
+        SchemaKind::Interface => schema_for!(ResolvedInterface),
+    };
+    schema.schema.metadata().description =
+        Some(format!("dump-header format version {}", FORMAT_VERSION));
+    schema
+}
@@ -0,0 +1,113 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{attribute_arguments, attribute_tokens};
+
+/// The size and alignment of a struct or union, in bytes, as laid out by
+/// clang for the target of the parser.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct RecordLayout {
+    pub size: usize,
+    pub align: usize,
+    /// `__attribute__((packed))`
+    #[serde(default)]
+    pub packed: bool,
+    /// The alignment requested by `__attribute__((aligned(N)))` or
+    /// `_Alignas(N)`, the target's largest alignment without an argument
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aligned: Option<usize>,
+}
+
+/// The position of a field in its record, as laid out by clang for the
+/// target of the parser.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// From the start of the record, in bits
+    pub bit_offset: usize,
+    /// The width of a bit-field, `None` for other fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bitfield_width: Option<usize>,
+    #[serde(default)]
+    pub packed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aligned: Option<usize>,
+}
+
+impl RecordLayout {
+    /// `None` for incomplete and dependent types.
+    pub fn from(ty: clang::Type) -> Option<Self> {
+        let size = ty.get_sizeof().ok()?;
+        let align = ty.get_alignof().ok()?;
+        let (packed, aligned) = ty
+            .get_declaration()
+            .map(|entity| get_alignment_attributes(&entity, align))
+            .unwrap_or_default();
+        Some(Self {
+            size,
+            align,
+            packed,
+            aligned,
+        })
+    }
+}
+
+impl FieldLayout {
+    /// `None` for fields of incomplete and dependent records.
+    pub fn from(entity: &clang::Entity) -> Option<Self> {
+        let bit_offset = entity.get_offset_of_field().ok()?;
+        let bitfield_width = if entity.is_bit_field() {
+            entity.get_bit_field_width()
+        } else {
+            None
+        };
+        let align = entity
+            .get_type()
+            .and_then(|ty| ty.get_alignof().ok())
+            .unwrap_or(1);
+        let (packed, aligned) = get_alignment_attributes(entity, align);
+        Some(Self {
+            bit_offset,
+            bitfield_width,
+            packed,
+            aligned,
+        })
+    }
+}
+
+// `packed` and the alignment of `aligned` among the attributes of `entity`.
+// `aligned` without an argument or with an expression falls back to `align`,
+// the alignment computed by clang.
+fn get_alignment_attributes(entity: &clang::Entity, align: usize) -> (bool, Option<usize>) {
+    let mut packed = false;
+    let mut aligned = None;
+    for e in entity.get_children() {
+        match e.get_kind() {
+            clang::EntityKind::PackedAttr => packed = true,
+            clang::EntityKind::AlignedAttr => {
+                let tokens = attribute_tokens(&e);
+                let value = attribute_arguments(
+                    &tokens,
+                    &["aligned", "__aligned__", "_Alignas", "alignas"],
+                )
+                .and_then(|arguments| match arguments {
+                    [value] => parse_integer(value),
+                    _ => None,
+                });
+                aligned = Some(aligned.unwrap_or(0).max(value.unwrap_or(align)));
+            }
+            _ => {}
+        }
+    }
+    (packed, aligned)
+}
+
+fn parse_integer(literal: &str) -> Option<usize> {
+    let literal = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    match literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => literal.parse().ok(),
+    }
+}
@@ -1,14 +1,20 @@
 use std::cell::RefCell;
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::rc::Rc;
 
 use clang::TypeKind;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+mod layout;
+mod table;
 mod typekind;
+pub use layout::{FieldLayout, RecordLayout};
+pub use table::TypeTable;
 use typekind::TypeKindDef;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 pub enum Nullability {
     NonNull = 0,
     Nullable = 1,
@@ -25,14 +31,16 @@ impl Nullability {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct RecordField {
-    name: Option<String>,
-    is_anonymous: Option<bool>,
-    ty: Typ,
+    pub name: Option<String>,
+    pub is_anonymous: Option<bool>,
+    pub ty: Typ,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<FieldLayout>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(tag = "kind")]
 pub enum Typ {
     Pointer {
@@ -44,6 +52,15 @@ pub enum Typ {
         pointee_type: Box<Typ>,
         is_const: bool,
     },
+    Reference {
+        name: String,
+        #[serde(with = "TypeKindDef")]
+        clang_kind: clang::TypeKind,
+        nullability: Option<Nullability>,
+        objc_encoding: Option<String>,
+        pointee_type: Box<Typ>,
+        is_const: bool,
+    },
     FunctionPrototype {
         name: String,
         #[serde(with = "TypeKindDef")]
@@ -73,6 +90,8 @@ pub enum Typ {
         nullability: Option<Nullability>,
         objc_encoding: Option<String>,
         is_const: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        layout: Option<RecordLayout>,
     },
     UnionRecord {
         name: Option<String>,
@@ -83,11 +102,17 @@ pub enum Typ {
         nullability: Option<Nullability>,
         objc_encoding: Option<String>,
         is_const: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        layout: Option<RecordLayout>,
     },
     // prevents infinite recursive loop for recursive struct
     RecordIdent {
         ident: String,
     },
+    /// A named record stored once in the `TypeTable` of the dump
+    TypeRef {
+        id: String,
+    },
     ObjC {
         name: String,
         #[serde(with = "TypeKindDef")]
@@ -97,6 +122,21 @@ pub enum Typ {
         objc_type_arguments: Vec<Typ>,
         is_const: bool,
     },
+    /// A use of a typedef, e.g. `NSInteger`, with the typedef it names down
+    /// to the canonical type
+    Typedef {
+        name: String,
+        #[serde(with = "TypeKindDef")]
+        clang_kind: clang::TypeKind,
+        nullability: Option<Nullability>,
+        objc_encoding: Option<String>,
+        is_const: bool,
+        typedef_name: String,
+        /// The header declaring the typedef
+        header: Option<PathBuf>,
+        /// Another `Typedef` for a typedef of a typedef
+        underlying_type: Box<Typ>,
+    },
     OtherType {
         name: String,
         #[serde(with = "TypeKindDef")]
@@ -112,18 +152,108 @@ impl Typ {
         Self::from0(ty, Rc::new(RefCell::new(HashSet::new())))
     }
 
+    /// The display name of the type, `None` for an anonymous record.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Typ::Pointer { name, .. }
+            | Typ::Reference { name, .. }
+            | Typ::FunctionPrototype { name, .. }
+            | Typ::CArray { name, .. }
+            | Typ::ObjC { name, .. }
+            | Typ::Typedef { name, .. }
+            | Typ::OtherType { name, .. } => Some(name),
+            Typ::StructRecord { name, .. } | Typ::UnionRecord { name, .. } => name.as_deref(),
+            Typ::RecordIdent { ident } => Some(ident),
+            Typ::TypeRef { id } => Some(id),
+        }
+    }
+
+    /// The type at the end of a typedef chain, e.g. `long` for `NSInteger`.
+    pub fn canonical(&self) -> &Typ {
+        match self {
+            Typ::Typedef {
+                underlying_type, ..
+            } => underlying_type.canonical(),
+            _ => self,
+        }
+    }
+
+    /// The nullability of the type, `None` for a reference to a record.
+    pub fn nullability_mut(&mut self) -> Option<&mut Option<Nullability>> {
+        match self {
+            Typ::Pointer { nullability, .. }
+            | Typ::Reference { nullability, .. }
+            | Typ::FunctionPrototype { nullability, .. }
+            | Typ::CArray { nullability, .. }
+            | Typ::StructRecord { nullability, .. }
+            | Typ::UnionRecord { nullability, .. }
+            | Typ::ObjC { nullability, .. }
+            | Typ::Typedef { nullability, .. }
+            | Typ::OtherType { nullability, .. } => Some(nullability),
+            Typ::RecordIdent { .. } | Typ::TypeRef { .. } => None,
+        }
+    }
+
+    /// Calls `f` on the types directly nested in this type.
+    pub fn for_each_child_mut(&mut self, f: &mut dyn FnMut(&mut Typ)) {
+        match self {
+            Typ::Pointer { pointee_type, .. } | Typ::Reference { pointee_type, .. } => {
+                f(pointee_type)
+            }
+            Typ::FunctionPrototype {
+                argument_types,
+                result_type,
+                ..
+            } => {
+                argument_types.iter_mut().flatten().for_each(&mut *f);
+                if let Some(result_type) = result_type {
+                    f(result_type);
+                }
+            }
+            Typ::CArray { element_type, .. } => f(element_type),
+            Typ::StructRecord { fields, .. } | Typ::UnionRecord { fields, .. } => {
+                fields.iter_mut().for_each(|field| f(&mut field.ty))
+            }
+            Typ::ObjC {
+                objc_type_arguments,
+                ..
+            } => objc_type_arguments.iter_mut().for_each(f),
+            Typ::Typedef {
+                underlying_type, ..
+            } => f(underlying_type),
+            Typ::RecordIdent { .. } | Typ::TypeRef { .. } | Typ::OtherType { .. } => {}
+        }
+    }
+
     fn from0(ty: clang::Type, memo: Rc<RefCell<HashSet<String>>>) -> Self {
         let name = ty.get_display_name();
         let nullability = ty.get_nullability().map(Nullability::from);
         match ty.get_kind() {
-            TypeKind::Attributed | TypeKind::Elaborated => {
-                let canonical_ty = ty.get_canonical_type();
-                Self::from_impl(canonical_ty, name, nullability, memo)
-            }
+            TypeKind::Attributed | TypeKind::Elaborated => match Self::sugared_typedef(ty) {
+                Some(typedef_ty) => Self::from_impl(typedef_ty, name, nullability, memo),
+                None => {
+                    let canonical_ty = ty.get_canonical_type();
+                    Self::from_impl(canonical_ty, name, nullability, memo)
+                }
+            },
             _ => Self::from_impl(ty, name, nullability, memo),
         }
     }
 
+    // The typedef under `_Nonnull NSString *`-like attributes and `struct`
+    // or namespace qualifiers, if any.
+    fn sugared_typedef(ty: clang::Type) -> Option<clang::Type> {
+        let mut ty = ty;
+        loop {
+            ty = match ty.get_kind() {
+                TypeKind::Attributed => ty.get_modified_type()?,
+                TypeKind::Elaborated => ty.get_elaborated_type()?,
+                TypeKind::Typedef => return Some(ty),
+                _ => return None,
+            };
+        }
+    }
+
     fn from_impl(
         ty: clang::Type,
         name: String,
@@ -133,13 +263,42 @@ impl Typ {
         let clang_kind = ty.get_kind();
         let objc_encoding = if clang_kind == clang::TypeKind::ObjCObject {
             Some("@".to_string())
+        } else if clang_kind == clang::TypeKind::Dependent {
+            // C++ template parameters have no encoding
+            None
         } else {
             // [WORKAROUND] can cause segfault!!
             ty.get_objc_encoding()
         };
         let is_const = ty.is_const_qualified();
+        // e.g. a pointer without a pointee type, as a name only
+        Self::try_from_impl(
+            ty,
+            name.clone(),
+            nullability.clone(),
+            objc_encoding.clone(),
+            is_const,
+            memo,
+        )
+        .unwrap_or(Self::OtherType {
+            name,
+            clang_kind,
+            nullability,
+            objc_encoding,
+            is_const,
+        })
+    }
 
-        match clang_kind {
+    fn try_from_impl(
+        ty: clang::Type,
+        name: String,
+        nullability: Option<Nullability>,
+        objc_encoding: Option<String>,
+        is_const: bool,
+        memo: Rc<RefCell<HashSet<String>>>,
+    ) -> Option<Self> {
+        let clang_kind = ty.get_kind();
+        let typ = match clang_kind {
             TypeKind::Pointer
             | TypeKind::ObjCObjectPointer
             | TypeKind::BlockPointer
@@ -148,10 +307,15 @@ impl Typ {
                 clang_kind,
                 nullability,
                 objc_encoding,
-                pointee_type: ty
-                    .get_pointee_type()
-                    .map(|t| Box::new(Typ::from0(t, memo)))
-                    .unwrap(),
+                pointee_type: Box::new(Typ::from0(ty.get_pointee_type()?, memo)),
+                is_const,
+            },
+            TypeKind::LValueReference | TypeKind::RValueReference => Self::Reference {
+                name,
+                clang_kind,
+                nullability,
+                objc_encoding,
+                pointee_type: Box::new(Typ::from0(ty.get_pointee_type()?, memo)),
                 is_const,
             },
             TypeKind::FunctionNoPrototype | TypeKind::FunctionPrototype => {
@@ -175,12 +339,19 @@ impl Typ {
                 clang_kind,
                 nullability,
                 objc_encoding,
-                element_type: Box::new(Typ::from0(ty.get_element_type().unwrap(), memo)),
+                element_type: Box::new(Typ::from0(ty.get_element_type()?, memo)),
                 size: ty.get_size(),
                 is_const,
             },
             TypeKind::Record => {
-                let entity = ty.get_declaration().unwrap();
+                let entity = ty.get_declaration()?;
+                let is_union = match entity.get_kind() {
+                    clang::EntityKind::StructDecl
+                    | clang::EntityKind::ClassDecl
+                    | clang::EntityKind::ClassTemplate => false,
+                    clang::EntityKind::UnionDecl => true,
+                    _ => return None,
+                };
                 let is_anonymous = entity.is_anonymous_record_decl();
                 let ident = if !is_anonymous {
                     entity.get_name()
@@ -191,26 +362,32 @@ impl Typ {
                 // prevents infinite recursive loop for recursive struct
                 if let Some(ref ident) = ident {
                     if memo.borrow().contains(ident) {
-                        return Self::RecordIdent {
+                        return Some(Self::RecordIdent {
                             ident: ident.clone(),
-                        };
+                        });
                     }
                     memo.borrow_mut().insert(ident.clone());
                 }
+                // C++ dependent types have no fields
                 let fields: Vec<RecordField> = ty
                     .get_fields()
-                    .unwrap()
+                    .unwrap_or_default()
                     .iter()
-                    .map(|e| RecordField {
-                        name: e.get_name(),
-                        is_anonymous: e.get_type().and_then(|t| {
-                            t.get_declaration().map(|e| e.is_anonymous_record_decl())
-                        }),
-                        ty: Typ::from0(e.get_type().unwrap(), memo.clone()),
+                    .filter_map(|e| {
+                        let field_ty = e.get_type()?;
+                        Some(RecordField {
+                            name: e.get_name(),
+                            is_anonymous: field_ty
+                                .get_declaration()
+                                .map(|e| e.is_anonymous_record_decl()),
+                            ty: Typ::from0(field_ty, memo.clone()),
+                            layout: FieldLayout::from(e),
+                        })
                     })
                     .collect();
-                match entity.get_kind() {
-                    clang::EntityKind::StructDecl => Self::StructRecord {
+                let layout = RecordLayout::from(ty);
+                if is_union {
+                    Self::UnionRecord {
                         name,
                         ident,
                         fields,
@@ -218,8 +395,10 @@ impl Typ {
                         nullability,
                         objc_encoding,
                         is_const,
-                    },
-                    clang::EntityKind::UnionDecl => Self::UnionRecord {
+                        layout,
+                    }
+                } else {
+                    Self::StructRecord {
                         name,
                         ident,
                         fields,
@@ -227,8 +406,8 @@ impl Typ {
                         nullability,
                         objc_encoding,
                         is_const,
-                    },
-                    _ => panic!("unexpected entity kind: {:?}", entity.get_kind()),
+                        layout,
+                    }
                 }
             }
             TypeKind::ObjCClass
@@ -248,6 +427,24 @@ impl Typ {
                     .collect(),
                 is_const,
             },
+            TypeKind::Typedef => {
+                let declaration = ty.get_declaration()?;
+                let header = declaration
+                    .get_location()
+                    .and_then(|location| location.get_file_location().file)
+                    .map(|file| file.get_path());
+                let underlying_type = Typ::from0(declaration.get_typedef_underlying_type()?, memo);
+                Self::Typedef {
+                    name,
+                    clang_kind,
+                    nullability,
+                    objc_encoding,
+                    is_const,
+                    typedef_name: declaration.get_name().unwrap_or_default(),
+                    header,
+                    underlying_type: Box::new(underlying_type),
+                }
+            }
             _ => Self::OtherType {
                 name,
                 clang_kind,
@@ -255,6 +452,7 @@ impl Typ {
                 objc_encoding,
                 is_const,
             },
-        }
+        };
+        Some(typ)
     }
 }
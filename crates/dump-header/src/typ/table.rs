@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::Typ;
+use crate::entity::Entry;
+
+/// Named records stored once per dump, keyed by their canonical name, e.g.
+/// `struct point`. Elsewhere in the dump they are `Typ::TypeRef`s.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default)]
+#[serde(transparent)]
+pub struct TypeTable {
+    types: BTreeMap<String, Typ>,
+}
+
+impl TypeTable {
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Typ> {
+        self.types.get(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Typ)> {
+        self.types.iter()
+    }
+
+    /// Moves the named records of `ty` into the table, replacing them with
+    /// references.
+    pub fn intern(&mut self, ty: &mut Typ) {
+        ty.for_each_child_mut(&mut |child| self.intern(child));
+        let Some(id) = record_id(ty) else {
+            return;
+        };
+        let record = std::mem::replace(ty, Typ::TypeRef { id: id.clone() });
+        // a record used before its definition has no fields
+        let is_defined = |ty: &Typ| match ty {
+            Typ::StructRecord { fields, .. } | Typ::UnionRecord { fields, .. } => {
+                !fields.is_empty()
+            }
+            _ => false,
+        };
+        match self.types.get(&id) {
+            Some(existing) if is_defined(existing) || !is_defined(&record) => {}
+            _ => {
+                self.types.insert(id, record);
+            }
+        }
+    }
+
+    pub fn intern_entries(&mut self, entries: &mut [Entry]) {
+        entries
+            .iter_mut()
+            .for_each(|entry| entry.for_each_type_mut(&mut |ty| self.intern(ty)));
+    }
+
+    /// Replaces the references of `ty` with the records of the table. A
+    /// record referring to itself refers to its `Typ::RecordIdent`, as
+    /// without a type table.
+    pub fn inline(&self, ty: &mut Typ) {
+        self.inline0(ty, &mut vec![]);
+    }
+
+    pub fn inline_entries(&self, entries: &mut [Entry]) {
+        entries
+            .iter_mut()
+            .for_each(|entry| entry.for_each_type_mut(&mut |ty| self.inline(ty)));
+    }
+
+    /// Adds the records of `other` missing in this table.
+    pub fn merge(&mut self, other: TypeTable) {
+        other.types.into_iter().for_each(|(id, ty)| {
+            self.types.entry(id).or_insert(ty);
+        });
+    }
+
+    fn inline0(&self, ty: &mut Typ, expanding: &mut Vec<String>) {
+        let Typ::TypeRef { id } = ty else {
+            ty.for_each_child_mut(&mut |child| self.inline0(child, expanding));
+            return;
+        };
+        let id = id.clone();
+        let Some(record) = self.types.get(&id) else {
+            return;
+        };
+        if expanding.contains(&id) {
+            if let Typ::StructRecord {
+                ident: Some(ident), ..
+            }
+            | Typ::UnionRecord {
+                ident: Some(ident), ..
+            } = record
+            {
+                *ty = Typ::RecordIdent {
+                    ident: ident.clone(),
+                };
+            }
+            return;
+        }
+        *ty = record.clone();
+        expanding.push(id);
+        ty.for_each_child_mut(&mut |child| self.inline0(child, expanding));
+        expanding.pop();
+    }
+}
+
+// Anonymous records stay where they are used.
+fn record_id(ty: &Typ) -> Option<String> {
+    match ty {
+        Typ::StructRecord {
+            name: Some(name),
+            ident: Some(_),
+            ..
+        }
+        | Typ::UnionRecord {
+            name: Some(name),
+            ident: Some(_),
+            ..
+        } => Some(name.clone()),
+        _ => None,
+    }
+}
@@ -1,6 +1,7 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(remote = "clang::TypeKind")]
 pub enum TypeKindDef {
     /// A type whose specific kind is not exposed via this interface.
@@ -0,0 +1,35 @@
+// Each test crate uses only some of the helpers.
+#![allow(dead_code)]
+
+use clang::Clang;
+use dump_header::{
+    cli::Lang,
+    parser::{Parser, ParserConfig},
+};
+
+pub type TestResult<T = ()> = Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+/// The configuration of `parser_with`, to be extended e.g. with framework
+/// paths.
+pub fn config(lang: Lang, target: Option<&str>) -> ParserConfig {
+    ParserConfig {
+        isysroot: None,
+        lang,
+        std: None,
+        target: target.map(String::from),
+        framework_paths: vec![],
+    }
+}
+
+/// A parser of `lang` for `target`, the host if `None`.
+pub fn parser_with(lang: Lang, target: Option<&str>) -> TestResult<Parser<'static>> {
+    parser_from(config(lang, target))
+}
+
+/// A parser with `config`. Its `Clang` is leaked, as libclang allows one
+/// instance at a time and each test crate creates a single parser.
+pub fn parser_from(config: ParserConfig) -> TestResult<Parser<'static>> {
+    clang_sys::load()?;
+    let clang = Box::leak(Box::new(Clang::new()?));
+    Ok(Parser::from(clang, config))
+}
@@ -0,0 +1,228 @@
+mod common;
+
+use std::path::Path;
+
+use common::{config, parser_from};
+use dump_header::{
+    cli::Lang,
+    entity::{ConvertOptions, Entry},
+    framework::FrameworkUnit,
+    headerfiletree::HeaderFile,
+    parser::ParserConfig,
+    typ::Nullability,
+};
+use temp_dir::TempDir;
+
+const API_NOTES: &str = r#"
+Name: Foo
+Classes:
+- Name: FooObject
+  SwiftName: Object
+  Methods:
+  - Selector: "initWithName:"
+    MethodKind: Instance
+    SwiftName: "init(name:)"
+    DesignatedInit: true
+    Parameters:
+    - Position: 0
+      Nullability: N
+  Properties:
+  - Name: name
+    Nullability: O
+Functions:
+- Name: foo_create
+  NullabilityOfRet: O
+  Availability: none
+  AvailabilityMsg: "use FooObject"
+SwiftVersions:
+- Version: 4
+  Functions:
+  - Name: foo_create
+    SwiftName: createFoo()
+"#;
+
+fn write_file(path: &Path, content: &str) -> std::io::Result<()> {
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(path, content)
+}
+
+fn pointer(name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "Pointer",
+        "name": name,
+        "clang_kind": "ObjCObjectPointer",
+        "nullability": null,
+        "objc_encoding": "@",
+        "pointee_type": {
+            "kind": "OtherType",
+            "name": "NSString",
+            "clang_kind": "ObjCInterface",
+            "nullability": null,
+            "objc_encoding": "@",
+            "is_const": false
+        },
+        "is_const": false
+    })
+}
+
+fn method(name: &str, argument: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "arguments": [{ "name": argument, "objc_type": pointer("NSString *") }],
+        "result_type": pointer("instancetype"),
+        "optional": false,
+        "platform_availability": null,
+        "availability": "Available"
+    })
+}
+
+// With `NS_SWIFT_NAME(swift_name)` in the header.
+fn header_swift_name(mut method: serde_json::Value, swift_name: &str) -> serde_json::Value {
+    method["swift_name"] = swift_name.into();
+    method["swift_attributes"] =
+        serde_json::json!([{ "name": "swift_name", "arguments": [swift_name] }]);
+    method
+}
+
+#[test]
+fn test_apply_api_notes() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let dir = TempDir::new()?;
+    let api_notes_path = dir.child("Foo.framework/Headers/Foo.apinotes");
+    write_file(&api_notes_path, API_NOTES)?;
+    let mut header_file: HeaderFile = serde_json::from_value(serde_json::json!({
+        "path": dir.child("Foo.framework/Headers/Foo.h"),
+        "entries": [
+            {
+                "kind": "ObjCInterfaceDecl",
+                "name": "FooObject",
+                "template_args": [],
+                "superclass": "NSObject",
+                "protocols": [],
+                "properties": [{
+                    "name": "name",
+                    "objc_type": pointer("NSString *"),
+                    "optional": false,
+                    "attributes": null,
+                    "platform_availability": null,
+                    "availability": "Available"
+                }],
+                "instance_methods": [
+                    header_swift_name(method("initWithName:", "name"), "init(header:)"),
+                    method("initWithValue:", "value"),
+                ],
+                "class_methods": [method("initWithName:", "name")],
+                "platform_availability": null,
+                "availability": "Available"
+            },
+            {
+                "kind": "FunctionDecl",
+                "name": "foo_create",
+                "ty": pointer("FooObject *(void)"),
+                "arguments": [],
+                "result_type": pointer("FooObject *"),
+                "platform_availability": null,
+                "availability": "Available"
+            }
+        ]
+    }))?;
+    header_file.apply_api_notes()?;
+
+    let Entry::ObjCInterfaceDecl {
+        api_notes,
+        instance_methods,
+        class_methods,
+        properties,
+        ..
+    } = &header_file.entries[0]
+    else {
+        panic!("not an interface");
+    };
+    assert_eq!(api_notes.len(), 1);
+    assert_eq!(api_notes[0].key, "SwiftName");
+    assert_eq!(api_notes[0].value, "Object");
+    assert_eq!(api_notes[0].file, api_notes_path);
+
+    let init = &instance_methods[0];
+    assert_eq!(init.swift_name.as_deref(), Some("init(name:)"));
+    // the API notes override the header
+    assert_eq!(init.swift_attributes.len(), 1);
+    assert_eq!(init.swift_attributes[0].arguments, ["init(name:)"]);
+    assert!(init.is_designated_initializer);
+    let keys: Vec<&str> = init
+        .api_notes
+        .iter()
+        .map(|note| note.key.as_str())
+        .collect();
+    assert_eq!(keys, ["SwiftName", "DesignatedInit"]);
+    assert_eq!(init.arguments[0].nullability, Some(Nullability::NonNull));
+    assert_eq!(init.arguments[0].api_notes[0].value, "NonNull");
+    // other selectors and method kinds are left as they are
+    assert!(instance_methods[1].api_notes.is_empty());
+    assert!(class_methods[0].api_notes.is_empty());
+    assert!(class_methods[0].swift_name.is_none());
+
+    let json = serde_json::to_value(&properties[0])?;
+    assert_eq!(json["objc_type"]["nullability"], "Nullable");
+
+    let Entry::FunctionDecl {
+        availability,
+        result_type,
+        api_notes,
+        ..
+    } = &header_file.entries[1]
+    else {
+        panic!("not a function");
+    };
+    assert_eq!(*availability, clang::Availability::Unavailable);
+    assert_eq!(
+        serde_json::to_value(result_type)?["nullability"],
+        "Nullable"
+    );
+    // not the Swift 4 name
+    let notes: Vec<(&str, &str)> = api_notes
+        .iter()
+        .map(|note| (note.key.as_str(), note.value.as_str()))
+        .collect();
+    assert_eq!(
+        notes,
+        [
+            ("Availability", "none"),
+            ("AvailabilityMsg", "use FooObject"),
+            ("NullabilityOfRet", "Nullable"),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_framework_api_notes() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let dir = TempDir::new()?;
+    write_file(
+        &dir.child("Foo.framework/Headers/Foo.h"),
+        "typedef struct foo foo;\nfoo *foo_create(void);\n",
+    )?;
+    write_file(&dir.child("Foo.framework/Headers/Foo.apinotes"), API_NOTES)?;
+    let parser = parser_from(ParserConfig {
+        framework_paths: vec![dir.path().to_path_buf()],
+        ..config(Lang::C, None)
+    })?;
+    let mut framework =
+        FrameworkUnit::with_parser("Foo", &parser)?.dump(&ConvertOptions::default());
+    framework.apply_api_notes()?;
+    let header_file = framework.iter().next().unwrap();
+    let api_notes = header_file
+        .entries
+        .iter()
+        .find_map(|entry| match entry {
+            Entry::FunctionDecl {
+                name, api_notes, ..
+            } if name == "foo_create" => Some(api_notes),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(api_notes.len(), 3);
+    assert!(api_notes
+        .iter()
+        .all(|note| note.file == dir.child("Foo.framework/Headers/Foo.apinotes")));
+    Ok(())
+}
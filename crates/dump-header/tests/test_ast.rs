@@ -0,0 +1,73 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    ast::{check_kind, select_nodes, AstFilter},
+    cli::Lang,
+    query::regex,
+};
+
+const SOURCE: &str = r#"
+#include <stddef.h>
+
+struct point {
+    int x;
+    int y;
+};
+
+int area(struct point a, struct point b) __attribute__((deprecated("use volume")));
+"#;
+
+#[test]
+fn test_ast_filters() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, None)?;
+    let (tu, _) = parser.parse_content(SOURCE)?;
+
+    let nodes = select_nodes(&tu, &AstFilter::default());
+    let kinds: Vec<&str> = nodes.iter().map(|node| node.kind.as_str()).collect();
+    assert_eq!(kinds, ["InclusionDirective", "StructDecl", "FunctionDecl"]);
+    let area = &nodes[2];
+    assert_eq!(area.usr.as_deref(), Some("c:@F@area"));
+    assert_eq!(area.location.as_ref().map(|l| l.line), Some(9));
+    assert_eq!(area.attributes, ["deprecated(\"use volume\")"]);
+
+    let fields = select_nodes(
+        &tu,
+        &AstFilter {
+            kinds: vec!["FieldDecl".to_string()],
+            ..AstFilter::default()
+        },
+    );
+    let names: Vec<Option<&str>> = fields.iter().map(|node| node.name.as_deref()).collect();
+    assert_eq!(names, [Some("x"), Some("y")]);
+
+    let shallow = select_nodes(
+        &tu,
+        &AstFilter {
+            name: Some(regex("^area$")?),
+            depth: Some(1),
+            ..AstFilter::default()
+        },
+    );
+    assert_eq!(shallow.len(), 1);
+    assert!(shallow[0].children.len() >= 2);
+    assert!(shallow[0].children.iter().all(|c| c.children.is_empty()));
+
+    let everywhere = select_nodes(
+        &tu,
+        &AstFilter {
+            name: Some(regex("^size_t$")?),
+            include_non_main_file: true,
+            ..AstFilter::default()
+        },
+    );
+    assert!(!everywhere.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_check_kind() {
+    assert!(check_kind("ObjCInterfaceDecl").is_ok());
+    assert!(check_kind("OverloadCandidate").is_ok());
+    assert!(check_kind("ObjcInterfaceDecl").is_err());
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cache::{hash_contents, CacheStats, ParseCache},
+    cli::Lang,
+    entity::{ConvertOptions, Entry},
+};
+use temp_dir::TempDir;
+
+#[test]
+fn test_hash_contents() -> std::io::Result<()> {
+    let dir = TempDir::new()?;
+    let header = dir.child("foo.h");
+    let include = dir.child("bar.h");
+    std::fs::write(&header, "#include \"bar.h\"\n")?;
+    std::fs::write(&include, "int bar;\n")?;
+    let dependencies = vec![include.clone(), header.clone()];
+
+    let key = hash_contents("key", &dependencies);
+    assert_eq!(key, hash_contents("key", &dependencies));
+    assert_ne!(key, hash_contents("other", &dependencies));
+    assert_ne!(key, hash_contents("key", &dependencies[1..]));
+
+    std::fs::write(&include, "int bar = 0;\n")?;
+    let edited = hash_contents("key", &dependencies);
+    assert_ne!(key, edited);
+
+    std::fs::remove_file(&include)?;
+    assert_ne!(edited, hash_contents("key", &dependencies));
+    Ok(())
+}
+
+#[test]
+fn test_cache_stats() {
+    let stats = CacheStats { hits: 2, misses: 1 };
+    assert_eq!(stats.to_string(), "2 hit(s), 1 miss(es)");
+}
+
+#[test]
+fn test_parse_cache() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, None)?;
+    let options = ConvertOptions::default();
+    let dir = TempDir::new()?;
+    let header = dir.child("foo.h");
+    let include = dir.child("bar.h");
+    std::fs::write(&header, "#include \"bar.h\"\nint foo(bar_t bar);\n")?;
+    std::fs::write(&include, "typedef int bar_t;\n")?;
+
+    let mut cache = ParseCache::new(&dir.child("cache"), false)?;
+    let parsed = cache.header_file(&parser, &header, &options)?;
+    let cached = cache.header_file(&parser, &header, &options)?;
+    assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+    assert_eq!(
+        serde_json::to_value(&parsed.header_file)?,
+        serde_json::to_value(&cached.header_file)?
+    );
+    assert!(matches!(
+        cached.header_file.entries.as_slice(),
+        [Entry::FunctionDecl { .. }]
+    ));
+
+    // editing an include invalidates the dump of the header file
+    std::fs::write(&include, "typedef long bar_t;\n")?;
+    cache.header_file(&parser, &header, &options)?;
+    cache.header_file(&parser, &header, &options)?;
+    assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 2 });
+
+    let mut cache = ParseCache::new(&dir.child("cache"), true)?;
+    cache.header_file(&parser, &header, &options)?;
+    assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 1 });
+    Ok(())
+}
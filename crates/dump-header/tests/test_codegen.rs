@@ -0,0 +1,525 @@
+use dump_header::{codegen::generate_rust, headerfiletree::HeaderFile};
+
+fn int(name: &str, kind: &str, is_const: bool) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "OtherType",
+        "name": name,
+        "clang_kind": kind,
+        "nullability": null,
+        "objc_encoding": null,
+        "is_const": is_const
+    })
+}
+
+fn header_file() -> HeaderFile {
+    let point = serde_json::json!({
+        "kind": "StructRecord",
+        "name": "struct point",
+        "ident": "point",
+        "fields": [
+            { "name": "x", "is_anonymous": null, "ty": int("int", "Int", false) },
+            {
+                "name": null,
+                "is_anonymous": true,
+                "ty": {
+                    "kind": "UnionRecord",
+                    "name": null,
+                    "ident": null,
+                    "fields": [
+                        { "name": "y", "is_anonymous": null, "ty": int("int", "Int", false) },
+                        { "name": "type", "is_anonymous": null, "ty": int("float", "Float", false) }
+                    ],
+                    "clang_kind": "Record",
+                    "is_const": false
+                }
+            }
+        ],
+        "clang_kind": "Record",
+        "is_const": false
+    });
+    let mut const_point = point.clone();
+    const_point["name"] = "const struct point".into();
+    const_point["is_const"] = true.into();
+    let point_ptr = serde_json::json!({
+        "kind": "Pointer",
+        "name": "const struct point *",
+        "clang_kind": "Pointer",
+        "pointee_type": const_point,
+        "is_const": false
+    });
+    let entries = serde_json::json!([
+        {
+            "kind": "StructDecl",
+            "name": "point",
+            "fields": [],
+            "ty": point,
+            "platform_availability": [],
+            "availability": "Available"
+        },
+        {
+            "kind": "TypedefDecl",
+            "name": "point_t",
+            "ty": point,
+            "platform_availability": [],
+            "availability": "Available"
+        },
+        {
+            "kind": "EnumDecl",
+            "name": "color",
+            "decls": [
+                { "name": "RED", "value": "0", "objc_type": int("int", "Int", false) },
+                { "name": "NONE", "value": "-1", "objc_type": int("int", "Int", false) }
+            ],
+            "ty": int("int", "Int", false),
+            "is_scoped": false,
+            "platform_availability": [],
+            "availability": "Available"
+        },
+        {
+            "kind": "VarDecl",
+            "name": "kMax",
+            "ty": int("const int", "Int", true),
+            "init_expr": { "kind": "Value", "Int": 42 },
+            "platform_availability": [],
+            "availability": "Available"
+        },
+        {
+            "kind": "VarDecl",
+            "name": "counter",
+            "ty": int("int", "Int", false),
+            "init_expr": null,
+            "platform_availability": [],
+            "availability": "Available"
+        },
+        {
+            "kind": "FunctionDecl",
+            "name": "move",
+            "ty": int("void (const struct point *, int)", "FunctionPrototype", false),
+            "arguments": [
+                { "name": "p", "objc_type": point_ptr },
+                { "name": null, "objc_type": int("int", "Int", false) }
+            ],
+            "result_type": int("void", "Void", false),
+            "platform_availability": [],
+            "availability": "Available",
+            "comment": {
+                "raw": "/// Moves a point.\n/// @param p the point",
+                "brief": "Moves a point.",
+                "paragraphs": ["Moves a point."],
+                "params": [{ "name": "p", "direction": null, "text": "the point" }],
+                "template_params": [],
+                "returns": null,
+                "notes": [],
+                "deprecated": null,
+                "commands": []
+            },
+            "location": {
+                "file": "t.h",
+                "line": 3,
+                "column": 6,
+                "extent": null
+            }
+        },
+        {
+            "kind": "MacroDefinition",
+            "name": "kFooMax",
+            "parameters": null,
+            "tokens": "(40 + 2)",
+            "value": { "Int": 42 }
+        },
+        {
+            "kind": "MacroDefinition",
+            "name": "kFooName",
+            "parameters": null,
+            "tokens": "\"foo\"",
+            "value": { "String": "foo" }
+        },
+        {
+            "kind": "MacroDefinition",
+            "name": "FOO_MIN",
+            "parameters": ["a", "b"],
+            "tokens": "((a) < (b) ? (a) : (b))",
+            "value": null
+        }
+    ]);
+    HeaderFile::new(
+        "t.h".into(),
+        serde_json::from_value(entries).expect("valid entries"),
+    )
+}
+
+#[test]
+fn test_generate_rust() {
+    let source = generate_rust(&header_file());
+    for expected in [
+        "#[repr(C)]\n#[derive(Copy, Clone)]\npub struct point {\n    pub x: c_int,\n    pub __anon1: point__anon1,\n}",
+        "pub union point__anon1 {\n    pub y: c_int,\n    pub r#type: c_float,\n}",
+        "pub type point_t = point;",
+        "pub type color = c_int;",
+        "pub const RED: color = 0;",
+        "pub const NONE: color = -1i64 as color;",
+        "pub const kMax: c_int = 42;",
+        "pub const kFooMax: i32 = 42;",
+        "pub const kFooName: &[u8] = b\"foo\\0\";",
+        "extern \"C\" {\n    pub static mut counter: c_int;\n    /// Moves a point.\n    ///\n    /// - `p`: the point\n    // t.h:3:6\n    pub fn r#move(p: *const point, arg1: c_int);\n}",
+    ] {
+        assert!(source.contains(expected), "missing {:?} in\n{}", expected, source);
+    }
+    assert_eq!(source.matches("pub struct point ").count(), 1);
+    assert!(!source.contains("FOO_MIN"));
+}
+
+#[test]
+fn test_generate_rust_recursive_record() {
+    let field = |field: &str, name: &str| {
+        serde_json::json!({
+            "name": field,
+            "is_anonymous": null,
+            "ty": {
+                "kind": "Pointer",
+                "name": name,
+                "clang_kind": "Pointer",
+                "pointee_type": { "kind": "RecordIdent", "ident": "node" },
+                "is_const": false
+            }
+        })
+    };
+    let entries = serde_json::json!([{
+        "kind": "StructDecl",
+        "name": "node",
+        "fields": [],
+        "ty": {
+            "kind": "StructRecord",
+            "name": "struct node",
+            "ident": "node",
+            "fields": [
+                field("next", "const struct node *"),
+                field("prev", "struct node *const")
+            ],
+            "clang_kind": "Record",
+            "is_const": false
+        },
+        "platform_availability": [],
+        "availability": "Available"
+    }]);
+    let source = generate_rust(&HeaderFile::new(
+        "t.h".into(),
+        serde_json::from_value(entries).expect("valid entries"),
+    ));
+    assert!(
+        source.contains("    pub next: *const node,\n    pub prev: *mut node,\n"),
+        "{}",
+        source
+    );
+}
+
+fn typedef(name: &str, underlying: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "Typedef",
+        "name": name,
+        "clang_kind": "Typedef",
+        "nullability": null,
+        "objc_encoding": null,
+        "is_const": false,
+        "typedef_name": name,
+        "header": "/usr/include/t.h",
+        "underlying_type": underlying
+    })
+}
+
+#[test]
+fn test_generate_rust_typedefs() {
+    let ns_integer = || typedef("NSInteger", int("long", "Long", false));
+    let entries = serde_json::json!([
+        {
+            "kind": "TypedefDecl",
+            "name": "FooIndex",
+            "ty": ns_integer(),
+            "platform_availability": null,
+            "availability": "Available"
+        },
+        {
+            "kind": "FunctionDecl",
+            "name": "foo",
+            "ty": int("int", "Int", false),
+            "arguments": [
+                { "name": "index", "objc_type": typedef("FooIndex", ns_integer()) },
+                { "name": "count", "objc_type": typedef("size_t", int("unsigned long", "ULong", false)) }
+            ],
+            "result_type": ns_integer(),
+            "platform_availability": null,
+            "availability": "Available"
+        }
+    ]);
+    let header_file = HeaderFile::new(
+        "t.h".into(),
+        serde_json::from_value(entries).expect("valid entries"),
+    );
+    let source = generate_rust(&header_file);
+    // `NSInteger` is declared in a header that is not generated
+    for expected in [
+        "pub type FooIndex = c_long;",
+        "pub fn foo(index: FooIndex, count: usize) -> c_long;",
+    ] {
+        assert!(
+            source.contains(expected),
+            "missing {:?} in\n{}",
+            expected,
+            source
+        );
+    }
+}
+
+fn enum_decl(name: &str, prefix: &str, attributes: serde_json::Value) -> serde_json::Value {
+    let mut decl = serde_json::json!({
+        "kind": "EnumDecl",
+        "decls": [
+            { "name": format!("{}A", prefix), "value": "1", "objc_type": int("int", "Int", false) },
+            { "name": format!("{}B", prefix), "value": "2", "objc_type": int("int", "Int", false) }
+        ],
+        "name": name,
+        "ty": int("unsigned int", "UInt", false),
+        "is_scoped": false,
+        "platform_availability": null,
+        "availability": "Available"
+    });
+    decl.as_object_mut()
+        .unwrap()
+        .extend(attributes.as_object().unwrap().clone());
+    decl
+}
+
+#[test]
+fn test_generate_rust_enums() {
+    let entries = serde_json::json!([
+        enum_decl(
+            "Closed",
+            "Closed",
+            serde_json::json!({ "extensibility": "Closed" })
+        ),
+        enum_decl(
+            "Mask",
+            "Mask",
+            serde_json::json!({ "enum_macro": "NSOptions", "extensibility": "Open", "is_flag_enum": true })
+        ),
+        enum_decl(
+            "Open",
+            "Open",
+            serde_json::json!({ "extensibility": "Open" })
+        ),
+        enum_decl(
+            "(anonymous enum at t.h:9:1)",
+            "Anon",
+            serde_json::json!({ "is_anonymous": true, "typedef_name": "Anon" })
+        ),
+    ]);
+    let header_file = HeaderFile::new(
+        "t.h".into(),
+        serde_json::from_value(entries).expect("valid entries"),
+    );
+    let source = generate_rust(&header_file);
+    for expected in [
+        "#[repr(u32)]\n#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]\npub enum Closed {\n    ClosedA = 1,\n    ClosedB = 2,\n}",
+        "#[repr(transparent)]\n#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]\npub struct Mask(pub c_uint);",
+        "impl std::ops::BitOr for Mask {",
+        "pub const MaskB: Mask = Mask(2);",
+        "pub type Open = c_uint;",
+        "pub const OpenA: Open = 1;",
+        "pub type Anon = c_uint;",
+        "pub const AnonA: Anon = 1;",
+    ] {
+        assert!(source.contains(expected), "missing {:?} in\n{}", expected, source);
+    }
+    assert!(!source.contains("pub const ClosedA"));
+}
+
+fn record(ident: &str, fields: serde_json::Value, layout: serde_json::Value) -> serde_json::Value {
+    let ty = serde_json::json!({
+        "kind": "StructRecord",
+        "name": format!("struct {}", ident),
+        "ident": ident,
+        "fields": fields,
+        "clang_kind": "Record",
+        "is_const": false,
+        "layout": layout
+    });
+    serde_json::json!({
+        "kind": "StructDecl",
+        "name": ident,
+        "fields": [],
+        "ty": ty,
+        "platform_availability": [],
+        "availability": "Available"
+    })
+}
+
+fn field(name: &str, ty: serde_json::Value, layout: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({ "name": name, "is_anonymous": null, "ty": ty, "layout": layout })
+}
+
+#[test]
+fn test_generate_rust_layouts() {
+    let char_field = |name: &str, bit_offset: usize| {
+        field(
+            name,
+            int("char", "CharS", false),
+            serde_json::json!({ "bit_offset": bit_offset }),
+        )
+    };
+    let int_field = |name: &str, bit_offset: usize| {
+        field(
+            name,
+            int("int", "Int", false),
+            serde_json::json!({ "bit_offset": bit_offset }),
+        )
+    };
+    let entries = serde_json::json!([
+        record(
+            "pair",
+            serde_json::json!([char_field("tag", 0), int_field("value", 32)]),
+            serde_json::json!({ "size": 8, "align": 4 }),
+        ),
+        record(
+            "packed_pair",
+            serde_json::json!([char_field("tag", 0), int_field("value", 8)]),
+            serde_json::json!({ "size": 5, "align": 1, "packed": true }),
+        ),
+        record(
+            "aligned_pair",
+            serde_json::json!([char_field("tag", 0), int_field("value", 32)]),
+            serde_json::json!({ "size": 16, "align": 16, "aligned": 16 }),
+        ),
+        record(
+            "flags",
+            serde_json::json!([field(
+                "mask",
+                int("unsigned int", "UInt", false),
+                serde_json::json!({ "bit_offset": 0, "bitfield_width": 3 }),
+            )]),
+            serde_json::json!({ "size": 4, "align": 4 }),
+        ),
+        record(
+            "packed_aligned",
+            serde_json::json!([char_field("tag", 0), int_field("value", 8)]),
+            serde_json::json!({ "size": 8, "align": 8, "packed": true, "aligned": 8 }),
+        ),
+        record(
+            "holder",
+            serde_json::json!([{
+                "name": null,
+                "is_anonymous": true,
+                "ty": {
+                    "kind": "UnionRecord",
+                    "name": null,
+                    "ident": null,
+                    "fields": [field(
+                        "mask",
+                        int("unsigned int", "UInt", false),
+                        serde_json::json!({ "bit_offset": 0, "bitfield_width": 3 }),
+                    )],
+                    "clang_kind": "Record",
+                    "is_const": false,
+                    "layout": { "size": 4, "align": 4 }
+                },
+                "layout": { "bit_offset": 0 }
+            }]),
+            serde_json::json!({ "size": 4, "align": 4 }),
+        ),
+    ]);
+    let source = generate_rust(&HeaderFile::new(
+        "t.h".into(),
+        serde_json::from_value(entries).expect("valid entries"),
+    ));
+    for expected in [
+        "#[repr(C)]\n#[derive(Copy, Clone)]\npub struct pair {",
+        "const _: () = {\n    assert!(std::mem::size_of::<pair>() == 8);\n    assert!(std::mem::align_of::<pair>() == 4);\n    assert!(std::mem::offset_of!(pair, tag) == 0);\n    assert!(std::mem::offset_of!(pair, value) == 4);\n};",
+        "#[repr(C, packed)]\n#[derive(Copy, Clone)]\npub struct packed_pair {",
+        "assert!(std::mem::offset_of!(packed_pair, value) == 1);",
+        "#[repr(C, align(16))]\n#[derive(Copy, Clone)]\npub struct aligned_pair {",
+        "assert!(std::mem::size_of::<aligned_pair>() == 16);",
+        "// opaque, unsupported `unsigned int mask : 3`\n#[repr(C, align(4))]\n#[derive(Copy, Clone)]\npub struct flags {\n    _opaque: [u8; 4],\n}",
+        "const _: () = {\n    assert!(std::mem::size_of::<flags>() == 4);\n    assert!(std::mem::align_of::<flags>() == 4);\n};",
+        "// opaque, unsupported `packed_aligned (packed and aligned)`\n#[repr(C, align(8))]\n#[derive(Copy, Clone)]\npub struct packed_aligned {\n    _opaque: [u8; 8],\n}",
+        "pub struct holder {\n    pub __anon0: holder__anon0,\n}",
+        "pub struct holder__anon0 {\n    _opaque: [u8; 4],\n}",
+    ] {
+        assert!(source.contains(expected), "missing {:?} in\n{}", expected, source);
+    }
+    assert!(!source.contains("// skipped"));
+}
+
+fn void_ptr() -> serde_json::Value {
+    serde_json::json!({
+        "kind": "Pointer",
+        "name": "void *",
+        "clang_kind": "Pointer",
+        "pointee_type": int("void", "Void", false),
+        "is_const": false
+    })
+}
+
+fn function(name: &str, attributes: serde_json::Value) -> serde_json::Value {
+    let mut function = serde_json::json!({
+        "kind": "FunctionDecl",
+        "name": name,
+        "ty": int("void *(void *)", "FunctionPrototype", false),
+        "arguments": [{ "name": "p", "objc_type": void_ptr() }],
+        "result_type": void_ptr(),
+        "platform_availability": [],
+        "availability": "Available"
+    });
+    function
+        .as_object_mut()
+        .unwrap()
+        .extend(attributes.as_object().unwrap().clone());
+    function
+}
+
+#[test]
+fn test_generate_rust_functions() {
+    let entries = serde_json::json!([
+        function(
+            "FooMin",
+            serde_json::json!({ "storage_class": "Static", "is_inline": true })
+        ),
+        function(
+            "FooCreate",
+            serde_json::json!({ "return_ownership": "Retained", "warn_unused_result": true })
+        ),
+        function(
+            "FooGet",
+            serde_json::json!({ "return_ownership": "NotRetained" })
+        ),
+        function(
+            "FooRelease",
+            serde_json::json!({
+                "arguments": [{ "name": "p", "objc_type": void_ptr(), "is_cf_consumed": true }]
+            }),
+        ),
+        function("FooLog", serde_json::json!({ "is_variadic": true })),
+        function(
+            "FooAbort",
+            serde_json::json!({ "result_type": int("void", "Void", false), "is_noreturn": true })
+        ),
+        function(
+            "FooVoid",
+            serde_json::json!({ "arguments": [], "is_variadic": true })
+        ),
+    ]);
+    let source = generate_rust(&HeaderFile::new(
+        "t.h".into(),
+        serde_json::from_value(entries).expect("valid entries"),
+    ));
+    for expected in [
+        "// skipped `FooMin`: static inline function without a symbol",
+        "    /// Returns a retained reference: the caller must release it.\n    #[must_use]\n    pub fn FooCreate(p: *mut c_void) -> *mut c_void;",
+        "    /// Returns a reference that is not retained: the caller must not release it.\n    pub fn FooGet(",
+        "    /// Consumes `p`: the function releases it.\n    pub fn FooRelease(",
+        "    pub fn FooLog(p: *mut c_void, ...) -> *mut c_void;",
+        "    pub fn FooAbort(p: *mut c_void) -> !;",
+        "// skipped `FooVoid`: variadic function without parameters",
+    ] {
+        assert!(source.contains(expected), "missing {:?} in\n{}", expected, source);
+    }
+    assert!(!source.contains("pub fn FooMin"));
+}
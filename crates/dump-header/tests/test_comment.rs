@@ -0,0 +1,77 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    entity::{ConvertOptions, Entry},
+    headerfiletree::HeaderFile,
+};
+
+#[test]
+fn test_doc_comments() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, None)?;
+    let source = r#"
+/**
+ * Moves a point.
+ *
+ * The point is moved in place.
+ *
+ * @param p the point
+ * @param dx the distance
+ * @return zero on success
+ * @note not thread safe
+ * @deprecated use move_to
+ */
+int move(int *p, int dx);
+
+/// Colors.
+enum color {
+    /// The red color.
+    RED,
+    GREEN,
+};
+
+int undocumented(void);
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let options = ConvertOptions {
+        comments: true,
+        ..Default::default()
+    };
+    let header_file = HeaderFile::from_path_with_options(&path, &tu, &options);
+    let entries = &header_file.entries;
+
+    let comment = entries[0].comment().expect("comment of move");
+    assert_eq!(comment.brief.as_deref(), Some("Moves a point."));
+    assert!(comment
+        .paragraphs
+        .iter()
+        .any(|paragraph| paragraph == "The point is moved in place."));
+    let params: Vec<_> = comment
+        .params
+        .iter()
+        .map(|param| (param.name.as_str(), param.text.as_str()))
+        .collect();
+    assert_eq!(params, vec![("p", "the point"), ("dx", "the distance")]);
+    assert_eq!(comment.returns.as_deref(), Some("zero on success"));
+    assert_eq!(comment.notes, vec!["not thread safe"]);
+    assert_eq!(comment.deprecated.as_deref(), Some("use move_to"));
+
+    let Entry::EnumDecl { decls, .. } = &entries[1] else {
+        panic!("expected an enum: {:?}", entries[1]);
+    };
+    assert_eq!(
+        entries[1].comment().and_then(|c| c.brief.as_deref()),
+        Some("Colors.")
+    );
+    assert_eq!(
+        decls[0].comment.as_ref().and_then(|c| c.brief.as_deref()),
+        Some("The red color.")
+    );
+    assert!(decls[1].comment.is_none());
+    assert!(entries[2].comment().is_none());
+
+    let header_file = HeaderFile::from_path(&path, &tu);
+    assert!(header_file.entries.iter().all(|e| e.comment().is_none()));
+    Ok(())
+}
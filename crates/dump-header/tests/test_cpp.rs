@@ -0,0 +1,118 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    entity::{Access, Entry},
+    headerfiletree::HeaderFile,
+    typ::Typ,
+};
+
+#[test]
+fn test_cpp_parsing() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::Cpp, None)?;
+    let source = r#"
+extern "C" {
+int c_function(int x);
+}
+
+namespace geo {
+enum class Unit : unsigned char { Meter, Foot };
+using Scalar = double;
+
+class Shape {
+public:
+    Shape();
+    virtual ~Shape();
+    virtual Scalar area() const = 0;
+    static int count();
+protected:
+    Unit unit;
+};
+
+struct Circle : public virtual Shape {
+    Circle(const Circle &other);
+    Scalar area() const override;
+    void scale(Scalar &&factor);
+private:
+    Scalar radius;
+};
+}
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let header_file = HeaderFile::from_path(&path, &tu);
+    let entries = &header_file.entries;
+    assert_eq!(entries.len(), 2);
+    assert!(matches!(&entries[0], Entry::FunctionDecl { name, .. } if name == "c_function"));
+
+    let Entry::Namespace {
+        name,
+        is_inline,
+        entries,
+        ..
+    } = &entries[1]
+    else {
+        panic!("expected a namespace: {:?}", entries[1]);
+    };
+    assert_eq!(name.as_deref(), Some("geo"));
+    assert!(!is_inline);
+    assert!(matches!(&entries[0], Entry::EnumDecl { name, is_scoped: true, .. } if name == "Unit"));
+    assert!(matches!(&entries[1], Entry::TypeAliasDecl { name, .. } if name == "Scalar"));
+
+    let Entry::ClassDecl {
+        name,
+        is_struct,
+        constructors,
+        destructor,
+        methods,
+        fields,
+        is_abstract,
+        ..
+    } = &entries[2]
+    else {
+        panic!("expected a class: {:?}", entries[2]);
+    };
+    assert_eq!(name.as_deref(), Some("Shape"));
+    assert!(!is_struct);
+    assert!(is_abstract);
+    assert_eq!(constructors.len(), 1);
+    assert!(destructor.as_ref().unwrap().is_virtual);
+    assert_eq!(methods.len(), 2);
+    assert!(methods[0].is_pure_virtual && methods[0].is_const);
+    assert!(methods[1].is_static);
+    assert!(matches!(fields[0].access, Some(Access::Protected)));
+
+    let Entry::ClassDecl {
+        name,
+        is_struct,
+        bases,
+        constructors,
+        methods,
+        fields,
+        ..
+    } = &entries[3]
+    else {
+        panic!("expected a class: {:?}", entries[3]);
+    };
+    assert_eq!(name.as_deref(), Some("Circle"));
+    assert!(is_struct);
+    assert_eq!(bases.len(), 1);
+    assert!(bases[0].name.ends_with("Shape") && bases[0].is_virtual);
+    assert!(matches!(bases[0].access, Some(Access::Public)));
+    assert!(matches!(
+        constructors[0].arguments[0].objc_type,
+        Typ::Reference {
+            clang_kind: clang::TypeKind::LValueReference,
+            ..
+        }
+    ));
+    assert!(matches!(
+        methods[1].arguments[0].objc_type,
+        Typ::Reference {
+            clang_kind: clang::TypeKind::RValueReference,
+            ..
+        }
+    ));
+    assert!(matches!(fields[0].access, Some(Access::Private)));
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use dump_header::{
+    deps::{framework_name, IncludeEdge, IncludeGraph},
+    entity::Entry,
+    headerfiletree::HeaderFile,
+};
+
+fn header_file(path: &str, includes: &[&str]) -> HeaderFile {
+    let entries = includes
+        .iter()
+        .map(|include| Entry::InclusionDirective {
+            name: include.rsplit('/').next().unwrap().to_string(),
+            path: PathBuf::from(include),
+            comment: None,
+            location: None,
+        })
+        .collect();
+    HeaderFile::new(PathBuf::from(path), entries)
+}
+
+fn edge(from: &str, to: &str) -> IncludeEdge {
+    IncludeEdge {
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+#[test]
+fn test_include_graph() {
+    let header_files = vec![
+        header_file(
+            "/F/Foo.framework/Headers/Foo.h",
+            &[
+                "/F/Foo.framework/Headers/A.h",
+                "/F/Bar.framework/Headers/Bar.h",
+            ],
+        ),
+        header_file(
+            "/F/Foo.framework/Headers/A.h",
+            &["/F/Foo.framework/Headers/B.h"],
+        ),
+        header_file(
+            "/F/Foo.framework/Headers/B.h",
+            &["/F/Foo.framework/Headers/A.h"],
+        ),
+    ];
+    let headers = vec![
+        PathBuf::from("/F/Foo.framework/Headers/A.h"),
+        PathBuf::from("/F/Foo.framework/Headers/Unused.h"),
+    ];
+    let root = PathBuf::from("/F/Foo.framework/Headers/Foo.h");
+
+    let graph = IncludeGraph::from_header_files(&root, &header_files, &headers, false);
+    assert_eq!(graph.root, "/F/Foo.framework/Headers/Foo.h");
+    assert_eq!(graph.nodes.len(), 4);
+    assert_eq!(
+        graph.edges,
+        vec![
+            edge(
+                "/F/Foo.framework/Headers/A.h",
+                "/F/Foo.framework/Headers/B.h"
+            ),
+            edge(
+                "/F/Foo.framework/Headers/B.h",
+                "/F/Foo.framework/Headers/A.h"
+            ),
+            edge(
+                "/F/Foo.framework/Headers/Foo.h",
+                "/F/Bar.framework/Headers/Bar.h"
+            ),
+            edge(
+                "/F/Foo.framework/Headers/Foo.h",
+                "/F/Foo.framework/Headers/A.h"
+            ),
+        ]
+    );
+    assert_eq!(
+        graph.cycles,
+        vec![vec![
+            "/F/Foo.framework/Headers/A.h".to_string(),
+            "/F/Foo.framework/Headers/B.h".to_string(),
+        ]]
+    );
+    assert_eq!(graph.unreachable, vec!["/F/Foo.framework/Headers/Unused.h"]);
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph includes {\n"));
+    assert!(dot.contains("    \"/F/Foo.framework/Headers/Foo.h\" [shape=box];\n"));
+    assert!(dot.contains("    \"/F/Foo.framework/Headers/A.h\" [color=red];\n"));
+    assert!(dot.contains("    \"/F/Foo.framework/Headers/Unused.h\" [style=dashed];\n"));
+    assert!(dot.contains(
+        "    \"/F/Foo.framework/Headers/Foo.h\" -> \"/F/Bar.framework/Headers/Bar.h\";\n"
+    ));
+
+    let collapsed = IncludeGraph::from_header_files(&root, &header_files, &headers, true);
+    assert_eq!(collapsed.root, "Foo");
+    assert_eq!(collapsed.nodes, vec!["Bar", "Foo"]);
+    assert_eq!(collapsed.edges, vec![edge("Foo", "Bar")]);
+    assert!(collapsed.cycles.is_empty());
+}
+
+#[test]
+fn test_framework_name() {
+    assert_eq!(
+        framework_name(&PathBuf::from("/S/Foo.framework/Headers/Foo.h")).as_deref(),
+        Some("Foo")
+    );
+    assert_eq!(
+        framework_name(&PathBuf::from(
+            "/S/Foo.framework/Frameworks/Bar.framework/Headers/Bar.h"
+        ))
+        .as_deref(),
+        Some("Bar")
+    );
+    assert_eq!(framework_name(&PathBuf::from("/usr/include/stdio.h")), None);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    diagnostic::{ensure_no_errors, get_diagnostics, Severity},
+    entity::ConvertOptions,
+    error::Error,
+    headerfiletree::HeaderFile,
+};
+
+#[test]
+fn test_diagnostics() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, None)?;
+    let source = r#"
+#include "missing.h"
+int foo(void)
+int bar(void);
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let header_file = HeaderFile::from_path(&path, &tu);
+    let diagnostics = &header_file.diagnostics;
+    let missing = diagnostics
+        .iter()
+        .find(|d| d.message.contains("missing.h"))
+        .expect("missing include");
+    assert_eq!(missing.severity, Severity::Fatal);
+    let location = missing.location.as_ref().unwrap();
+    assert_eq!(
+        (location.file.as_path(), location.line),
+        (path.as_path(), 2)
+    );
+
+    let semicolon = diagnostics
+        .iter()
+        .find(|d| d.message.contains("';'"))
+        .expect("missing semicolon");
+    assert!(semicolon.is_error());
+    assert!(semicolon
+        .fix_its
+        .iter()
+        .any(|fix_it| fix_it.replacement == ";"));
+
+    let diagnostics = get_diagnostics(&tu, None, &ConvertOptions::default());
+    assert!(matches!(
+        ensure_no_errors(&diagnostics),
+        Err(Error::Diagnostics(errors)) if errors.len() >= 2
+    ));
+
+    let (tu, _) = parser.parse_content("int baz(void);\n")?;
+    assert!(ensure_no_errors(&get_diagnostics(&tu, None, &ConvertOptions::default())).is_ok());
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use dump_header::{
+    diff::{diff, ApiDiff, ChangeKind, ItemKind},
+    entity::Entry,
+};
+
+fn ty(name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "OtherType",
+        "name": name,
+        "clang_kind": "Int",
+        "nullability": null,
+        "objc_encoding": "i",
+        "is_const": false
+    })
+}
+
+fn macos(introduced: u32, unavailable: bool) -> serde_json::Value {
+    serde_json::json!([{
+        "platform": "macos",
+        "introduced": { "x": introduced, "y": null, "z": null },
+        "deprecated": null,
+        "obsoleted": null,
+        "unavailable": unavailable,
+        "message": null
+    }])
+}
+
+fn function(
+    name: &str,
+    result: &str,
+    platform_availability: serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "FunctionDecl",
+        "name": name,
+        "ty": ty(&format!("{} (int)", result)),
+        "arguments": [{ "name": "x", "objc_type": ty("int") }],
+        "result_type": ty(result),
+        "platform_availability": platform_availability,
+        "availability": "Available"
+    })
+}
+
+fn point(fields: &[&str]) -> serde_json::Value {
+    let fields: Vec<serde_json::Value> = fields
+        .iter()
+        .map(|name| serde_json::json!({ "kind": "FieldDecl", "name": name, "ty": ty("int") }))
+        .collect();
+    serde_json::json!({
+        "kind": "StructDecl",
+        "name": "point",
+        "fields": fields,
+        "ty": {
+            "kind": "StructRecord",
+            "name": "struct point",
+            "ident": "point",
+            "fields": [],
+            "clang_kind": "Record",
+            "nullability": null,
+            "objc_encoding": null,
+            "is_const": false
+        },
+        "platform_availability": null,
+        "availability": "Available"
+    })
+}
+
+fn interface(methods: &[&str]) -> serde_json::Value {
+    let methods: Vec<serde_json::Value> = methods
+        .iter()
+        .map(|name| {
+            serde_json::json!({
+                "name": name,
+                "arguments": [],
+                "result_type": ty("void"),
+                "optional": false,
+                "platform_availability": null,
+                "availability": "Available"
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "kind": "ObjCInterfaceDecl",
+        "name": "Foo",
+        "template_args": [],
+        "superclass": "NSObject",
+        "protocols": [],
+        "properties": [],
+        "instance_methods": methods,
+        "class_methods": [],
+        "platform_availability": null,
+        "availability": "Available"
+    })
+}
+
+fn entries(values: Vec<serde_json::Value>) -> Vec<Entry> {
+    serde_json::from_value(serde_json::Value::Array(values)).unwrap()
+}
+
+fn find(api_diff: &ApiDiff, kind: ItemKind, name: &str) -> (ChangeKind, bool) {
+    let change = api_diff
+        .changes
+        .iter()
+        .find(|change| change.kind == kind && change.name == name)
+        .unwrap_or_else(|| panic!("no change of {}", name));
+    (change.change, change.breaking)
+}
+
+#[test]
+fn test_diff_identical() {
+    let old = entries(vec![function("f", "int", macos(10, false)), point(&["x"])]);
+    let api_diff = diff(&old, &old);
+    assert!(api_diff.changes.is_empty());
+    assert!(!api_diff.has_breaking_changes());
+}
+
+#[test]
+fn test_diff_additions_are_not_breaking() {
+    let old = entries(vec![function("f", "int", macos(10, false))]);
+    let new = entries(vec![
+        function("f", "int", macos(10, false)),
+        function("g", "int", macos(11, false)),
+        interface(&["bar"]),
+    ]);
+    let api_diff = diff(&old, &new);
+    assert_eq!(
+        find(&api_diff, ItemKind::Function, "g"),
+        (ChangeKind::Added, false)
+    );
+    assert_eq!(
+        find(&api_diff, ItemKind::Method, "Foo -bar"),
+        (ChangeKind::Added, false)
+    );
+    assert!(!api_diff.has_breaking_changes());
+}
+
+#[test]
+fn test_diff_breaking_changes() {
+    let old = entries(vec![
+        function("f", "int", macos(10, false)),
+        function("g", "int", macos(10, false)),
+        point(&["x"]),
+        interface(&["bar", "baz"]),
+    ]);
+    let new = entries(vec![
+        function("f", "long", macos(10, false)),
+        function("g", "int", macos(10, true)),
+        point(&["x", "y"]),
+        interface(&["bar"]),
+    ]);
+    let api_diff = diff(&old, &new);
+    assert_eq!(
+        find(&api_diff, ItemKind::Function, "f"),
+        (ChangeKind::Changed, true)
+    );
+    assert_eq!(
+        find(&api_diff, ItemKind::Function, "g"),
+        (ChangeKind::AvailabilityChanged, true)
+    );
+    assert_eq!(
+        find(&api_diff, ItemKind::Field, "point.y"),
+        (ChangeKind::Added, true)
+    );
+    assert_eq!(
+        find(&api_diff, ItemKind::Method, "Foo -baz"),
+        (ChangeKind::Removed, true)
+    );
+    assert!(api_diff.has_breaking_changes());
+    assert!(api_diff
+        .to_text()
+        .contains("~! function f: int (int) -> long (int)\n"));
+}
+
+#[test]
+fn test_diff_newer_introduction_is_not_breaking() {
+    let old = entries(vec![function("f", "int", macos(10, false))]);
+    let new = entries(vec![function("f", "int", macos(11, false))]);
+    let api_diff = diff(&old, &new);
+    assert_eq!(
+        find(&api_diff, ItemKind::Function, "f"),
+        (ChangeKind::AvailabilityChanged, false)
+    );
+}
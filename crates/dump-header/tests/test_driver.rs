@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use dump_header::driver::{find_header_files, parallel_map, worker_count};
+use temp_dir::TempDir;
+
+#[test]
+fn test_parallel_map_order() {
+    let items: Vec<u64> = (0..32).collect();
+    let expected: Vec<u64> = items.iter().map(|i| i * i).collect();
+    for jobs in [0, 1, 2, 3, 8, 64] {
+        // later items finish first
+        let squares = parallel_map(&items, jobs, |i| {
+            std::thread::sleep(Duration::from_millis(32 - i));
+            i * i
+        });
+        assert_eq!(squares, expected, "jobs: {}", jobs);
+    }
+    assert!(parallel_map(&Vec::<u64>::new(), 4, |i| *i).is_empty());
+    assert!(worker_count(0) >= 1);
+    assert_eq!(worker_count(3), 3);
+}
+
+#[test]
+fn test_find_header_files() -> std::io::Result<()> {
+    let dir = TempDir::new()?;
+    std::fs::create_dir_all(dir.child("sub"))?;
+    for name in ["b.h", "a.h", "sub/c.h", "d.m", "sub/e.txt"] {
+        std::fs::write(dir.child(name), "")?;
+    }
+    let other = dir.child("sub/e.txt");
+    let files = find_header_files(&[dir.path().to_path_buf(), other.clone(), dir.child("a.h")])?;
+    assert_eq!(
+        files,
+        vec![
+            dir.child("a.h"),
+            dir.child("b.h"),
+            dir.child("sub/c.h"),
+            other,
+        ]
+    );
+    Ok(())
+}
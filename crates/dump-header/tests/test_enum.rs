@@ -0,0 +1,91 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    entity::{Entry, EnumExtensibility, EnumMacro},
+    headerfiletree::HeaderFile,
+};
+
+#[test]
+fn test_enum_semantics() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, None)?;
+    let source = r#"
+#define NS_ENUM(_type, _name) enum __attribute__((enum_extensibility(open))) _name : _type _name; enum _name : _type
+#define NS_OPTIONS(_type, _name) enum __attribute__((flag_enum, enum_extensibility(open))) _name : _type _name; enum _name : _type
+typedef NS_ENUM(long, FooStyle) { FooStyleA, FooStyleB };
+typedef NS_OPTIONS(unsigned long, FooMask) { FooMaskA = 1, FooMaskB = 2 };
+enum __attribute__((enum_extensibility(closed))) bar { BAR_A, BAR_B };
+typedef enum { BAZ_A } baz;
+enum { kQux = 7 };
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let header_file = HeaderFile::from_path(&path, &tu);
+    let enums: Vec<_> = header_file
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::EnumDecl {
+                name,
+                is_anonymous,
+                typedef_name,
+                enum_macro,
+                extensibility,
+                is_flag_enum,
+                ..
+            } => Some((
+                name.as_str(),
+                *is_anonymous,
+                typedef_name.as_deref(),
+                *enum_macro,
+                *extensibility,
+                *is_flag_enum,
+            )),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(enums.len(), 5, "{:?}", enums);
+    assert_eq!(
+        enums[0],
+        (
+            "FooStyle",
+            false,
+            Some("FooStyle"),
+            Some(EnumMacro::NSEnum),
+            Some(EnumExtensibility::Open),
+            false
+        )
+    );
+    assert_eq!(
+        enums[1],
+        (
+            "FooMask",
+            false,
+            Some("FooMask"),
+            Some(EnumMacro::NSOptions),
+            Some(EnumExtensibility::Open),
+            true
+        )
+    );
+    assert_eq!(
+        enums[2],
+        (
+            "bar",
+            false,
+            None,
+            None,
+            Some(EnumExtensibility::Closed),
+            false
+        )
+    );
+    assert!(enums[3].1);
+    assert_eq!(enums[3].2, Some("baz"));
+    assert!(enums[4].1);
+    assert!(
+        enums[4].0.starts_with("(anonymous enum at "),
+        "{}",
+        enums[4].0
+    );
+    assert_eq!(enums[4].2, None);
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use dump_header::{
+    ast::AstNode,
+    cli::Format,
+    entity::Entry,
+    format::{from_slice, to_vec, Document},
+    framework::Framework,
+    headerfiletree::HeaderFile,
+};
+
+const FORMATS: [Format; 6] = [
+    Format::Json,
+    Format::JsonCompact,
+    Format::Ndjson,
+    Format::Yaml,
+    Format::Cbor,
+    Format::MessagePack,
+];
+
+fn int() -> serde_json::Value {
+    serde_json::json!({
+        "kind": "OtherType",
+        "name": "int",
+        "clang_kind": "Int",
+        "nullability": null,
+        "objc_encoding": "i",
+        "is_const": false
+    })
+}
+
+fn header_file_json(path: &str) -> serde_json::Value {
+    serde_json::json!({
+        "path": path,
+        "entries": [
+            {
+                "kind": "InclusionDirective",
+                "name": "b.h",
+                "path": "/F/Foo.framework/Headers/b.h"
+            },
+            {
+                "kind": "VarDecl",
+                "name": "kValues",
+                "ty": int(),
+                "init_expr": {
+                    "kind": "InitListExpr",
+                    "values": [{ "Int": -1 }, { "Float": 0.5 }, { "String": "a" }, "Null"]
+                },
+                "platform_availability": [
+                    {
+                        "platform": "macos",
+                        "introduced": { "x": 10, "y": 15, "z": null },
+                        "deprecated": null,
+                        "obsoleted": null,
+                        "unavailable": false,
+                        "message": null
+                    }
+                ],
+                "availability": "Deprecated",
+                "comment": {
+                    "raw": "/// Values.",
+                    "brief": "Values.",
+                    "paragraphs": ["Values."],
+                    "params": [],
+                    "template_params": [],
+                    "returns": null,
+                    "notes": [],
+                    "deprecated": null,
+                    "commands": []
+                },
+                "location": {
+                    "file": "a.h",
+                    "line": 3,
+                    "column": 12,
+                    "extent": { "start": { "line": 3, "column": 1 }, "end": { "line": 3, "column": 30 } }
+                }
+            },
+            {
+                "kind": "MacroDefinition",
+                "name": "kMax",
+                "parameters": null,
+                "tokens": "0xffffffffffffffffULL",
+                "value": { "UInt": u64::MAX }
+            }
+        ],
+        "diagnostics": [
+            {
+                "severity": "Warning",
+                "message": "unused",
+                "location": null,
+                "fix_its": [{ "location": null, "replacement": ";" }]
+            }
+        ]
+    })
+}
+
+fn assert_round_trip<T: Document>(value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    let expected = serde_json::to_value(value)?;
+    for format in FORMATS {
+        let bytes = to_vec(value, format)?;
+        let actual: T = from_slice(&bytes, format)
+            .map_err(|e| format!("{:?}: {:?}", format, std::error::Error::source(&e)))?;
+        assert_eq!(serde_json::to_value(&actual)?, expected, "{:?}", format);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_header_file_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let header_file: HeaderFile = serde_json::from_value(header_file_json("/F/a.h"))?;
+    assert_round_trip(&header_file)?;
+
+    let ndjson = String::from_utf8(to_vec(&header_file, Format::Ndjson)?)?;
+    let lines: Vec<&str> = ndjson.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(
+        lines[0].starts_with("{\"kind\":\"HeaderFile\",\"format_version\":0,\"path\":\"/F/a.h\"")
+    );
+    assert!(lines[1].starts_with("{\"kind\":\"InclusionDirective\""));
+    Ok(())
+}
+
+#[test]
+fn test_version_0_header_file() -> Result<(), Box<dyn std::error::Error>> {
+    // a dump from before `is_scoped`
+    let header_file: HeaderFile = serde_json::from_value(serde_json::json!({
+        "format_version": 0,
+        "path": "/F/a.h",
+        "entries": [
+            {
+                "kind": "EnumDecl",
+                "decls": [],
+                "name": "Foo",
+                "ty": int(),
+                "platform_availability": null,
+                "availability": "Available"
+            }
+        ]
+    }))?;
+    assert!(matches!(
+        header_file.entries[0],
+        Entry::EnumDecl {
+            is_scoped: false,
+            ..
+        }
+    ));
+    assert_round_trip(&header_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_header_files_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let header_files: Vec<HeaderFile> = vec![
+        serde_json::from_value(header_file_json("/F/a.h"))?,
+        serde_json::from_value(header_file_json("/F/b.h"))?,
+    ];
+    assert_round_trip(&header_files)?;
+    assert_round_trip(&Vec::<HeaderFile>::new())?;
+    Ok(())
+}
+
+#[test]
+fn test_framework_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let framework = |name: &str| -> serde_json::Result<Framework> {
+        let root = format!("/F/{}.framework/Headers/{}.h", name, name);
+        let other = format!("/F/{}.framework/Headers/b.h", name);
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "root_header": root,
+            "header_file_tree": {
+                "root_path": root,
+                "path_entry_hash_map": {
+                    root.clone(): header_file_json(&root),
+                    other.clone(): { "path": other, "entries": [] }
+                }
+            }
+        }))
+    };
+    let foo = framework("Foo")?;
+    assert_round_trip(&foo)?;
+    assert_round_trip(&vec![foo, framework("Bar")?])?;
+    Ok(())
+}
+
+#[test]
+fn test_ast_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let nodes: Vec<AstNode> = serde_json::from_value(serde_json::json!([
+        {
+            "name": "foo",
+            "kind": "FunctionDecl",
+            "ty": "int (int)",
+            "usr": "c:@F@foo",
+            "location": {
+                "file": "/F/a.h",
+                "line": 3,
+                "column": 5,
+                "extent": {
+                    "start": { "line": 3, "column": 1 },
+                    "end": { "line": 3, "column": 40 }
+                }
+            },
+            "attributes": ["__attribute__((deprecated(\"use bar\")))"],
+            "children": [
+                { "name": "x", "kind": "ParmDecl", "ty": "int", "children": [] }
+            ]
+        },
+        { "name": null, "kind": "StructDecl", "ty": null, "children": [] }
+    ]))?;
+    assert_round_trip(&nodes)?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::path::Path;
+
+use common::{config, parser_from};
+use dump_header::{
+    cli::Lang,
+    entity::{ConvertOptions, Entry},
+    error::Error,
+    framework::{find_frameworks, FrameworkUnit},
+    parser::ParserConfig,
+};
+use temp_dir::TempDir;
+
+fn write_header(path: &Path, content: &str) -> std::io::Result<()> {
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(path, content)
+}
+
+fn synthetic_frameworks() -> std::io::Result<TempDir> {
+    let dir = TempDir::new()?;
+    write_header(
+        &dir.child("Foo.framework/Headers/Foo.h"),
+        "#include <Foo/FooTypes.h>\nint foo(FooInt x);\n",
+    )?;
+    write_header(
+        &dir.child("Foo.framework/Headers/FooTypes.h"),
+        "typedef int FooInt;\n",
+    )?;
+    write_header(
+        &dir.child("Bar.framework/Headers/Bar.h"),
+        "int bar(void);\n",
+    )?;
+    // no umbrella header
+    write_header(
+        &dir.child("Baz.framework/Headers/Other.h"),
+        "int baz(void);\n",
+    )?;
+    std::fs::create_dir_all(dir.child("NotAFramework/Headers"))?;
+    Ok(dir)
+}
+
+#[test]
+fn test_find_frameworks() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let dir = synthetic_frameworks()?;
+    let names = find_frameworks(&[dir.path().to_path_buf(), dir.path().to_path_buf()])?;
+    assert_eq!(names, vec!["Bar", "Foo"]);
+    Ok(())
+}
+
+#[test]
+fn test_framework_dump() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let dir = synthetic_frameworks()?;
+    let parser = parser_from(ParserConfig {
+        framework_paths: vec![dir.path().to_path_buf()],
+        ..config(Lang::C, None)
+    })?;
+
+    let framework = FrameworkUnit::with_parser("Foo", &parser)?.dump(&ConvertOptions::default());
+    assert_eq!(framework.name(), "Foo");
+    assert!(framework
+        .root_header()
+        .ends_with("Foo.framework/Headers/Foo.h"));
+    let root = framework.header_file_tree().get_root().unwrap();
+    assert!(root
+        .entries()
+        .iter()
+        .any(|entry| matches!(entry, Entry::FunctionDecl { name, .. } if name == "foo")));
+    let children = root.get_children();
+    assert_eq!(children.len(), 1);
+    assert!(children[0].path().ends_with("FooTypes.h"));
+
+    assert!(matches!(
+        FrameworkUnit::with_parser("Missing", &parser),
+        Err(Error::FrameworkNotFound(_))
+    ));
+    Ok(())
+}
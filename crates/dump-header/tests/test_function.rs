@@ -0,0 +1,179 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    entity::{
+        CallingConvention, Entry, FormatAttribute, ReturnOwnership, StorageClass, Visibility,
+    },
+    headerfiletree::HeaderFile,
+};
+
+fn tokens(text: &str) -> Vec<String> {
+    text.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn test_format_attribute_tokens() {
+    let format = |archetype: &str, format_index, first_argument| {
+        Some(FormatAttribute {
+            archetype: archetype.to_string(),
+            format_index,
+            first_argument,
+        })
+    };
+    assert_eq!(
+        FormatAttribute::from_tokens(&tokens("format ( printf , 1 , 2 )")),
+        format("printf", 1, 2)
+    );
+    assert_eq!(
+        FormatAttribute::from_tokens(&tokens("__format__ ( __NSString__ , 2 , 0 )")),
+        format("NSString", 2, 0)
+    );
+    assert_eq!(
+        FormatAttribute::from_tokens(&tokens("CF_FORMAT_FUNCTION ( 3 , 4 )")),
+        format("CFString", 3, 4)
+    );
+    assert_eq!(
+        FormatAttribute::from_tokens(&tokens("format_arg ( 1 )")),
+        None
+    );
+    assert_eq!(
+        FormatAttribute::from_tokens(&tokens("NS_FORMAT_FUNCTION ( F , A )")),
+        None
+    );
+}
+
+#[test]
+fn test_function_attributes() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, Some("x86_64-apple-macos10.15"))?;
+    let source = r#"
+#define CF_RETURNS_RETAINED __attribute__((cf_returns_retained))
+#define CF_CONSUMED __attribute__((cf_consumed))
+int foo_log(const char *format, ...) __attribute__((format(printf, 1, 2)));
+static inline int foo_min(int a, int b) { return a < b ? a : b; }
+extern void foo_abort(void) __attribute__((noreturn));
+_Noreturn void foo_exit(int status);
+__attribute__((visibility("hidden"))) void foo_private(void);
+__attribute__((swiftcall)) void foo_swift(void);
+void *foo_create(void) CF_RETURNS_RETAINED __attribute__((warn_unused_result));
+void foo_release(CF_CONSUMED void *p);
+void foo_copy(void *dst, const void *src, int n) __attribute__((nonnull(1))) __attribute__((nonnull(2)));
+void foo_fill(void *dst, int n) __attribute__((nonnull));
+static int foo_count;
+extern _Thread_local int foo_error;
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let header_file = HeaderFile::from_path(&path, &tu);
+    let function = |name: &str| -> &Entry {
+        header_file
+            .entries
+            .iter()
+            .find(|entry| matches!(entry, Entry::FunctionDecl { name: n, .. } if n == name))
+            .unwrap_or_else(|| panic!("no function {} in {:?}", name, header_file.entries))
+    };
+
+    let Entry::FunctionDecl {
+        is_variadic,
+        format_attributes,
+        storage_class,
+        calling_convention,
+        visibility,
+        ..
+    } = function("foo_log")
+    else {
+        unreachable!()
+    };
+    assert!(is_variadic);
+    assert_eq!(
+        format_attributes,
+        &[FormatAttribute {
+            archetype: "printf".to_string(),
+            format_index: 1,
+            first_argument: 2
+        }]
+    );
+    assert_eq!(storage_class, &None);
+    assert_eq!(calling_convention, &Some(CallingConvention::C));
+    assert_eq!(visibility, &Some(Visibility::Default));
+
+    assert!(matches!(
+        function("foo_min"),
+        Entry::FunctionDecl {
+            storage_class: Some(StorageClass::Static),
+            is_inline: true,
+            ..
+        }
+    ));
+    assert!(matches!(
+        function("foo_abort"),
+        Entry::FunctionDecl {
+            storage_class: Some(StorageClass::Extern),
+            is_noreturn: true,
+            ..
+        }
+    ));
+    assert!(matches!(
+        function("foo_exit"),
+        Entry::FunctionDecl {
+            is_noreturn: true,
+            ..
+        }
+    ));
+    assert!(matches!(
+        function("foo_private"),
+        Entry::FunctionDecl {
+            visibility: Some(Visibility::Hidden),
+            ..
+        }
+    ));
+    assert!(matches!(
+        function("foo_swift"),
+        Entry::FunctionDecl {
+            calling_convention: Some(CallingConvention::Swift),
+            ..
+        }
+    ));
+    assert!(matches!(
+        function("foo_create"),
+        Entry::FunctionDecl {
+            return_ownership: Some(ReturnOwnership::Retained),
+            warn_unused_result: true,
+            ..
+        }
+    ));
+    let Entry::FunctionDecl { arguments, .. } = function("foo_release") else {
+        unreachable!()
+    };
+    assert!(arguments[0].is_cf_consumed);
+    let Entry::FunctionDecl { nonnull, .. } = function("foo_copy") else {
+        unreachable!()
+    };
+    assert_eq!(nonnull, &Some(vec![1, 2]));
+    let Entry::FunctionDecl { nonnull, .. } = function("foo_fill") else {
+        unreachable!()
+    };
+    assert_eq!(nonnull, &Some(vec![]));
+
+    let variables: Vec<(&str, Option<StorageClass>, bool)> = header_file
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::VarDecl {
+                name,
+                storage_class,
+                is_thread_local,
+                ..
+            } => Some((name.as_str(), *storage_class, *is_thread_local)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        variables,
+        [
+            ("foo_count", Some(StorageClass::Static), false),
+            ("foo_error", Some(StorageClass::Extern), true),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,298 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    headerfiletree::HeaderFile,
+    interface::{ContainerKind, InterfaceIndex, ResolvedMember},
+};
+
+fn ty(name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "ObjC",
+        "name": name,
+        "clang_kind": "ObjCObjectPointer",
+        "nullability": null,
+        "objc_encoding": "@",
+        "objc_type_arguments": [],
+        "is_const": false
+    })
+}
+
+fn method(name: &str, optional: bool) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "arguments": [],
+        "result_type": ty("id"),
+        "optional": optional,
+        "platform_availability": null,
+        "availability": "Available"
+    })
+}
+
+fn property(name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "objc_type": ty("NSString *"),
+        "optional": false,
+        "attributes": null,
+        "platform_availability": null,
+        "availability": "Available"
+    })
+}
+
+fn container(
+    kind: &str,
+    name: serde_json::Value,
+    properties: Vec<serde_json::Value>,
+    instance_methods: Vec<serde_json::Value>,
+    extra: serde_json::Value,
+) -> serde_json::Value {
+    let mut entry = serde_json::json!({
+        "kind": kind,
+        "name": name,
+        "properties": properties,
+        "instance_methods": instance_methods,
+        "class_methods": [],
+        "platform_availability": null,
+        "availability": "Available"
+    });
+    entry
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    entry
+}
+
+fn interface(
+    name: &str,
+    superclass: &str,
+    protocols: &[&str],
+    instance_methods: Vec<serde_json::Value>,
+) -> serde_json::Value {
+    container(
+        "ObjCInterfaceDecl",
+        name.into(),
+        vec![property("name")],
+        instance_methods,
+        serde_json::json!({
+            "template_args": [],
+            "superclass": superclass,
+            "protocols": protocols
+        }),
+    )
+}
+
+fn header_files() -> Vec<HeaderFile> {
+    let header_file = |path: &str, entries: Vec<serde_json::Value>| -> HeaderFile {
+        serde_json::from_value(serde_json::json!({ "path": path, "entries": entries })).unwrap()
+    };
+    vec![
+        header_file(
+            "/F/NSObject.h",
+            vec![
+                container(
+                    "ObjCProtocolDecl",
+                    "NSObject".into(),
+                    vec![],
+                    vec![
+                        method("description", false),
+                        method("hash", false),
+                        method("debugDescription", true),
+                    ],
+                    serde_json::json!({}),
+                ),
+                interface("NSObject", "", &["NSObject"], vec![method("init", false)]),
+            ],
+        ),
+        header_file(
+            "/F/Foo.h",
+            vec![interface(
+                "Foo",
+                "NSObject",
+                &["NSCopying"],
+                vec![method("description", false), method("bar", false)],
+            )],
+        ),
+        header_file(
+            "/F/Foo+Extras.h",
+            vec![
+                container(
+                    "ObjCProtocolDecl",
+                    "FooDelegate".into(),
+                    vec![],
+                    vec![method("fooDidFinish:", true)],
+                    serde_json::json!({ "protocols": ["NSObject"] }),
+                ),
+                container(
+                    "ObjCCategoryDecl",
+                    "Extras".into(),
+                    vec![],
+                    vec![method("baz", false)],
+                    serde_json::json!({ "class_name": "Foo", "protocols": ["FooDelegate"] }),
+                ),
+                container(
+                    "ObjCCategoryDecl",
+                    serde_json::Value::Null,
+                    vec![property("name")],
+                    vec![],
+                    serde_json::json!({ "class_name": "Foo" }),
+                ),
+            ],
+        ),
+    ]
+}
+
+fn origins<T>(members: &[ResolvedMember<T>], name: impl Fn(&T) -> &str) -> Vec<(String, String)> {
+    members
+        .iter()
+        .map(|member| {
+            (
+                name(&member.decl).to_string(),
+                member.origin.container.clone(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_resolve_interface() {
+    let header_files = header_files();
+    let index = InterfaceIndex::new(&header_files);
+    assert_eq!(index.class_names(), ["Foo", "NSObject"]);
+    assert!(index.resolve("NSString").is_none());
+
+    let foo = index.resolve("Foo").unwrap();
+    assert_eq!(foo.superclasses, ["NSObject"]);
+    assert_eq!(foo.protocols, ["NSCopying", "FooDelegate", "NSObject"]);
+    assert_eq!(foo.categories, ["Foo (Extras)", "Foo ()"]);
+    assert_eq!(foo.unresolved, ["<NSCopying>"]);
+
+    let pair = |name: &str, container: &str| (name.to_string(), container.to_string());
+    assert_eq!(
+        origins(&foo.instance_methods, |method| &method.name),
+        [
+            pair("description", "Foo"),
+            pair("bar", "Foo"),
+            pair("baz", "Foo (Extras)"),
+            pair("fooDidFinish:", "<FooDelegate>"),
+            pair("hash", "<NSObject>"),
+            pair("debugDescription", "<NSObject>"),
+            pair("init", "NSObject"),
+        ]
+    );
+    let description = &foo.instance_methods[0];
+    assert_eq!(description.overrides.len(), 1);
+    assert_eq!(description.overrides[0].container, "<NSObject>");
+    // adopted through the category of Foo
+    assert_eq!(foo.instance_methods[4].origin.class_name, "Foo");
+    assert_eq!(foo.instance_methods[4].origin.kind, ContainerKind::Protocol);
+    assert!(foo.instance_methods[5].decl.optional);
+
+    let name = &foo.properties[0];
+    assert_eq!(name.origin.kind, ContainerKind::Interface);
+    let overrides: Vec<&str> = name
+        .overrides
+        .iter()
+        .map(|origin| origin.container.as_str())
+        .collect();
+    assert_eq!(overrides, ["Foo ()", "NSObject"]);
+    assert_eq!(foo.properties.len(), 1);
+}
+
+#[test]
+fn test_resolved_interface_text() {
+    let header_files = header_files();
+    let text = InterfaceIndex::new(&header_files)
+        .resolve("Foo")
+        .unwrap()
+        .to_text();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines[..4],
+        [
+            "@interface Foo : NSObject <NSCopying, FooDelegate, NSObject> // /F/Foo.h",
+            "// superclasses: NSObject",
+            "// categories: Foo (Extras), Foo ()",
+            "// unresolved: <NSCopying>",
+        ]
+    );
+    for expected in [
+        "@property NSString * name; // Foo, /F/Foo.h, overrides Foo (), NSObject",
+        "- (id)baz; // Foo (Extras), /F/Foo+Extras.h",
+        "@optional - (id)fooDidFinish:; // <FooDelegate>, /F/Foo+Extras.h",
+        "- (id)description; // Foo, /F/Foo.h, overrides <NSObject>",
+    ] {
+        assert!(
+            lines.contains(&expected),
+            "missing {:?} in\n{}",
+            expected,
+            text
+        );
+    }
+    assert_eq!(lines.last(), Some(&"@end"));
+}
+
+#[test]
+fn test_resolve_superclass_cycle() {
+    let header_files: Vec<HeaderFile> = vec![serde_json::from_value(serde_json::json!({
+        "path": "/F/Cycle.h",
+        "entries": [
+            interface("A", "B", &[], vec![]),
+            interface("B", "A", &[], vec![]),
+            interface("C", "Missing", &[], vec![]),
+        ]
+    }))
+    .unwrap()];
+    let index = InterfaceIndex::new(&header_files);
+    let a = index.resolve("A").unwrap();
+    assert_eq!(a.superclasses, ["B"]);
+    assert!(a.unresolved.is_empty());
+    let c = index.resolve("C").unwrap();
+    assert_eq!(c.superclasses, ["Missing"]);
+    assert_eq!(c.unresolved, ["Missing"]);
+}
+
+#[test]
+fn test_resolve_parsed_interface() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>
+{
+    let parser = parser_with(Lang::ObjC, None)?;
+    let source = r#"
+@protocol Base
+- (void)base;
+@end
+@protocol Delegate <Base>
+@optional
+- (void)didFinish;
+@end
+@interface Root
++ (instancetype)new;
+@end
+@interface Foo : Root
+- (void)foo;
+@end
+@interface Foo (Extras) <Delegate>
+- (void)extra;
+@end
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let header_file = HeaderFile::from_path(&path, &tu);
+    let header_files = vec![header_file];
+    let foo = InterfaceIndex::new(&header_files)
+        .resolve("Foo")
+        .expect("Foo is declared");
+    assert_eq!(foo.superclasses, ["Root"]);
+    assert_eq!(foo.protocols, ["Delegate", "Base"]);
+    assert!(foo.unresolved.is_empty());
+    assert_eq!(
+        origins(&foo.instance_methods, |method| &method.name),
+        [
+            ("foo".to_string(), "Foo".to_string()),
+            ("extra".to_string(), "Foo (Extras)".to_string()),
+            ("didFinish".to_string(), "<Delegate>".to_string()),
+            ("base".to_string(), "<Base>".to_string()),
+        ]
+    );
+    assert_eq!(foo.class_methods[0].origin.container, "Root");
+    Ok(())
+}
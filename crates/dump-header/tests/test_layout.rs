@@ -0,0 +1,98 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    entity::Entry,
+    headerfiletree::HeaderFile,
+    typ::{FieldLayout, RecordLayout, Typ},
+};
+
+type FieldLayouts = Vec<(String, Option<FieldLayout>)>;
+
+fn field_layouts(fields: &[Entry]) -> FieldLayouts {
+    fields
+        .iter()
+        .filter_map(|field| match field {
+            Entry::FieldDecl { name, layout, .. } => Some((name.clone(), layout.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn field_layout(bit_offset: usize, bitfield_width: Option<usize>) -> Option<FieldLayout> {
+    Some(FieldLayout {
+        bit_offset,
+        bitfield_width,
+        packed: false,
+        aligned: None,
+    })
+}
+
+#[test]
+fn test_record_layouts() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, Some("x86_64-apple-macos10.15"))?;
+    let source = r#"
+struct pair { char tag; long value; };
+struct __attribute__((packed)) packed_pair { char tag; long value; };
+struct __attribute__((aligned(32))) aligned_pair { char tag; };
+struct flags { unsigned int a : 3; unsigned int b : 5; char c; };
+union number { int i; double d; };
+struct incomplete;
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let header_file = HeaderFile::from_path(&path, &tu);
+    let layouts: Vec<(Option<RecordLayout>, FieldLayouts)> = header_file
+        .entries
+        .iter()
+        .map(|entry| match entry {
+            Entry::StructDecl { layout, fields, .. } | Entry::UnionDecl { layout, fields, .. } => {
+                (layout.clone(), field_layouts(fields))
+            }
+            _ => panic!("not a record: {:?}", entry),
+        })
+        .collect();
+    let record = |size, align, packed, aligned| {
+        Some(RecordLayout {
+            size,
+            align,
+            packed,
+            aligned,
+        })
+    };
+
+    assert_eq!(layouts[0].0, record(16, 8, false, None));
+    assert_eq!(
+        layouts[0].1,
+        [
+            ("tag".to_string(), field_layout(0, None)),
+            ("value".to_string(), field_layout(64, None)),
+        ]
+    );
+    assert_eq!(layouts[1].0, record(9, 1, true, None));
+    assert_eq!(layouts[1].1[1].1.as_ref().map(|l| l.bit_offset), Some(8));
+    assert_eq!(layouts[2].0, record(32, 32, false, Some(32)));
+    assert_eq!(layouts[3].0, record(4, 4, false, None));
+    assert_eq!(
+        layouts[3].1,
+        [
+            ("a".to_string(), field_layout(0, Some(3))),
+            ("b".to_string(), field_layout(3, Some(5))),
+            ("c".to_string(), field_layout(8, None)),
+        ]
+    );
+    assert_eq!(layouts[4].0, record(8, 8, false, None));
+    assert_eq!(layouts[5].0, None);
+
+    // the record types carry the same layouts
+    let Entry::StructDecl {
+        ty: Typ::StructRecord { layout, fields, .. },
+        ..
+    } = &header_file.entries[3]
+    else {
+        panic!("not a struct: {:?}", header_file.entries[3]);
+    };
+    assert_eq!(layout, &layouts[3].0);
+    assert_eq!(fields[1].layout, field_layout(3, Some(5)));
+    Ok(())
+}
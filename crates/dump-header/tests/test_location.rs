@@ -0,0 +1,64 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    entity::{ConvertOptions, Entry, Extent, Location, Position},
+    headerfiletree::HeaderFile,
+};
+
+#[test]
+fn test_source_locations() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, None)?;
+    let source = r#"
+struct point {
+    int x;
+    int y;
+};
+
+enum color {
+    RED,
+    GREEN,
+};
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let options = ConvertOptions {
+        locations: true,
+        location_root: path.parent().map(|dir| dir.to_path_buf()),
+        ..Default::default()
+    };
+    let header_file = HeaderFile::from_path_with_options(&path, &tu, &options);
+    let entries = &header_file.entries;
+    let file_name = PathBuf::from(path.file_name().unwrap());
+
+    assert_eq!(
+        entries[0].location(),
+        Some(&Location {
+            file: file_name.clone(),
+            line: 2,
+            column: 8,
+            extent: Some(Extent {
+                start: Position { line: 2, column: 1 },
+                end: Position { line: 5, column: 2 },
+            }),
+        })
+    );
+    let Entry::StructDecl { fields, .. } = &entries[0] else {
+        panic!("expected a struct: {:?}", entries[0]);
+    };
+    let y = fields[1].location().expect("location of y");
+    assert_eq!((y.line, y.column), (4, 9));
+
+    let Entry::EnumDecl { decls, .. } = &entries[1] else {
+        panic!("expected an enum: {:?}", entries[1]);
+    };
+    let green = decls[1].location.as_ref().expect("location of GREEN");
+    assert_eq!(green.file, file_name);
+    assert_eq!((green.line, green.column), (9, 5));
+
+    let header_file = HeaderFile::from_path(&path, &tu);
+    assert!(header_file.entries.iter().all(|e| e.location().is_none()));
+    Ok(())
+}
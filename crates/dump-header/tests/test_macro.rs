@@ -0,0 +1,81 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    entity::{Entry, InitValue},
+    headerfiletree::HeaderFile,
+};
+
+#[test]
+fn test_macro_definitions() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, None)?;
+    let source = r#"
+#define kFooMax 42
+#define kFooMask (1u << 31)
+#define kFooScale 0.5
+#define kFooName "foo"
+#define kFooTwice (kFooMax * 2)
+#define FOO_MIN(a, b) ((a) < (b) ? (a) : (b))
+#define FOO_EMPTY
+#define FOO_TYPE int
+"#;
+    // More non-expression macros than clang's default error limit of 20.
+    let source = (0..25).fold(source.to_string(), |source, i| {
+        source + &format!("#define FOO_EXPORT_{} extern\n", i)
+    }) + "#define kAfter 1\n";
+    let (tu, path) = parser.parse_content(&source)?;
+    let mut header_file = HeaderFile::from_path(&path, &tu);
+    header_file.evaluate_macros(&parser)?;
+    let macros: Vec<_> = header_file
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::MacroDefinition {
+                name,
+                parameters,
+                tokens,
+                value,
+                ..
+            } => Some((name.as_str(), parameters, tokens.as_str(), value)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(macros.len(), 34);
+
+    assert!(matches!(
+        macros[0],
+        ("kFooMax", None, "42", Some(InitValue::Int(42)))
+    ));
+    assert!(matches!(
+        macros[1],
+        (
+            "kFooMask",
+            None,
+            "(1u << 31)",
+            Some(InitValue::UInt(0x8000_0000))
+        )
+    ));
+    assert!(
+        matches!(macros[2], ("kFooScale", None, "0.5", Some(InitValue::Float(f))) if *f == 0.5)
+    );
+    assert!(
+        matches!(macros[3], ("kFooName", None, "\"foo\"", Some(InitValue::String(s))) if s == "foo")
+    );
+    assert!(matches!(
+        macros[4],
+        ("kFooTwice", None, _, Some(InitValue::Int(84)))
+    ));
+    assert!(matches!(
+        macros[5],
+        ("FOO_MIN", Some(parameters), "((a) < (b) ? (a) : (b))", None) if parameters == &["a", "b"]
+    ));
+    assert!(matches!(macros[6], ("FOO_EMPTY", None, "", None)));
+    assert!(matches!(macros[7], ("FOO_TYPE", None, "int", None)));
+    assert!(matches!(macros[8], ("FOO_EXPORT_0", None, "extern", None)));
+    assert!(matches!(
+        macros[33],
+        ("kAfter", None, "1", Some(InitValue::Int(1)))
+    ));
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use dump_header::entity::MethodFamily;
+
+#[test]
+fn test_method_family() {
+    let families: Vec<(&str, Option<MethodFamily>)> = [
+        "init",
+        "initWithFoo:bar:",
+        "_initWithFoo:",
+        "initialize",
+        "alloc",
+        "allocWithZone:",
+        "copy",
+        "copyright",
+        "mutableCopyWithZone:",
+        "new",
+        "newFoo",
+        "news",
+        "description",
+    ]
+    .into_iter()
+    .map(|selector| (selector, MethodFamily::from_selector(selector)))
+    .collect();
+    assert_eq!(
+        families,
+        [
+            ("init", Some(MethodFamily::Init)),
+            ("initWithFoo:bar:", Some(MethodFamily::Init)),
+            ("_initWithFoo:", Some(MethodFamily::Init)),
+            ("initialize", None),
+            ("alloc", Some(MethodFamily::Alloc)),
+            ("allocWithZone:", Some(MethodFamily::Alloc)),
+            ("copy", Some(MethodFamily::Copy)),
+            ("copyright", None),
+            ("mutableCopyWithZone:", Some(MethodFamily::MutableCopy)),
+            ("new", Some(MethodFamily::New)),
+            ("newFoo", Some(MethodFamily::New)),
+            ("news", None),
+            ("description", None),
+        ]
+    );
+    assert_eq!(MethodFamily::from_attribute("none"), None);
+    assert_eq!(
+        MethodFamily::from_attribute("init"),
+        Some(MethodFamily::Init)
+    );
+}
@@ -0,0 +1,234 @@
+use dump_header::{
+    cli::{AvailabilityFilter, Format},
+    diff::ItemKind,
+    format::to_vec,
+    framework::Framework,
+    headerfiletree::HeaderFile,
+    query::{load_header_files, regex, Query},
+};
+use temp_dir::TempDir;
+
+fn ty(name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "OtherType",
+        "name": name,
+        "clang_kind": "Int",
+        "nullability": null,
+        "objc_encoding": "i",
+        "is_const": false
+    })
+}
+
+fn string_ref() -> serde_json::Value {
+    serde_json::json!({
+        "kind": "Typedef",
+        "name": "CFStringRef",
+        "clang_kind": "Typedef",
+        "nullability": null,
+        "objc_encoding": "^{__CFString=}",
+        "is_const": false,
+        "typedef_name": "CFStringRef",
+        "header": null,
+        "underlying_type": {
+            "kind": "Pointer",
+            "name": "const struct __CFString *",
+            "clang_kind": "Pointer",
+            "nullability": null,
+            "objc_encoding": "^{__CFString=}",
+            "pointee_type": ty("const struct __CFString"),
+            "is_const": false
+        }
+    })
+}
+
+fn function(name: &str, argument: serde_json::Value, deprecated: bool) -> serde_json::Value {
+    let deprecated = deprecated.then(|| serde_json::json!({ "x": 10, "y": 8, "z": null }));
+    serde_json::json!({
+        "kind": "FunctionDecl",
+        "name": name,
+        "ty": ty("int (int)"),
+        "arguments": [{ "name": "s", "objc_type": argument }],
+        "result_type": ty("int"),
+        "platform_availability": [{
+            "platform": "macos",
+            "introduced": { "x": 10, "y": 0, "z": null },
+            "deprecated": deprecated,
+            "obsoleted": null,
+            "unavailable": false,
+            "message": null
+        }],
+        "availability": "Available"
+    })
+}
+
+fn interface() -> serde_json::Value {
+    serde_json::json!({
+        "kind": "ObjCInterfaceDecl",
+        "name": "NSURLSession",
+        "template_args": [],
+        "superclass": "NSObject",
+        "protocols": [],
+        "properties": [],
+        "instance_methods": [{
+            "name": "dataTaskWithURL:",
+            "selector_pieces": ["dataTaskWithURL"],
+            "arguments": [{ "name": "url", "objc_type": ty("NSURL *") }],
+            "result_type": ty("NSURLSessionDataTask *"),
+            "optional": false,
+            "platform_availability": null,
+            "availability": "Available"
+        }],
+        "class_methods": [{
+            "name": "sharedSession",
+            "arguments": [],
+            "result_type": ty("NSURLSession *"),
+            "optional": false,
+            "platform_availability": null,
+            "availability": "Available"
+        }],
+        "platform_availability": null,
+        "availability": "Available"
+    })
+}
+
+fn header_files() -> Vec<HeaderFile> {
+    let header_file = |path: &str, entries: Vec<serde_json::Value>| -> HeaderFile {
+        serde_json::from_value(serde_json::json!({ "path": path, "entries": entries })).unwrap()
+    };
+    vec![
+        header_file(
+            "/F/CFString.h",
+            vec![
+                function("CFStringGetLength", string_ref(), false),
+                function("CFStringGetIntValue", string_ref(), true),
+                function("abs", ty("int"), false),
+            ],
+        ),
+        header_file("/F/NSURLSession.h", vec![interface()]),
+    ]
+}
+
+fn names(query: &Query) -> Vec<String> {
+    query
+        .run(&header_files())
+        .into_iter()
+        .map(|query_match| query_match.name)
+        .collect()
+}
+
+#[test]
+fn test_query_filters() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(names(&Query::default()).len(), 6);
+
+    let functions_taking_strings = Query {
+        kinds: vec![ItemKind::Function],
+        mentions: Some("CFStringRef".to_string()),
+        ..Query::default()
+    };
+    assert_eq!(
+        names(&functions_taking_strings),
+        ["CFStringGetLength", "CFStringGetIntValue"]
+    );
+
+    let instance_methods = Query {
+        name: Some(regex("^NSURLSession -")?),
+        ..Query::default()
+    };
+    assert_eq!(names(&instance_methods), ["NSURLSession -dataTaskWithURL:"]);
+
+    let deprecated = Query {
+        availability: Some(AvailabilityFilter::Deprecated),
+        platform: Some("macos".to_string()),
+        ..Query::default()
+    };
+    assert_eq!(names(&deprecated), ["CFStringGetIntValue"]);
+    let deprecated_on_ios = Query {
+        platform: Some("ios".to_string()),
+        ..deprecated
+    };
+    assert!(names(&deprecated_on_ios).is_empty());
+
+    let in_header = Query {
+        header: Some(regex("NSURLSession\\.h$")?),
+        kinds: vec![ItemKind::Class],
+        ..Query::default()
+    };
+    assert_eq!(names(&in_header), ["NSURLSession"]);
+
+    assert!(regex("(").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_query_signatures() {
+    let lines: Vec<String> = Query::default()
+        .run(&header_files())
+        .iter()
+        .map(|query_match| query_match.to_line())
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "/F/CFString.h: int CFStringGetLength(CFStringRef s)",
+            "/F/CFString.h: int CFStringGetIntValue(CFStringRef s)",
+            "/F/CFString.h: int abs(int s)",
+            "/F/NSURLSession.h: @interface NSURLSession : NSObject",
+            "/F/NSURLSession.h: NSURLSession -(NSURLSessionDataTask *)dataTaskWithURL:(NSURL *)url",
+            "/F/NSURLSession.h: NSURLSession +(NSURLSession *)sharedSession",
+        ]
+    );
+}
+
+#[test]
+fn test_load_header_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = TempDir::new()?;
+    let header_files = header_files();
+    let list = dir.child("list.cbor");
+    std::fs::write(&list, to_vec(&header_files, Format::Cbor)?)?;
+    assert_eq!(load_header_files(&list, Format::Cbor)?.len(), 2);
+    let single = dir.child("single.json");
+    std::fs::write(&single, to_vec(&header_files[1], Format::Json)?)?;
+    let loaded = load_header_files(&single, Format::Json)?;
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].path, header_files[1].path);
+    Ok(())
+}
+
+#[test]
+fn test_load_framework() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = TempDir::new()?;
+    let framework: Framework = serde_json::from_value(serde_json::json!({
+        "name": "F",
+        "root_header": "/F/NSURLSession.h",
+        "header_file_tree": {
+            "root_path": "/F/NSURLSession.h",
+            "path_entry_hash_map": header_files()
+                .into_iter()
+                .map(|header_file| (header_file.path.display().to_string(), header_file))
+                .collect::<std::collections::BTreeMap<_, _>>()
+        }
+    }))?;
+    let paths = |header_files: Vec<HeaderFile>| -> Vec<String> {
+        header_files
+            .iter()
+            .map(|header_file| header_file.path.display().to_string())
+            .collect()
+    };
+    for format in [
+        Format::Json,
+        Format::Ndjson,
+        Format::MessagePack,
+        Format::Cbor,
+    ] {
+        let single = dir.child("single");
+        std::fs::write(&single, to_vec(&framework, format)?)?;
+        assert_eq!(
+            paths(load_header_files(&single, format)?),
+            ["/F/CFString.h", "/F/NSURLSession.h"]
+        );
+        let list = dir.child("list");
+        std::fs::write(&list, to_vec(&vec![framework.clone(); 2], format)?)?;
+        assert_eq!(load_header_files(&list, format)?.len(), 4);
+    }
+    Ok(())
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::{Format, Lang},
+    entity::{ConversionReport, Location, SkippedEntity},
+    format,
+    headerfiletree::HeaderFile,
+};
+
+#[test]
+fn test_ignored_kinds() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, None)?;
+    let source = r#"
+_Static_assert(sizeof(int) == 4, "int");
+_Static_assert(sizeof(long) >= 4, "long");
+int f(void);
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let header_file = HeaderFile::from_path(&path, &tu);
+    assert_eq!(header_file.entries.len(), 1);
+    assert!(header_file.report.skipped.is_empty());
+    assert_eq!(
+        header_file.report.ignored_kinds.get("StaticAssert"),
+        Some(&2)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_report_round_trip() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let mut header_file = HeaderFile::new("/t.h".into(), vec![]);
+    header_file.report.skipped.push(SkippedEntity {
+        kind: "VarDecl".to_string(),
+        name: Some("x".to_string()),
+        reason: "no type".to_string(),
+        location: Some(Location {
+            file: "/t.h".into(),
+            line: 3,
+            column: 5,
+            extent: None,
+        }),
+    });
+    header_file
+        .report
+        .ignored_kinds
+        .insert("StaticAssert".to_string(), 2);
+    assert_eq!(
+        header_file.report.skipped[0].to_string(),
+        "/t.h:3:5: skipped VarDecl x: no type"
+    );
+
+    for format in [Format::Json, Format::Ndjson] {
+        let read: HeaderFile = format::from_slice(&format::to_vec(&header_file, format)?, format)?;
+        assert_eq!(read.report, header_file.report);
+    }
+
+    let mut report = ConversionReport::default();
+    assert!(report.is_empty());
+    report.merge(header_file.report.clone());
+    report.merge(header_file.report);
+    assert_eq!(report.skipped.len(), 2);
+    assert_eq!(report.ignored_kinds.get("StaticAssert"), Some(&4));
+    assert!(!serde_json::to_string(&HeaderFile::new("/t.h".into(), vec![]))?.contains("report"));
+    Ok(())
+}
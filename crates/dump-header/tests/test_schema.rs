@@ -0,0 +1,76 @@
+use dump_header::{
+    cli::SchemaKind, format::FORMAT_VERSION, headerfiletree::HeaderFile, schema::schema,
+};
+
+fn enum_values(schema: &serde_json::Value, name: &str) -> Vec<String> {
+    let definition = &schema["definitions"][name];
+    let values = match definition["enum"].as_array() {
+        Some(values) => values.clone(),
+        // variants with a doc comment are listed separately
+        None => definition["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|variant| variant["enum"].as_array().cloned().unwrap_or_default())
+            .collect(),
+    };
+    values
+        .iter()
+        .map(|value| value.as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_header_file_schema() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = serde_json::to_value(schema(SchemaKind::HeaderFile))?;
+    assert_eq!(
+        schema["description"],
+        format!("dump-header format version {}", FORMAT_VERSION)
+    );
+    assert!(schema["required"]
+        .as_array()
+        .unwrap()
+        .contains(&"entries".into()));
+
+    let header_file = serde_json::to_value(HeaderFile::new("t.h".into(), vec![]))?;
+    assert_eq!(header_file["format_version"], FORMAT_VERSION);
+    header_file.as_object().unwrap().keys().for_each(|key| {
+        assert!(
+            schema["properties"].get(key).is_some(),
+            "{} is missing in the schema",
+            key
+        );
+    });
+
+    // the remote enums of clang
+    assert!(enum_values(&schema, "TypeKind").contains(&"ObjCObjectPointer".to_string()));
+    assert_eq!(
+        enum_values(&schema, "Availability"),
+        vec!["Available", "Deprecated", "Inaccessible", "Unavailable"]
+    );
+    assert!(
+        schema["definitions"]["Entry"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .len()
+            > 10
+    );
+    Ok(())
+}
+
+#[test]
+fn test_other_schemas() -> Result<(), Box<dyn std::error::Error>> {
+    for kind in [
+        SchemaKind::Framework,
+        SchemaKind::Ast,
+        SchemaKind::Deps,
+        SchemaKind::Query,
+        SchemaKind::Interface,
+    ] {
+        let schema = serde_json::to_value(schema(kind))?;
+        assert!(schema["$schema"].is_string(), "{:?}", kind);
+    }
+    let framework = serde_json::to_value(schema(SchemaKind::Framework))?;
+    assert!(framework["definitions"]["HeaderFile"].is_object());
+    Ok(())
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{
+    cli::Lang,
+    entity::{Entry, SwiftAttribute},
+    headerfiletree::HeaderFile,
+};
+
+fn attribute(tokens: &[&str]) -> Option<(String, Vec<String>)> {
+    let tokens: Vec<String> = tokens.iter().map(|token| token.to_string()).collect();
+    SwiftAttribute::from_tokens(&tokens).map(|attribute| (attribute.name, attribute.arguments))
+}
+
+fn expected(name: &str, arguments: &[&str]) -> Option<(String, Vec<String>)> {
+    Some((
+        name.to_string(),
+        arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect(),
+    ))
+}
+
+#[test]
+fn test_swift_attribute_from_tokens() {
+    assert_eq!(
+        attribute(&["NS_SWIFT_SENDABLE"]),
+        expected("swift_attr", &["@Sendable"])
+    );
+    assert_eq!(
+        attribute(&["__swift_attr__", "(", "\"@UIActor\"", ")"]),
+        expected("swift_attr", &["@UIActor"])
+    );
+    assert_eq!(
+        attribute(&["NS_SWIFT_NAME", "(", "init", "(", "value", ":", ")", ")"]),
+        expected("swift_name", &["init(value:)"])
+    );
+    assert_eq!(
+        attribute(&["swift_name", "(", "\"init(value:)\"", ")"]),
+        expected("swift_name", &["init(value:)"])
+    );
+    assert_eq!(
+        attribute(&["NS_REFINED_FOR_SWIFT"]),
+        expected("swift_private", &[])
+    );
+    assert_eq!(
+        attribute(&["NS_SWIFT_ASYNC", "(", "2", ")"]),
+        expected("swift_async", &["not_swift_private", "2"])
+    );
+    assert_eq!(
+        attribute(&["swift_async", "(", "none", ")"]),
+        expected("swift_async", &["none"])
+    );
+    assert_eq!(
+        attribute(&["NS_SWIFT_UNAVAILABLE", "(", "\"Use bar\"", ")"]),
+        expected("availability", &["swift", "unavailable", "message=Use bar"])
+    );
+    assert_eq!(
+        attribute(&[
+            "availability",
+            "(",
+            "swift",
+            ",",
+            "unavailable",
+            ",",
+            "message",
+            "=",
+            "\"Use bar\"",
+            ")"
+        ]),
+        expected("availability", &["swift", "unavailable", "message=Use bar"])
+    );
+    assert_eq!(
+        attribute(&["NS_SWIFT_UNAVAILABLE_FROM_ASYNC", "(", "\"Use bar\"", ")"]),
+        expected("NS_SWIFT_UNAVAILABLE_FROM_ASYNC", &["Use bar"])
+    );
+    assert_eq!(
+        attribute(&[
+            "availability",
+            "(",
+            "macos",
+            ",",
+            "introduced",
+            "=",
+            "10.15",
+            ")"
+        ]),
+        None
+    );
+    assert_eq!(attribute(&["objc_direct"]), None);
+}
+
+#[test]
+fn test_swift_attributes() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::ObjC, None)?;
+    let source = r#"
+#define NS_SWIFT_UI_ACTOR __attribute__((swift_attr("@UIActor")))
+#define NS_SWIFT_ASYNC(_index) __attribute__((swift_async(not_swift_private, _index)))
+__attribute__((objc_root_class))
+__attribute__((swift_attr("@Sendable")))
+@interface Foo
+- (void)loadWithCompletionHandler:(void (^)(int))handler NS_SWIFT_ASYNC(1);
+@property int value __attribute__((swift_private));
+@end
+NS_SWIFT_UI_ACTOR
+@interface Foo (Bar)
+@end
+NS_SWIFT_UI_ACTOR
+void foo_update(void) __attribute__((availability(swift, unavailable, message="Use Foo")));
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let header_file = HeaderFile::from_path(&path, &tu);
+    let names = |attributes: &[SwiftAttribute]| -> Vec<(String, Vec<String>)> {
+        attributes
+            .iter()
+            .map(|attribute| (attribute.name.clone(), attribute.arguments.clone()))
+            .collect()
+    };
+    // after the macro definitions
+    let entries: Vec<&Entry> = header_file
+        .entries
+        .iter()
+        .filter(|entry| !matches!(entry, Entry::MacroDefinition { .. }))
+        .collect();
+    let Entry::ObjCInterfaceDecl {
+        swift_attributes,
+        instance_methods,
+        properties,
+        ..
+    } = entries[0]
+    else {
+        panic!("not an interface");
+    };
+    assert_eq!(
+        names(swift_attributes),
+        [expected("swift_attr", &["@Sendable"]).unwrap()]
+    );
+    assert_eq!(
+        names(&instance_methods[0].swift_attributes),
+        [expected("swift_async", &["not_swift_private", "1"]).unwrap()]
+    );
+    assert_eq!(
+        names(&properties[0].swift_attributes),
+        [expected("swift_private", &[]).unwrap()]
+    );
+    let Entry::ObjCCategoryDecl {
+        swift_attributes, ..
+    } = entries[1]
+    else {
+        panic!("not a category");
+    };
+    assert_eq!(
+        names(swift_attributes),
+        [expected("swift_attr", &["@UIActor"]).unwrap()]
+    );
+    let Entry::FunctionDecl {
+        swift_attributes, ..
+    } = entries[2]
+    else {
+        panic!("not a function");
+    };
+    assert_eq!(
+        names(swift_attributes),
+        [
+            expected("swift_attr", &["@UIActor"]).unwrap(),
+            expected("availability", &["swift", "unavailable", "message=Use Foo"]).unwrap(),
+        ]
+    );
+    Ok(())
+}
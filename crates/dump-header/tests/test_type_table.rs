@@ -0,0 +1,127 @@
+use dump_header::{codegen::generate_rust, headerfiletree::HeaderFile, typ::Typ};
+
+fn int() -> serde_json::Value {
+    serde_json::json!({
+        "kind": "OtherType",
+        "name": "int",
+        "clang_kind": "Int",
+        "nullability": null,
+        "objc_encoding": "i",
+        "is_const": false
+    })
+}
+
+fn pointer(pointee: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "Pointer",
+        "name": "struct node *",
+        "clang_kind": "Pointer",
+        "nullability": null,
+        "objc_encoding": "^v",
+        "pointee_type": pointee,
+        "is_const": false
+    })
+}
+
+fn record(ident: &str, fields: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "StructRecord",
+        "name": format!("struct {}", ident),
+        "ident": ident,
+        "fields": fields,
+        "clang_kind": "Record",
+        "nullability": null,
+        "objc_encoding": null,
+        "is_const": false
+    })
+}
+
+fn point() -> serde_json::Value {
+    record(
+        "point",
+        serde_json::json!([
+            { "name": "x", "is_anonymous": false, "ty": int() },
+            { "name": "y", "is_anonymous": false, "ty": int() }
+        ]),
+    )
+}
+
+// struct node { struct node *next; struct point value; };
+fn node() -> serde_json::Value {
+    record(
+        "node",
+        serde_json::json!([
+            {
+                "name": "next",
+                "is_anonymous": false,
+                "ty": pointer(serde_json::json!({ "kind": "RecordIdent", "ident": "node" }))
+            },
+            { "name": "value", "is_anonymous": false, "ty": point() }
+        ]),
+    )
+}
+
+fn function(name: &str, argument: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "FunctionDecl",
+        "name": name,
+        "ty": int(),
+        "arguments": [{ "name": "p", "objc_type": argument }],
+        "result_type": int(),
+        "platform_availability": null,
+        "availability": "Available"
+    })
+}
+
+fn header_file() -> HeaderFile {
+    serde_json::from_value(serde_json::json!({
+        "path": "/t.h",
+        "entries": [
+            function("first", pointer(node())),
+            function("second", point()),
+            function("third", pointer(node()))
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_type_table() {
+    let original = header_file();
+    let mut header_file = original.clone();
+    header_file.use_type_table();
+
+    let ids: Vec<&String> = header_file.types.iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, ["struct node", "struct point"]);
+    // the node record refers to the point record of the table
+    let Some(Typ::StructRecord { fields, .. }) = header_file.types.get("struct node") else {
+        panic!("struct node is not a record");
+    };
+    assert!(matches!(&fields[1].ty, Typ::TypeRef { id } if id == "struct point"));
+
+    let json = serde_json::to_value(&header_file).unwrap();
+    assert_eq!(
+        json["entries"][1]["arguments"][0]["objc_type"],
+        serde_json::json!({ "kind": "TypeRef", "id": "struct point" })
+    );
+    assert!(
+        serde_json::to_string(&header_file).unwrap().len()
+            < serde_json::to_string(&original).unwrap().len()
+    );
+
+    let mut read: HeaderFile = serde_json::from_value(json).unwrap();
+    read.inline_types();
+    assert!(read.types.is_empty());
+    assert_eq!(
+        serde_json::to_value(&read).unwrap(),
+        serde_json::to_value(&original).unwrap()
+    );
+}
+
+#[test]
+fn test_type_table_codegen() {
+    let original = header_file();
+    let mut header_file = original.clone();
+    header_file.use_type_table();
+    assert_eq!(generate_rust(&header_file), generate_rust(&original));
+}
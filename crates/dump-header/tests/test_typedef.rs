@@ -0,0 +1,56 @@
+mod common;
+
+use common::parser_with;
+use dump_header::{cli::Lang, entity::Entry, headerfiletree::HeaderFile, typ::Typ};
+
+#[test]
+fn test_typedef_chains() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let parser = parser_with(Lang::C, None)?;
+    let source = r#"
+typedef long NSInteger;
+typedef NSInteger FooIndex;
+FooIndex foo_index(const NSInteger *values, int count);
+"#;
+    let (tu, path) = parser.parse_content(source)?;
+    let header_file = HeaderFile::from_path(&path, &tu);
+    let Some(Entry::FunctionDecl {
+        arguments,
+        result_type,
+        ..
+    }) = header_file.entries.get(2)
+    else {
+        panic!("no function in {:?}", header_file.entries);
+    };
+
+    let Typ::Typedef {
+        typedef_name,
+        header,
+        underlying_type,
+        ..
+    } = result_type
+    else {
+        panic!("not a typedef: {:?}", result_type);
+    };
+    assert_eq!(typedef_name, "FooIndex");
+    assert_eq!(header.as_ref(), Some(&path));
+    assert!(
+        matches!(underlying_type.as_ref(), Typ::Typedef { typedef_name, .. } if typedef_name == "NSInteger")
+    );
+    assert!(matches!(
+        result_type.canonical(),
+        Typ::OtherType {
+            clang_kind: clang::TypeKind::Long,
+            ..
+        }
+    ));
+
+    let Typ::Pointer { pointee_type, .. } = &arguments[0].objc_type else {
+        panic!("not a pointer: {:?}", arguments[0].objc_type);
+    };
+    assert!(matches!(
+        pointee_type.as_ref(),
+        Typ::Typedef { typedef_name, is_const: true, .. } if typedef_name == "NSInteger"
+    ));
+    assert!(matches!(arguments[1].objc_type, Typ::OtherType { .. }));
+    Ok(())
+}